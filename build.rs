@@ -1,13 +1,39 @@
-use std::path::Path;
+use std::env;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 fn main() {
+    // Set when OneLOop is linked; without it the crate uses stand-ins, see `ffi`
+    println!("cargo::rustc-check-cfg=cfg(oneloop_linked)");
+    // Any rerun-if line replaces cargo's default of rerunning on every change
+    // in the package, so list everything the outcome depends on
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=oneloop/libavh_olo.a");
+    println!("cargo:rerun-if-env-changed=OLO_NO_ONELOOP");
+
+    if env::var_os("CARGO_FEATURE_ONI").is_some() {
+        build_oni();
+    }
+
     // Directory where the OneLOop source lives
     let lib_dir = Path::new("oneloop");
     let lib_file = lib_dir.join("libavh_olo.a");
 
+    // With the pure-rust feature, OLO_NO_ONELOOP=1 builds without OneLOop even
+    // if it is available, e.g. to test that configuration
+    if env::var_os("CARGO_FEATURE_PURE_RUST").is_some() && env::var_os("OLO_NO_ONELOOP").is_some_and(|v| v == "1") {
+        println!("cargo:warning=OLO_NO_ONELOOP is set, linking without OneLOop");
+        return;
+    }
+
     // Only build if the library doesn't already exist
     if !lib_file.exists() {
+        // With the pure-rust feature, OneLOop is optional
+        if env::var_os("CARGO_FEATURE_PURE_RUST").is_some() && !tools_available() {
+            println!("cargo:warning=libavh_olo.a not found and cannot be built, linking without OneLOop");
+            return;
+        }
+
         println!("cargo:warning=libavh_olo.a not found, building via create.py...");
 
         // Check that python3 is available
@@ -28,7 +54,7 @@ fn main() {
         // Run the Python build script
         let status = Command::new("python3")
             .arg("create.py")
-            .current_dir(&lib_dir)
+            .current_dir(lib_dir)
             .status()
             .expect("Failed to run create.py inside oneloop/");
 
@@ -47,5 +73,65 @@ fn main() {
 
     // Link standard Fortran libraries
     println!("cargo:rustc-link-lib=gfortran");
+    println!("cargo:rustc-cfg=oneloop_linked");
     //println!("cargo:rustc-link-lib=quadmath");
 }
+
+/// Whether python3, gfortran and m4 are all available.
+fn tools_available() -> bool {
+    ["python3", "gfortran", "m4"]
+        .iter()
+        .all(|tool| Command::new(tool).arg("--version").status().is_ok())
+}
+
+/// Compiles the vendored ONI into `libavh_oni.a` in the output directory, as
+/// its makefile does. `cuhre` is provided by the `oni` module.
+fn build_oni() {
+    let src_dir = Path::new("oneloop/ONI/src");
+    // The whole directory, as the sources include the headers next to them
+    println!("cargo:rerun-if-changed={}", src_dir.display());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap()).join("oni");
+    std::fs::create_dir_all(&out_dir).expect("Failed to create the ONI build directory");
+
+    if Command::new("gfortran").arg("--version").status().is_err() {
+        panic!("gfortran not found! You need gfortran to build ONI.");
+    }
+
+    let mut objects = Vec::new();
+    let mut sources: Vec<PathBuf> = std::fs::read_dir(src_dir)
+        .expect("oneloop/ONI/src not found")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|e| e == "f"))
+        .collect();
+    sources.sort();
+    for source in sources {
+        let object = out_dir.join(source.file_stem().unwrap()).with_extension("o");
+        let status = Command::new("gfortran")
+            .args(["-O", "-fPIC", "-c"])
+            .arg("-I")
+            .arg(src_dir)
+            .arg(&source)
+            .arg("-o")
+            .arg(&object)
+            .status()
+            .expect("Failed to run gfortran");
+        if !status.success() {
+            panic!("gfortran failed to compile {}", source.display());
+        }
+        objects.push(object);
+    }
+
+    let status = Command::new("ar")
+        .arg("crs")
+        .arg(out_dir.join("libavh_oni.a"))
+        .args(&objects)
+        .status()
+        .expect("Failed to run ar");
+    if !status.success() {
+        panic!("ar failed, could not create libavh_oni.a");
+    }
+
+    println!("cargo:rustc-link-search=native={}", out_dir.display());
+    println!("cargo:rustc-link-lib=static=avh_oni");
+    println!("cargo:rustc-link-lib=gfortran");
+}
@@ -9,6 +9,7 @@ Features:
 - Compute 1-point (tadpole), 2-point (bubble), 3-point (triangle), and 4-point (box) functions.
 - Access Laurent coefficients: `epsilon_0` (finite), `epsilon_minus_1`, `epsilon_minus_2`.
 - Conversion to standard Feynman-diagram normalization via `TO_FEYNMAN`.
+- Scans over linear or logarithmic ranges of the inputs, exported as CSV or JSON lines.
 - Configure logging, renormalization scale, and on-shell thresholds.
 
 Example:
@@ -24,7 +25,7 @@ r2 = oneloop_bridge.two_point(1.0, 0.5, 0.2)
 print("Finite term in Feynman normalization:", r2.epsilon_0 * oneloop_bridge.TO_FEYNMAN)
 """
 
-from typing import Optional
+from typing import List, Optional, Tuple, Union
 
 TO_FEYNMAN: float
 """
@@ -54,6 +55,22 @@ class PyOLOResult:
     
     def __repr__(self) -> str: ...
 
+    def to_mathematica(self) -> str:
+        """The result as `c0 + c1/eps + c2/eps^2` in Mathematica syntax, with `*^` exponents."""
+        ...
+
+    def to_form(self) -> str:
+        """The result as a FORM expression in `ep` with exact rational coefficients."""
+        ...
+
+    def to_latex(self) -> str:
+        """The result as a LaTeX formula in `\\epsilon`, without surrounding `$`."""
+        ...
+
+    def _repr_latex_(self) -> str:
+        """The LaTeX formula in `$`, shown by Jupyter."""
+        ...
+
 def one_point(m: float) -> PyOLOResult:
     """
     Computes the 1-point scalar (tadpole) function.
@@ -114,6 +131,89 @@ def four_point(
     """
     ...
 
+class PyScan:
+    """
+    The samples of a scan, with the last range varying fastest.
+
+    Attributes:
+        values: the values of the scanned inputs of each sample, in the order of the ranges
+        results: the value of the function at each sample
+    """
+    @property
+    def values(self) -> List[List[float]]: ...
+
+    @property
+    def results(self) -> List[PyOLOResult]: ...
+
+    def to_csv(self) -> str:
+        """
+        All inputs and Laurent coefficients as CSV with a header line, e.g.
+        `p,m1_re,m1_im,m2_re,m2_im,epsilon_0_re,epsilon_0_im,...`.
+        """
+        ...
+
+    def to_json_lines(self) -> str:
+        """
+        All inputs and Laurent coefficients as one JSON object per line, with
+        complex numbers as `[re, im]`.
+        """
+        ...
+
+    def __len__(self) -> int: ...
+
+    def __repr__(self) -> str: ...
+
+def scan(
+    function: str,
+    momenta: List[float],
+    masses: List[complex],
+    ranges: List[Union[Tuple[str, float, float, int], Tuple[str, float, float, int, str]]],
+) -> PyScan:
+    """
+    Evaluates a scalar function over a range of one input or a grid in several.
+
+    Args:
+        function: "A0", "B0", "C0" or "D0"
+        momenta: the squared momenta of the base point, as for the function
+        masses: the squared masses of the base point
+        ranges: `(name, start, end, points)` or `(name, start, end, points, "log")`,
+            where `name` is an argument name such as "p", "p12" or "m2". A
+            scanned mass keeps its imaginary part.
+
+    Returns:
+        PyScan with the samples, evaluated at the current renormalization scale.
+
+    Raises:
+        ValueError: for an unknown function or input, wrong numbers of momenta
+            or masses, an input scanned twice, an empty range or the bounds of
+            a logarithmic range not being of the same sign.
+
+    Example:
+        s = scan("B0", [0.0], [1.0, 1.0], [("p", 3.0, 5.0, 21)])
+        open("threshold.csv", "w").write(s.to_csv())
+    """
+    ...
+
+def evaluate(expression: str, bindings: dict[str, complex] = {}) -> PyOLOResult:
+    """
+    Evaluates a linear combination of integrals written as in LoopTools or FeynCalc.
+
+    Args:
+        expression: e.g. "2*B0[s, 0, mt^2] - C0[0, 0, s, mt^2, mt^2, mt^2]/s", with
+            the functions A0, B0, B1, B00, DB0, C0 and D0, numbers such as 1.5e-3
+            or 1.5*^-3, `I`, `Pi`, `+ - * / ^` and parentheses
+        bindings: the values of the symbols of the expression, e.g. {"s": 1e4, "mt": 173}
+
+    Returns:
+        PyOLOResult in the normalization of OneLOop, at the current renormalization scale.
+
+    Raises:
+        ValueError: for a syntax error, an unknown function, a wrong number of
+            arguments, an unbound symbol, a complex squared momentum or an
+            expression that is not linear in the integrals.
+    """
+    ...
+
 def set_renormalization_scale(mu: float) -> None:
     """
     Sets the renormalization scale μ for OneLOop calculations.
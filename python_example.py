@@ -50,3 +50,14 @@ print("4-point:", r4)
 print("3-point ε⁰:", r3.epsilon_0)
 print("3-point ε⁻¹:", r3.epsilon_minus_1)
 print("3-point ε⁻²:", r3.epsilon_minus_2)
+
+# Scan B0 across the threshold at p = 4 and the box in s and t
+s = oneloop_bridge.scan("B0", [0.0], [1.0, 1.0], [("p", 3.0, 5.0, 21)])
+print(s.to_csv())
+s = oneloop_bridge.scan("D0", [0, 0, 0, 0, 1, -1], [1, 1, 1, 1], [("p12", 1, 10, 10, "log"), ("p23", -1, -10, 10)])
+print(len(s), "box samples")
+
+# Evaluate an amplitude exported from FeynCalc
+r = oneloop_bridge.evaluate("2*B0[s, 0, mt^2] - C0[0, 0, s, mt^2, mt^2, mt^2]/s", {"s": 1e4, "mt": 173.0})
+print("amplitude:", r)
+print(r.to_mathematica())
@@ -0,0 +1,314 @@
+//! Pluggable evaluation of the scalar 1- to 4-point functions.
+//!
+//! `one_point`, `two_point`, `three_point` and `four_point`, and everything
+//! built on them (`snap`, `pv`, `five_point`, `six_point`, ...), evaluate the
+//! integrals through a `ScalarIntegralBackend`, as does `pv` for the 2-point
+//! tensor coefficients. By default this is `Builtin`,
+//! which is OneLOop or, with the `pure-rust` feature, `Native`. `set_backend`
+//! installs a different one for the whole process, for instance a mock
+//! returning canned values or a decorator that logs or caches the calls of
+//! another backend, without changing any call site.
+//!
+//! `set_renormalization_scale` and `set_onshell_threshold` are forwarded to
+//! the current backend, and `set_backend` passes the current settings on to
+//! the new one.
+//!
+//! With the `oni` feature, `Oni` evaluates all integrals with the vendored
+//! ONI library, which reads these settings at every call.
+use crate::complex_momentum::{self, Sheet, TwoPointCoefficients};
+use crate::{OLOResult, ffi, native, onshell_threshold, renormalization_scale};
+use num_complex::Complex64;
+use std::sync::{Arc, RwLock};
+
+/// Evaluates the scalar integrals in the conventions of the functions of the
+/// same name at the crate root.
+pub trait ScalarIntegralBackend: Send + Sync {
+    /// The 1-point function `A0(m)`.
+    fn one_point(&self, m: Complex64) -> OLOResult;
+
+    /// The 2-point function `B0(p, m1, m2)`.
+    fn two_point(&self, p: f64, m1: Complex64, m2: Complex64) -> OLOResult;
+
+    /// The 2-point tensor coefficients `B0`, `B1`, `B00` and `B11` at
+    /// `(p, m1, m2)`. Uses `complex_momentum::two_point_coefficients` by
+    /// default, so that a backend need only provide the scalar integrals.
+    fn two_point_coefficients(&self, p: f64, m1: Complex64, m2: Complex64) -> TwoPointCoefficients {
+        complex_momentum::two_point_coefficients(p.into(), m1, m2, Sheet::First)
+    }
+
+    /// The 3-point function `C0(p1, p2, p3, m1, m2, m3)`.
+    fn three_point(&self, p1: f64, p2: f64, p3: f64, m1: Complex64, m2: Complex64, m3: Complex64) -> OLOResult;
+
+    /// The 4-point function `D0(p1, p2, p3, p4, p12, p23, m1, m2, m3, m4)`.
+    #[allow(clippy::too_many_arguments)]
+    fn four_point(
+        &self,
+        p1: f64,
+        p2: f64,
+        p3: f64,
+        p4: f64,
+        p12: f64,
+        p23: f64,
+        m1: Complex64,
+        m2: Complex64,
+        m3: Complex64,
+        m4: Complex64,
+    ) -> OLOResult;
+
+    /// Called by `set_renormalization_scale`. Does nothing by default.
+    fn set_renormalization_scale(&self, _mu: f64) {}
+
+    /// Called by `set_onshell_threshold`. Does nothing by default.
+    fn set_onshell_threshold(&self, _threshold: f64) {}
+}
+
+/// The OneLOop Fortran library.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OneLoop;
+
+impl ScalarIntegralBackend for OneLoop {
+    fn one_point(&self, m: Complex64) -> OLOResult {
+        let mut r = OLOResult::default(); // stack-allocated, aligned
+        unsafe { ffi::__avh_olo_dp_MOD_a0_c(r.as_mut_ptr(), &m) }
+        r
+    }
+
+    fn two_point(&self, p: f64, m1: Complex64, m2: Complex64) -> OLOResult {
+        let mut r = OLOResult::default();
+        unsafe { ffi::__avh_olo_dp_MOD_b0cc(r.as_mut_ptr(), &p.into(), &m1, &m2) }
+        r
+    }
+
+    fn two_point_coefficients(&self, p: f64, m1: Complex64, m2: Complex64) -> TwoPointCoefficients {
+        let mut b11 = OLOResult::default();
+        let mut b00 = OLOResult::default();
+        let mut b1 = OLOResult::default();
+        let mut b0 = OLOResult::default();
+        unsafe {
+            ffi::__avh_olo_dp_MOD_b11cc(
+                b11.as_mut_ptr(),
+                b00.as_mut_ptr(),
+                b1.as_mut_ptr(),
+                b0.as_mut_ptr(),
+                &p.into(),
+                &m1,
+                &m2,
+            )
+        }
+        TwoPointCoefficients { b0, b1, b00, b11 }
+    }
+
+    fn three_point(&self, p1: f64, p2: f64, p3: f64, m1: Complex64, m2: Complex64, m3: Complex64) -> OLOResult {
+        let mut r = OLOResult::default();
+        unsafe { ffi::__avh_olo_dp_MOD_c0cc(r.as_mut_ptr(), &p1.into(), &p2.into(), &p3.into(), &m1, &m2, &m3) }
+        r
+    }
+
+    fn four_point(
+        &self,
+        p1: f64,
+        p2: f64,
+        p3: f64,
+        p4: f64,
+        p12: f64,
+        p23: f64,
+        m1: Complex64,
+        m2: Complex64,
+        m3: Complex64,
+        m4: Complex64,
+    ) -> OLOResult {
+        let mut r = OLOResult::default();
+        unsafe {
+            ffi::__avh_olo_dp_MOD_d0cc(
+                r.as_mut_ptr(),
+                &p1.into(),
+                &p2.into(),
+                &p3.into(),
+                &p4.into(),
+                &p12.into(),
+                &p23.into(),
+                &m1,
+                &m2,
+                &m3,
+                &m4,
+            )
+        }
+        r
+    }
+
+    fn set_renormalization_scale(&self, mu: f64) {
+        unsafe { ffi::__avh_olo_dp_MOD_olo_scale(&mu) }
+    }
+
+    fn set_onshell_threshold(&self, threshold: f64) {
+        unsafe { ffi::__avh_olo_dp_MOD_olo_onshell(&threshold) }
+    }
+}
+
+/// The functions of the `native` module, with the 2-point tensor coefficients
+/// of `complex_momentum`, falling back to `OneLoop` for the divergent
+/// triangles and for the boxes. Without OneLOop, see `pure-rust`,
+/// the fallbacks panic.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Native;
+
+impl ScalarIntegralBackend for Native {
+    fn one_point(&self, m: Complex64) -> OLOResult {
+        native::one_point(m)
+    }
+
+    fn two_point(&self, p: f64, m1: Complex64, m2: Complex64) -> OLOResult {
+        native::two_point(p, m1, m2)
+    }
+
+    fn three_point(&self, p1: f64, p2: f64, p3: f64, m1: Complex64, m2: Complex64, m3: Complex64) -> OLOResult {
+        native::three_point(p1, p2, p3, m1, m2, m3).unwrap_or_else(|| OneLoop.three_point(p1, p2, p3, m1, m2, m3))
+    }
+
+    fn four_point(
+        &self,
+        p1: f64,
+        p2: f64,
+        p3: f64,
+        p4: f64,
+        p12: f64,
+        p23: f64,
+        m1: Complex64,
+        m2: Complex64,
+        m3: Complex64,
+        m4: Complex64,
+    ) -> OLOResult {
+        OneLoop.four_point(p1, p2, p3, p4, p12, p23, m1, m2, m3, m4)
+    }
+
+    fn set_renormalization_scale(&self, mu: f64) {
+        OneLoop.set_renormalization_scale(mu)
+    }
+
+    fn set_onshell_threshold(&self, threshold: f64) {
+        OneLoop.set_onshell_threshold(threshold)
+    }
+}
+
+/// The functions of the `oni` module, with the 2-point tensor coefficients of
+/// `complex_momentum`.
+#[cfg(feature = "oni")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Oni;
+
+#[cfg(feature = "oni")]
+impl ScalarIntegralBackend for Oni {
+    fn one_point(&self, m: Complex64) -> OLOResult {
+        crate::oni::one_point(m)
+    }
+
+    fn two_point(&self, p: f64, m1: Complex64, m2: Complex64) -> OLOResult {
+        crate::oni::two_point(p, m1, m2)
+    }
+
+    fn three_point(&self, p1: f64, p2: f64, p3: f64, m1: Complex64, m2: Complex64, m3: Complex64) -> OLOResult {
+        crate::oni::three_point(p1, p2, p3, m1, m2, m3)
+    }
+
+    fn four_point(
+        &self,
+        p1: f64,
+        p2: f64,
+        p3: f64,
+        p4: f64,
+        p12: f64,
+        p23: f64,
+        m1: Complex64,
+        m2: Complex64,
+        m3: Complex64,
+        m4: Complex64,
+    ) -> OLOResult {
+        crate::oni::four_point(p1, p2, p3, p4, p12, p23, m1, m2, m3, m4)
+    }
+}
+
+/// The backend used when none is set: `Native` with the `pure-rust` feature,
+/// `OneLoop` otherwise.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Builtin;
+
+impl ScalarIntegralBackend for Builtin {
+    fn one_point(&self, m: Complex64) -> OLOResult {
+        if cfg!(feature = "pure-rust") { Native.one_point(m) } else { OneLoop.one_point(m) }
+    }
+
+    fn two_point(&self, p: f64, m1: Complex64, m2: Complex64) -> OLOResult {
+        if cfg!(feature = "pure-rust") { Native.two_point(p, m1, m2) } else { OneLoop.two_point(p, m1, m2) }
+    }
+
+    fn two_point_coefficients(&self, p: f64, m1: Complex64, m2: Complex64) -> TwoPointCoefficients {
+        if cfg!(feature = "pure-rust") {
+            Native.two_point_coefficients(p, m1, m2)
+        } else {
+            OneLoop.two_point_coefficients(p, m1, m2)
+        }
+    }
+
+    fn three_point(&self, p1: f64, p2: f64, p3: f64, m1: Complex64, m2: Complex64, m3: Complex64) -> OLOResult {
+        if cfg!(feature = "pure-rust") {
+            Native.three_point(p1, p2, p3, m1, m2, m3)
+        } else {
+            OneLoop.three_point(p1, p2, p3, m1, m2, m3)
+        }
+    }
+
+    fn four_point(
+        &self,
+        p1: f64,
+        p2: f64,
+        p3: f64,
+        p4: f64,
+        p12: f64,
+        p23: f64,
+        m1: Complex64,
+        m2: Complex64,
+        m3: Complex64,
+        m4: Complex64,
+    ) -> OLOResult {
+        OneLoop.four_point(p1, p2, p3, p4, p12, p23, m1, m2, m3, m4)
+    }
+
+    fn set_renormalization_scale(&self, mu: f64) {
+        OneLoop.set_renormalization_scale(mu)
+    }
+
+    fn set_onshell_threshold(&self, threshold: f64) {
+        OneLoop.set_onshell_threshold(threshold)
+    }
+}
+
+/// The backend installed with `set_backend`, if any.
+static BACKEND: RwLock<Option<Arc<dyn ScalarIntegralBackend>>> = RwLock::new(None);
+
+/// Evaluates all scalar integrals with `backend` from now on, after passing
+/// the current renormalization scale and on-shell threshold on to it.
+pub fn set_backend(backend: impl ScalarIntegralBackend + 'static) {
+    apply_settings(&backend);
+    *BACKEND.write().unwrap() = Some(Arc::new(backend));
+}
+
+/// Goes back to the `Builtin` backend.
+pub fn reset_backend() {
+    *BACKEND.write().unwrap() = None;
+    apply_settings(&Builtin);
+}
+
+fn apply_settings(backend: &dyn ScalarIntegralBackend) {
+    backend.set_renormalization_scale(renormalization_scale());
+    if let Some(threshold) = onshell_threshold() {
+        backend.set_onshell_threshold(threshold);
+    }
+}
+
+/// The backend installed with `set_backend`, or `None` for `Builtin`.
+///
+/// The lock is released before the backend is used, so that a backend may
+/// call the functions of this crate itself.
+pub(crate) fn installed() -> Option<Arc<dyn ScalarIntegralBackend>> {
+    BACKEND.read().unwrap().clone()
+}
@@ -0,0 +1,331 @@
+//! Evaluates a scalar one-loop integral from the command line.
+//!
+//! ```text
+//! olo B0 91.19^2 80.4^2 0
+//! olo D0 0 0 0 0 2.1 -1.9 0 0 0 0 --scale 1.1 --json
+//! olo B0 0 80.4^2 0 --scan p=6000:7000:101 --csv
+//! olo --expression '2*B0[s, 0, mt^2] - C0[0, 0, s, mt^2, mt^2, mt^2]/s' --set s=1e4 --set mt=173
+//! olo < oneloop/example/input
+//! ```
+use num_complex::Complex64;
+use oneloop_bridge::expression::Expression;
+use oneloop_bridge::input::{self, Point};
+use oneloop_bridge::scan::{self, Range, Sample, Scan};
+use oneloop_bridge::{OLOResult, TO_FEYNMAN, renormalization_scale, set_onshell_threshold, set_renormalization_scale};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+usage: olo <A0|B0|C0|D0> <invariants...> [options]
+       olo --expression EXPR [--set NAME=VALUE...] [options]
+       olo [options] < input
+
+Evaluates a scalar one-loop integral. The invariants are the squared momenta
+followed by the squared masses, as for one_point to four_point:
+
+  A0 m
+  B0 p m1 m2
+  C0 p1 p2 p3 m1 m2 m3
+  D0 p1 p2 p3 p4 p12 p23 m1 m2 m3 m4
+
+Numbers may be written as 1.5, 91.19^2, 1.5e3 or 1.5d3, and masses may be
+complex, as 6400-170i or (6400,-170). Without a function, the points are read
+from stdin in the format of OneLOop's example input, each at its own scale.
+
+An expression is a linear combination of A0, B0, B1, B00, DB0, C0 and D0 as
+written by LoopTools or FeynCalc, e.g. 2*B0[s, 0, mt^2] - C0[0, 0, s, mt^2,
+mt^2, mt^2]/s, with its symbols bound by --set.
+
+options:
+  --scale MU             renormalization scale (default 1, or that of the input)
+  --threshold T          on-shell threshold
+  --normalization NAME   oneloop (default) or feynman, i.e. times -1/(16π²)
+  --scan NAME=A:B:N[:log]
+                         vary the invariant NAME (p1, m2, ...) over N values
+                         from A to B, equally spaced or with equal ratios;
+                         may be given twice for a grid
+  --expression EXPR      evaluate the expression EXPR
+  --set NAME=VALUE       bind the symbol NAME of the expression to VALUE
+  --precision N          significant digits of the text output (default 16)
+  --json                 print JSON, one object per line
+  --csv                  print CSV with a header line
+  --help                 print this message
+";
+
+/// The command-line options.
+struct Options {
+    scale: Option<f64>,
+    threshold: Option<f64>,
+    feynman: bool,
+    scans: Vec<String>,
+    expression: Option<String>,
+    bindings: HashMap<String, Complex64>,
+    precision: usize,
+    format: Format,
+}
+
+#[derive(PartialEq)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1).collect()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("olo: {}", message);
+            eprintln!("Try 'olo --help' for more information.");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+    let mut options = Options {
+        scale: None,
+        threshold: None,
+        feynman: false,
+        scans: Vec::new(),
+        expression: None,
+        bindings: HashMap::new(),
+        precision: 16,
+        format: Format::Text,
+    };
+    let mut positional = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("missing value for {}", name));
+        match arg.as_str() {
+            "--help" | "-h" => {
+                print!("{}", USAGE);
+                return Ok(());
+            }
+            "--scale" => options.scale = Some(real(&value("--scale")?)?),
+            "--threshold" => options.threshold = Some(real(&value("--threshold")?)?),
+            "--normalization" => {
+                options.feynman = match value("--normalization")?.to_lowercase().as_str() {
+                    "oneloop" => false,
+                    "feynman" => true,
+                    other => return Err(format!("unknown normalization '{}'", other)),
+                }
+            }
+            "--scan" => options.scans.push(value("--scan")?),
+            "--expression" => options.expression = Some(value("--expression")?),
+            "--set" => {
+                let binding = value("--set")?;
+                let (name, number) =
+                    binding.split_once('=').ok_or(format!("invalid binding '{}', expected NAME=VALUE", binding))?;
+                options.bindings.insert(name.trim().to_string(), complex(number.trim())?);
+            }
+            "--precision" => {
+                let digits = value("--precision")?;
+                options.precision = match digits.parse() {
+                    Ok(n) if (1..=17).contains(&n) => n,
+                    _ => return Err(format!("invalid precision '{}', expected 1 to 17 digits", digits)),
+                };
+            }
+            "--json" | "--csv" if options.format != Format::Text => {
+                return Err("--json and --csv cannot be combined".to_string());
+            }
+            "--json" => options.format = Format::Json,
+            "--csv" => options.format = Format::Csv,
+            _ if arg.starts_with("--") => return Err(format!("unknown option '{}'", arg)),
+            _ => positional.push(arg),
+        }
+    }
+
+    if let Some(threshold) = options.threshold {
+        set_onshell_threshold(threshold);
+    }
+    if let Some(expression) = &options.expression {
+        if !positional.is_empty() || !options.scans.is_empty() || options.format == Format::Csv {
+            return Err("--expression cannot be combined with a function, --scan or --csv".to_string());
+        }
+        return evaluate(expression, &options);
+    }
+    if !options.bindings.is_empty() {
+        return Err("--set needs --expression".to_string());
+    }
+    let points = if positional.is_empty() {
+        if !options.scans.is_empty() || options.format == Format::Csv {
+            return Err(format!("{} needs a function", if options.scans.is_empty() { "--csv" } else { "--scan" }));
+        }
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text).map_err(|e| e.to_string())?;
+        input::parse(&text).map_err(|e| e.to_string())?
+    } else {
+        vec![point(&positional, options.scale.unwrap_or(1.0))?]
+    };
+    let mut stdout = std::io::stdout().lock();
+    for point in points {
+        let ranges = options.scans.iter().map(|s| range(s, &point)).collect::<Result<Vec<_>, _>>()?;
+        scan::validate(&point, &ranges).map_err(|e| e.to_string())?;
+        set_renormalization_scale(options.scale.unwrap_or(point.mu));
+        let mut scan = scan::scan(&point, &ranges);
+        if options.feynman {
+            for sample in &mut scan.samples {
+                sample.result = sample.result * TO_FEYNMAN;
+            }
+        }
+        let written = match options.format {
+            Format::Text => scan.samples.iter().try_for_each(|s| writeln!(stdout, "{}", text(&scan, s, &options))),
+            Format::Json => scan.write_json_lines(&mut stdout),
+            Format::Csv => scan.write_csv(&mut stdout),
+        };
+        // Stop quietly when the reader has gone, e.g. `olo < input | head`
+        if written.is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Builds a point from the function name and its invariants.
+fn point(args: &[String], mu: f64) -> Result<Point, String> {
+    let legs = match args[0].to_uppercase().as_str() {
+        "A0" => 1,
+        "B0" => 2,
+        "C0" => 3,
+        "D0" => 4,
+        _ => return Err(format!("unknown function '{}', expected A0, B0, C0 or D0", args[0])),
+    };
+    let momenta = [0, 1, 3, 6][legs - 1];
+    if args.len() - 1 != momenta + legs {
+        return Err(format!("{} takes {} invariants, got {}", args[0].to_uppercase(), momenta + legs, args.len() - 1));
+    }
+    Ok(Point {
+        legs,
+        mu,
+        momenta: args[1..=momenta].iter().map(|a| real(a)).collect::<Result<_, _>>()?,
+        masses: args[momenta + 1..].iter().map(|a| complex(a)).collect::<Result<_, _>>()?,
+        line: 0,
+    })
+}
+
+/// Parses a scan `NAME=A:B:N` or `NAME=A:B:N:log` of an invariant of `point`.
+fn range(spec: &str, point: &Point) -> Result<Range, String> {
+    let invalid = || format!("invalid scan '{}', expected NAME=A:B:N or NAME=A:B:N:log", spec);
+    let (name, bounds) = spec.split_once('=').ok_or_else(invalid)?;
+    let variable = scan::variable_by_name(point.legs, name)
+        .ok_or_else(|| format!("{} has no invariant '{}'", NAMES[point.legs - 1], name))?;
+    let fields: Vec<&str> = bounds.split(':').collect();
+    let (start, end, points) = match fields[..] {
+        [start, end, points] | [start, end, points, "log"] => (real(start)?, real(end)?, points),
+        _ => return Err(invalid()),
+    };
+    let points = points.parse().map_err(|_| invalid())?;
+    match fields.len() {
+        3 => Ok(Range::linear(variable, start, end, points)),
+        _ => Ok(Range::logarithmic(variable, start, end, points)),
+    }
+}
+
+/// Parses a real number, also as `x^2` or with a Fortran `d` exponent. As in
+/// expressions, a sign applies after the power, so `-3^2` is `-9`.
+fn real(arg: &str) -> Result<f64, String> {
+    let parse = |s: &str| s.trim().replace(['d', 'D'], "e").parse::<f64>().ok();
+    match arg.split_once('^') {
+        Some((base, exponent)) => {
+            let base = base.trim_start();
+            let (sign, base) = match base.strip_prefix('-') {
+                Some(base) => (-1.0, base),
+                None => (1.0, base.strip_prefix('+').unwrap_or(base)),
+            };
+            parse(base).zip(parse(exponent)).map(|(b, e)| sign * b.powf(e))
+        }
+        None => parse(arg),
+    }
+    .ok_or(format!("invalid number '{}'", arg))
+}
+
+/// Parses a complex number as `re`, `re+imi`, `imi` or `(re,im)`.
+fn complex(arg: &str) -> Result<Complex64, String> {
+    let invalid = || format!("invalid complex number '{}'", arg);
+    if let Some(inner) = arg.strip_prefix('(').and_then(|a| a.strip_suffix(')')) {
+        let (re, im) = inner.split_once(',').ok_or_else(invalid)?;
+        return Ok(Complex64::new(real(re)?, real(im)?));
+    }
+    let Some(body) = arg.strip_suffix('i') else {
+        return real(arg).map(Complex64::from);
+    };
+    // Split before the sign of the imaginary part, which is not that of an exponent
+    let split = body
+        .char_indices()
+        .skip(1)
+        .filter(|&(i, c)| (c == '+' || c == '-') && !matches!(body.as_bytes()[i - 1], b'e' | b'E' | b'd' | b'D'))
+        .map(|(i, _)| i)
+        .last();
+    let (re, im) = match split {
+        Some(i) => (real(&body[..i])?, &body[i..]),
+        None => (0.0, body),
+    };
+    let im = match im {
+        "" | "+" => 1.0,
+        "-" => -1.0,
+        im => real(im)?,
+    };
+    Ok(Complex64::new(re, im))
+}
+
+const NAMES: [&str; 4] = ["A0", "B0", "C0", "D0"];
+
+/// Evaluates and prints an expression.
+fn evaluate(text: &str, options: &Options) -> Result<(), String> {
+    let expression = Expression::parse(text).map_err(|e| e.to_string())?;
+    set_renormalization_scale(options.scale.unwrap_or(1.0));
+    let mut result = expression.evaluate(&options.bindings).map_err(|e| e.to_string())?;
+    if options.feynman {
+        result = result * TO_FEYNMAN;
+    }
+    let out = match options.format {
+        Format::Json => {
+            let number = |x: f64| if x.is_finite() { format!("{:e}", x) } else { "null".to_string() };
+            let complex = |z: Complex64| format!("[{}, {}]", number(z.re), number(z.im));
+            format!(
+                concat!(
+                    "{{\"expression\": \"{}\", \"scale\": {}, ",
+                    "\"epsilon_0\": {}, \"epsilon_minus_1\": {}, \"epsilon_minus_2\": {}}}"
+                ),
+                text.replace('\\', "\\\\").replace('"', "\\\""),
+                number(renormalization_scale()),
+                complex(result.epsilon_0()),
+                complex(result.epsilon_minus_1()),
+                complex(result.epsilon_minus_2())
+            )
+        }
+        _ => format!("{}\n{}", text, coefficients(&result, options.precision)),
+    };
+    // Ignore a reader that has gone, as for points
+    let _ = writeln!(std::io::stdout(), "{}", out);
+    Ok(())
+}
+
+fn text(scan: &Scan, sample: &Sample, options: &Options) -> String {
+    let args: Vec<String> = sample
+        .momenta
+        .iter()
+        .map(|x| x.to_string())
+        .chain(sample.masses.iter().map(|m| if m.im == 0.0 { m.re.to_string() } else { m.to_string() }))
+        .collect();
+    format!("{}({})\n{}", NAMES[scan.legs - 1], args.join(", "), coefficients(&sample.result, options.precision))
+}
+
+/// The coefficients of `result`, one per line, with `precision` significant
+/// digits.
+fn coefficients(result: &OLOResult, precision: usize) -> String {
+    let p = precision - 1;
+    let mut out = String::new();
+    let values = [result.epsilon_0(), result.epsilon_minus_1(), result.epsilon_minus_2()];
+    for (label, c) in ["ε⁰ ", "ε⁻¹", "ε⁻²"].iter().zip(values) {
+        // Adding zero turns -0 into 0
+        let (re, im) = (c.re + 0.0, c.im + 0.0);
+        let re = format!("{:.p$e}", re);
+        let sign = if im < 0.0 { '-' } else { '+' };
+        out += &format!("  {}: {:>w$} {} {:.p$e}i\n", label, re, sign, im.abs(), w = p + 8);
+    }
+    out.pop();
+    out
+}
@@ -0,0 +1,147 @@
+//! The 2-point function and its tensor coefficients at a complex external
+//! invariant `p²`, as needed for self-energies at a complex pole
+//! `p² = M² - iMΓ`.
+//!
+//! With `Δ(x) = x m2 + (1-x) m1 - x(1-x) p`, the coefficients follow from the
+//! moments `Iₙ = ∫₀¹ dx xⁿ ln(Δ(x)/μ²)`:
+//!
+//! ```text
+//! B0  = 1/ε - I0
+//! B1  = -1/(2ε) + I1
+//! B00 = (1/ε + 1)/2 ∫₀¹ dx Δ(x) - (p I2 + (m2 - m1 - p) I1 + m1 I0)/2
+//! B11 = 1/(3ε) - I2
+//! ```
+//!
+//! As in OneLOop, `ln Δ` is split at the roots `x₁, x₂` of `Δ` into
+//! `ln m1 + ln(1 - x/x₁) + ln(1 - x/x₂)`, and the moments are expressed by
+//! `qmplx::bnlog`. This is analytic in `p` except where a root crosses
+//! `(0, 1)`, which for real masses is the real axis above threshold, and it
+//! equals `two_point` at real `p`, where `p` is taken as `p + i0`.
+use crate::native::{EPSN, IEPS, log, solve_quadratic};
+use crate::qmplx::bnlog;
+use crate::{OLOResult, onshell_threshold, renormalization_scale};
+use num_complex::Complex64;
+use std::f64::consts::PI;
+
+/// A Riemann sheet of the 2-point functions in `p²`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sheet {
+    /// The physical sheet, cut where a root of `Δ` lies in `(0, 1)`.
+    First,
+    /// The sheet reached from the physical region `p² + i0` above threshold
+    /// by crossing the cut into the lower half plane, where resonance poles
+    /// lie. It differs from the first by the discontinuity `2πi √λ/p²` of
+    /// `B0`, with the principal square root of the Källén function `λ`.
+    Second,
+}
+
+/// The 2-point tensor coefficients, defined by `B^μ = p^μ B1` and
+/// `B^μν = g^μν B00 + p^μ p^ν B11` for the propagators `[q²-m1][(q+p)²-m2]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TwoPointCoefficients {
+    pub b0: OLOResult,
+    pub b1: OLOResult,
+    pub b00: OLOResult,
+    pub b11: OLOResult,
+}
+
+/// Computes the 2-point scalar function `B0` at complex `p` on `sheet`, see
+/// `two_point`.
+pub fn two_point(p: Complex64, m1: impl Into<Complex64>, m2: impl Into<Complex64>, sheet: Sheet) -> OLOResult {
+    two_point_coefficients(p, m1, m2, sheet).b0
+}
+
+/// Computes `B0`, `B1`, `B00` and `B11` at complex `p` on `sheet`.
+///
+/// # Arguments
+/// * `p`  - The squared momentum, on the side `p + i0` if real.
+/// * `m1` - The squared mass of the first propagator. Imaginary part should be non-positive.
+/// * `m2` - The squared mass of the second propagator. Imaginary part should be non-positive.
+/// * `sheet` - The Riemann sheet to evaluate on.
+pub fn two_point_coefficients(
+    p: Complex64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    sheet: Sheet,
+) -> TwoPointCoefficients {
+    let zero = Complex64::new(0.0, 0.0);
+    let (m1, m2): (Complex64, Complex64) = (m1.into(), m2.into());
+    // As OneLOop, flip the sign of a positive imaginary part of the masses
+    let mut m1 = Complex64::new(m1.re, -m1.im.abs());
+    let mut m2 = Complex64::new(m2.re, -m2.im.abs());
+    let mut p = p;
+    if let Some(threshold) = onshell_threshold() {
+        for x in [&mut p, &mut m1, &mut m2] {
+            if x.re.abs() + x.im.abs() < threshold {
+                *x = zero;
+            }
+        }
+    }
+    if p == zero && m1 == zero && m2 == zero {
+        let vanishing = OLOResult::default();
+        return TwoPointCoefficients { b0: vanishing, b1: vanishing, b00: vanishing, b11: vanishing };
+    }
+
+    let mu2 = renormalization_scale().powi(2);
+    // Put the smaller mass at x = 0, and map x → 1 - x back afterwards
+    let swap = m2.norm() < m1.norm();
+    let [mut i0, mut i1, mut i2] = if swap { moments(p, m2, m1, mu2) } else { moments(p, m1, m2, mu2) };
+    if swap {
+        (i1, i2) = (i0 - i1, i0 - 2.0 * i1 + i2);
+    }
+    if sheet == Sheet::Second && p != zero {
+        // On the second sheet ln Δ is lower by 2πi between the roots x₋ < x₊
+        let lambda = p * p + m1 * m1 + m2 * m2 - 2.0 * (p * m1 + p * m2 + m1 * m2);
+        let r = lambda.sqrt() / p;
+        let s = (p + m1 - m2) / p;
+        let (xp, xm) = ((s + r) / 2.0, (s - r) / 2.0);
+        let two_pi_i = Complex64::new(0.0, 2.0 * PI);
+        i0 -= two_pi_i * (xp - xm);
+        i1 -= two_pi_i * (xp * xp - xm * xm) / 2.0;
+        i2 -= two_pi_i * (xp.powi(3) - xm.powi(3)) / 3.0;
+    }
+
+    let integral = (m1 + m2) / 2.0 - p / 6.0;
+    let b00 = (integral - (p * i2 + (m2 - m1 - p) * i1 + m1 * i0)) / 2.0;
+    TwoPointCoefficients {
+        b0: OLOResult::from_values([-i0, Complex64::new(1.0, 0.0), zero]),
+        b1: OLOResult::from_values([i1, Complex64::new(-0.5, 0.0), zero]),
+        b00: OLOResult::from_values([b00, integral / 2.0, zero]),
+        b11: OLOResult::from_values([-i2, Complex64::new(1.0 / 3.0, 0.0), zero]),
+    }
+}
+
+/// `Iₙ = ∫₀¹ dx xⁿ ln(Δ(x)/μ²)` for `n = 0, 1, 2`, on the first sheet, where
+/// `|m1| ≤ |m2|`.
+fn moments(p: Complex64, m1: Complex64, m2: Complex64, mu2: f64) -> [Complex64; 3] {
+    let zero = Complex64::new(0.0, 0.0);
+    let am1 = m1.norm();
+    let am2 = m2.norm();
+    let moment = |n: usize, log: Complex64, roots: &[Complex64]| {
+        (log + roots.iter().map(|&x| bnlog(n, x)).sum::<Complex64>()) / (n + 1) as f64
+    };
+    if p == zero {
+        if (m1 - m2).norm() <= am2 * EPSN * 10.0 {
+            return [0, 1, 2].map(|n| moment(n, log(m2 / mu2), &[]));
+        }
+        if m1 == zero {
+            // Δ = m2 x, and ∫₀¹ dx xⁿ ln x = -1/(n+1)²
+            return [0, 1, 2].map(|n| moment(n, log(m2 / mu2) - 1.0 / (n + 1) as f64, &[]));
+        }
+        // Δ = m1 (1 - x/r)
+        let r = (m1 - IEPS * am1) / (m1 - m2);
+        return [0, 1, 2].map(|n| moment(n, log(m1 / mu2), &[r]));
+    }
+    if m1 == zero {
+        if (p - m2).norm() <= am2 * EPSN * 10.0 {
+            // Δ = m2 x², and ∫₀¹ dx xⁿ ln x² = -2/(n+1)²
+            return [0, 1, 2].map(|n| moment(n, log(m2 / mu2) - 2.0 / (n + 1) as f64, &[]));
+        }
+        // Δ = (m2 - p) x (1 - x/r)
+        let r = (p - m2 + IEPS * am2) / p;
+        return [0, 1, 2].map(|n| moment(n, log((m2 - p) / mu2) - 1.0 / (n + 1) as f64, &[r]));
+    }
+    // Δ = m1 (1 - x/x₁)(1 - x/x₂)
+    let (x1, x2) = solve_quadratic(p, m2 - m1 - p, m1 - IEPS * am1);
+    [0, 1, 2].map(|n| moment(n, log(m1 / mu2), &[x1, x2]))
+}
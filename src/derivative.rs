@@ -0,0 +1,213 @@
+//! Derivatives of the scalar functions with respect to a mass or invariant,
+//! e.g. `∂B0/∂p²` for wave-function and `∂B0/∂m²`, `∂C0/∂m²` for mass
+//! counterterm insertions.
+//!
+//! Variables are numbered as in `snap`, by their position among the momenta
+//! or masses of the corresponding scalar function, starting from zero. The
+//! derivative of `two_point` is computed analytically from its
+//! Feynman-parameter representation `B0 = 1/ε - ∫₀¹ dx ln(Δ(x)/μ²)`, unless
+//! an input vanishes or `p²` is at threshold. All other derivatives are
+//! Richardson-extrapolated central differences (Ridders' method) with an
+//! error estimate. Masses are stepped along the real axis, which is exact for
+//! the analytic dependence on a complex mass.
+//!
+//! Near a threshold or an IR-divergent configuration the derivative itself
+//! diverges, which shows up as a large `error`.
+use crate::native::{EPSN, IEPS, solve_quadratic};
+use crate::{OLOResult, onshell_threshold};
+use num_complex::Complex64;
+
+/// The initial step of the finite differences relative to the variable, or
+/// to the largest input if the variable vanishes.
+const INITIAL_STEP: f64 = 0.05;
+
+/// The factor by which the step shrinks between successive differences.
+const STEP_SHRINK: f64 = 1.4;
+
+/// The maximal number of finite differences.
+const MAX_STEPS: usize = 10;
+
+/// A variable of a scalar function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Variable {
+    /// The squared momentum with the given index.
+    Momentum(usize),
+    /// The squared mass with the given index.
+    Mass(usize),
+}
+
+/// How a derivative was obtained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// From a closed formula.
+    Analytic,
+    /// From Richardson-extrapolated finite differences.
+    Richardson,
+}
+
+/// A derivative of a scalar function.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Derivative {
+    /// The derivative of each coefficient of the Laurent expansion.
+    pub value: OLOResult,
+    /// The estimated absolute error, the largest over the coefficients. Zero
+    /// for analytic derivatives.
+    pub error: f64,
+    /// How the derivative was obtained.
+    pub method: Method,
+}
+
+/// Computes the derivative of `two_point` with respect to `variable`.
+///
+/// # Panics
+/// If `variable` is not `Momentum(0)`, `Mass(0)` or `Mass(1)`.
+pub fn two_point(p: f64, m1: impl Into<Complex64>, m2: impl Into<Complex64>, variable: Variable) -> Derivative {
+    let m = [m1.into(), m2.into()];
+    check(variable, 1, 2);
+    if let Some(value) = analytic_two_point(p, m[0], m[1], variable) {
+        return Derivative { value, error: 0.0, method: Method::Analytic };
+    }
+    richardson([p], m, variable, |[p], [m1, m2]| crate::two_point(p, m1, m2))
+}
+
+/// Computes the derivative of `three_point` with respect to `variable`.
+///
+/// # Panics
+/// If `variable` is not one of `Momentum(0..3)` or `Mass(0..3)`.
+pub fn three_point(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+    variable: Variable,
+) -> Derivative {
+    check(variable, 3, 3);
+    let m = [m1.into(), m2.into(), m3.into()];
+    richardson([p1, p2, p3], m, variable, |[p1, p2, p3], [m1, m2, m3]| {
+        crate::three_point(p1, p2, p3, m1, m2, m3)
+    })
+}
+
+/// Computes the derivative of `four_point` with respect to `variable`.
+///
+/// # Panics
+/// If `variable` is not one of `Momentum(0..6)` or `Mass(0..4)`.
+#[allow(clippy::too_many_arguments)]
+pub fn four_point(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    p4: f64,
+    p12: f64,
+    p23: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+    m4: impl Into<Complex64>,
+    variable: Variable,
+) -> Derivative {
+    check(variable, 6, 4);
+    let m = [m1.into(), m2.into(), m3.into(), m4.into()];
+    richardson([p1, p2, p3, p4, p12, p23], m, variable, |[p1, p2, p3, p4, p12, p23], [m1, m2, m3, m4]| {
+        crate::four_point(p1, p2, p3, p4, p12, p23, m1, m2, m3, m4)
+    })
+}
+
+fn check(variable: Variable, momenta: usize, masses: usize) {
+    match variable {
+        Variable::Momentum(i) => assert!(i < momenta, "momentum index {} out of range 0..{}", i, momenta),
+        Variable::Mass(i) => assert!(i < masses, "mass index {} out of range 0..{}", i, masses),
+    }
+}
+
+/// `-∫₀¹ dx ∂Δ/Δ` with `Δ(x) = p(x - x₁)(x - x₂)`, if `p`, the masses and
+/// `x₁ - x₂` are non-vanishing.
+fn analytic_two_point(p: f64, m1: Complex64, m2: Complex64, variable: Variable) -> Option<OLOResult> {
+    // As OneLOop, flip the sign of a positive imaginary part
+    let m1 = Complex64::new(m1.re, -m1.im.abs());
+    let m2 = Complex64::new(m2.re, -m2.im.abs());
+    let threshold = onshell_threshold().unwrap_or(0.0);
+    let scale = p.abs().max(m1.norm()).max(m2.norm());
+    if [p.abs(), m1.norm(), m2.norm()].iter().any(|&a| a == 0.0 || a < threshold) {
+        return None;
+    }
+    let pp = Complex64::from(p);
+    let (x1, x2) = solve_quadratic(pp, m2 - m1 - pp, m1 - IEPS * m1.norm());
+    let d = x1 - x2;
+    if d.norm() * d.norm() * p.abs() <= scale * EPSN.sqrt() {
+        return None;
+    }
+
+    // ∫₀¹ dx/(x - y) = ln(1 - 1/y), and Jₙ = ∫₀¹ dx xⁿ/((x - x₁)(x - x₂))
+    let [l1, l2] = [x1, x2].map(|x| (1.0 - 1.0 / x).ln());
+    let j0 = (l1 - l2) / d;
+    let j1 = (x1 * l1 - x2 * l2) / d;
+    let j2 = 1.0 + (x1 * x1 * l1 - x2 * x2 * l2) / d;
+    // ∂Δ/∂p = -x(1-x), ∂Δ/∂m1 = 1-x and ∂Δ/∂m2 = x
+    let integral = match variable {
+        Variable::Momentum(_) => j1 - j2,
+        Variable::Mass(0) => j1 - j0,
+        Variable::Mass(_) => -j1,
+    };
+    let zero = Complex64::new(0.0, 0.0);
+    Some(OLOResult::from_values([integral / pp, zero, zero]))
+}
+
+/// Ridders' extrapolation of central differences of `f` in `variable` to
+/// vanishing step.
+fn richardson<const K: usize, const N: usize>(
+    p: [f64; K],
+    m: [Complex64; N],
+    variable: Variable,
+    f: impl Fn([f64; K], [Complex64; N]) -> OLOResult,
+) -> Derivative {
+    let at = |t: f64| {
+        let (mut p, mut m) = (p, m);
+        match variable {
+            Variable::Momentum(i) => p[i] += t,
+            Variable::Mass(i) => m[i] += t,
+        }
+        f(p, m)
+    };
+    let x = match variable {
+        Variable::Momentum(i) => p[i].abs(),
+        Variable::Mass(i) => m[i].norm(),
+    };
+    let scale = p.iter().map(|p| p.abs()).chain(m.iter().map(|m| m.norm())).fold(0.0, f64::max);
+    let mut h = INITIAL_STEP * if x > 0.0 { x } else if scale > 0.0 { scale } else { 1.0 };
+    let difference = |h: f64| (at(h) - at(-h)) * (0.5 / h);
+
+    // tableau[j][i] extrapolates the differences i - j..=i to order h^(2j+2)
+    let mut tableau = vec![vec![OLOResult::default(); MAX_STEPS]; MAX_STEPS];
+    tableau[0][0] = difference(h);
+    let mut best = tableau[0][0];
+    let mut error = f64::INFINITY;
+    for i in 1..MAX_STEPS {
+        h /= STEP_SHRINK;
+        tableau[0][i] = difference(h);
+        let mut factor = STEP_SHRINK * STEP_SHRINK;
+        for j in 1..=i {
+            tableau[j][i] = (tableau[j - 1][i] * factor - tableau[j - 1][i - 1]) * (1.0 / (factor - 1.0));
+            factor *= STEP_SHRINK * STEP_SHRINK;
+            let estimate = distance(tableau[j][i], tableau[j - 1][i]).max(distance(tableau[j][i], tableau[j - 1][i - 1]));
+            if estimate <= error {
+                error = estimate;
+                best = tableau[j][i];
+            }
+        }
+        // Stop once higher orders get worse
+        if distance(tableau[i][i], tableau[i - 1][i - 1]) >= 2.0 * error {
+            break;
+        }
+    }
+    Derivative { value: best, error, method: Method::Richardson }
+}
+
+/// The largest difference between the coefficients of `a` and `b`.
+fn distance(a: OLOResult, b: OLOResult) -> f64 {
+    let d = a - b;
+    [d.epsilon_0(), d.epsilon_minus_1(), d.epsilon_minus_2()].iter().map(|c| c.norm()).fold(0.0, f64::max)
+}
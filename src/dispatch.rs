@@ -0,0 +1,368 @@
+//! The OneLOop routine a 3- or 4-point input is dispatched to.
+//!
+//! OneLOop decides which analytic expression to use from the masses and
+//! momenta that vanish and the legs that sit on shell: the triangles go to
+//! `tria0`..`tria4` (IR divergent) or `trif0`..`trif3` (finite), the boxes to
+//! `box00`..`box16` (IR divergent) or `boxf0`..`boxf5` and `boxc` (finite).
+//! The functions here mirror that decision, including the permutations and
+//! the on-shell threshold, without evaluating the integral.
+//!
+//! Without `set_onshell_threshold` only exact zeros count, and OneLOop merely
+//! warns about values that are smaller than its relative threshold. These are
+//! listed in `Dispatch::nearly_on_shell`, so that they can be put on shell
+//! explicitly before calling `three_point` or `four_point`.
+use crate::kinematics::DEFAULT_RELATIVE_TOLERANCE;
+use crate::{onshell_threshold, renormalization_scale};
+use num_complex::Complex64;
+
+/// A OneLOop routine evaluating a scalar 3- or 4-point function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Routine {
+    /// Massless triangle with one or two non-light-like legs.
+    Tria0 { massive_legs: usize },
+    Tria1,
+    Tria2,
+    Tria3,
+    Tria4,
+    TriF0,
+    TriF1,
+    TriF2,
+    TriF3,
+    /// Three internal masses and a negative Källén function of the momenta.
+    TriF3HV,
+    /// Massless box with no, one or two adjacent non-light-like legs.
+    Box00 { massive_legs: usize },
+    Box03,
+    Box05,
+    Box06,
+    Box07,
+    Box08,
+    Box09,
+    Box10,
+    Box11,
+    Box12,
+    Box13,
+    Box14,
+    Box15,
+    Box16,
+    BoxF0,
+    BoxF1,
+    BoxF2,
+    BoxF3,
+    BoxF4,
+    BoxF5,
+    BoxC,
+}
+
+impl Routine {
+    /// The highest pole in `ε` of the result: 2, 1, or 0 if finite.
+    pub fn pole_order(&self) -> usize {
+        use Routine::*;
+        match self {
+            Tria0 { massive_legs: 1 } | Tria2 => 2,
+            Tria0 { .. } | Tria1 | Tria3 | Tria4 => 1,
+            Box00 { .. } | Box06 | Box07 | Box08 | Box09 | Box11 | Box12 => 2,
+            Box03 | Box05 | Box10 | Box13 | Box14 | Box15 | Box16 => 1,
+            _ => 0,
+        }
+    }
+
+    /// Whether the result has poles in `ε`.
+    pub fn is_divergent(&self) -> bool {
+        self.pole_order() > 0
+    }
+}
+
+/// An input OneLOop could not classify unambiguously, numbered by its
+/// position in the arguments of `three_point` or `four_point`, starting from
+/// zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NearlyOnShell {
+    /// A momentum squared that is small but not zero.
+    Momentum(usize),
+    /// A mass squared that is small but not zero.
+    Mass(usize),
+    /// A momentum squared that is close to but not equal to a mass squared.
+    Leg { momentum: usize, mass: usize },
+}
+
+/// The routine an input maps to, and the inputs that are only nearly on shell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dispatch {
+    pub routine: Routine,
+    pub nearly_on_shell: Vec<NearlyOnShell>,
+}
+
+impl Dispatch {
+    /// The highest pole in `ε` of the result.
+    pub fn pole_order(&self) -> usize {
+        self.routine.pole_order()
+    }
+}
+
+const TRIANGLE_PERMUTATIONS: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [0, 1, 2],
+    [2, 0, 1],
+    [0, 1, 2],
+    [1, 2, 0],
+    [1, 2, 0],
+    [2, 0, 1],
+    [0, 1, 2],
+];
+const TRIANGLE_CASES: [usize; 8] = [0, 1, 1, 2, 1, 2, 2, 3];
+
+const BOX_PERMUTATIONS: [[usize; 6]; 16] = [
+    [0, 1, 2, 3, 4, 5],
+    [0, 1, 2, 3, 4, 5],
+    [3, 0, 1, 2, 5, 4],
+    [0, 1, 2, 3, 4, 5],
+    [2, 3, 0, 1, 4, 5],
+    [0, 1, 2, 3, 4, 5],
+    [3, 0, 1, 2, 5, 4],
+    [0, 1, 2, 3, 4, 5],
+    [1, 2, 3, 0, 5, 4],
+    [1, 2, 3, 0, 5, 4],
+    [3, 0, 1, 2, 5, 4],
+    [1, 2, 3, 0, 5, 4],
+    [2, 3, 0, 1, 4, 5],
+    [2, 3, 0, 1, 4, 5],
+    [3, 0, 1, 2, 5, 4],
+    [0, 1, 2, 3, 4, 5],
+];
+const BOX_CASES: [usize; 16] = [0, 1, 1, 2, 1, 5, 2, 3, 1, 2, 5, 3, 2, 3, 3, 4];
+
+/// Relabelings of the box tried when `p12` or `p23` vanishes.
+const LEG_ORDERINGS: [[usize; 6]; 3] = [[0, 1, 2, 3, 4, 5], [4, 1, 5, 3, 0, 2], [0, 5, 2, 4, 3, 1]];
+const MASS_ORDERINGS: [[usize; 4]; 3] = [[0, 1, 2, 3], [0, 2, 1, 3], [0, 1, 3, 2]];
+
+/// The on-shell threshold as OneLOop applies it.
+struct Threshold {
+    value: f64,
+    absolute: bool,
+}
+
+impl Threshold {
+    /// Returns `None` if all inputs vanish, in which case OneLOop returns zero.
+    fn new(momenta: &[f64], masses: &[f64]) -> Option<Self> {
+        let smax = momenta.iter().chain(masses).fold(0.0, |a: f64, &b| a.max(b));
+        if smax == 0.0 {
+            return None;
+        }
+        Some(match onshell_threshold() {
+            Some(value) => Self { value, absolute: true },
+            None => Self {
+                value: DEFAULT_RELATIVE_TOLERANCE * smax.max(renormalization_scale().powi(2)),
+                absolute: false,
+            },
+        })
+    }
+
+    /// Puts `x` to zero if below an explicitly set threshold, and otherwise
+    /// records it if OneLOop would warn about it.
+    fn apply(&self, x: &mut f64, what: NearlyOnShell, nearly_on_shell: &mut Vec<NearlyOnShell>) {
+        if self.absolute {
+            if *x < self.value {
+                *x = 0.0;
+            }
+        } else if 0.0 < *x && *x < self.value {
+            nearly_on_shell.push(what);
+        }
+    }
+}
+
+/// OneLOop flips the sign of a positive imaginary part of a mass.
+pub(crate) fn conjugate_if_positive(m: Complex64) -> Complex64 {
+    Complex64::new(m.re, -m.im.abs())
+}
+
+/// The triangle inputs in the order OneLOop passes them to the routine.
+pub(crate) struct Triangle {
+    pub momenta: [f64; 3],
+    /// The absolute values of the momenta, put to zero below the threshold.
+    pub abs_momenta: [f64; 3],
+    pub masses: [Complex64; 3],
+    /// The largest input, at least the renormalization scale squared.
+    pub smax: f64,
+}
+
+/// Returns the routine OneLOop uses for `three_point` with the same
+/// arguments, or `None` if it returns zero with an error instead.
+pub fn three_point(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+) -> Option<Dispatch> {
+    triangle(p1, p2, p3, m1.into(), m2.into(), m3.into()).map(|(dispatch, _)| dispatch)
+}
+
+/// Like `three_point`, but also returns the permuted inputs.
+pub(crate) fn triangle(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    m1: Complex64,
+    m2: Complex64,
+    m3: Complex64,
+) -> Option<(Dispatch, Triangle)> {
+    let pp = [p1, p2, p3];
+    let mm = [m1, m2, m3].map(conjugate_if_positive);
+    let mut ap = pp.map(f64::abs);
+    let mut am = mm.map(|m| m.re.abs() + m.im.abs());
+    let threshold = Threshold::new(&ap, &am)?;
+    let smax = ap.iter().chain(&am).fold(renormalization_scale().powi(2), |a: f64, &b| a.max(b));
+    let mut nearly_on_shell = Vec::new();
+    for i in 0..3 {
+        threshold.apply(&mut ap[i], NearlyOnShell::Momentum(i), &mut nearly_on_shell);
+        threshold.apply(&mut am[i], NearlyOnShell::Mass(i), &mut nearly_on_shell);
+    }
+
+    let icase: usize = (0..3).filter(|&i| am[i] > 0.0).map(|i| 4 >> i).sum();
+    let perm = TRIANGLE_PERMUTATIONS[icase];
+    let ss = perm.map(|i| pp[i]);
+    let as_ = perm.map(|i| ap[i]);
+
+    let mut distance = |i: usize, j: usize| {
+        let mut d = (pp[perm[i]] - mm[perm[j]]).norm();
+        let what = NearlyOnShell::Leg { momentum: perm[i], mass: perm[j] };
+        threshold.apply(&mut d, what, &mut nearly_on_shell);
+        d
+    };
+    let s1r2 = distance(0, 1);
+    let s2r3 = distance(1, 2);
+    let s3r3 = distance(2, 2);
+
+    let routine = match TRIANGLE_CASES[icase] {
+        3 => {
+            let [x, y, z] = ss;
+            if x * x + y * y + z * z - 2.0 * (x * y + y * z + z * x) < 0.0 {
+                Routine::TriF3HV
+            } else {
+                Routine::TriF3
+            }
+        }
+        2 if s1r2 != 0.0 || s3r3 != 0.0 => Routine::TriF2,
+        2 => Routine::Tria4,
+        1 if as_[0] != 0.0 => Routine::TriF1,
+        1 if s2r3 != 0.0 && s3r3 != 0.0 => Routine::Tria3,
+        1 if s2r3 != 0.0 || s3r3 != 0.0 => Routine::Tria2,
+        1 => Routine::Tria1,
+        _ => {
+            let massive_legs = as_.iter().filter(|&&a| a > 0.0).count();
+            match massive_legs {
+                0 => return None,
+                3 => Routine::TriF0,
+                _ => Routine::Tria0 { massive_legs },
+            }
+        }
+    };
+    let triangle = Triangle { momenta: ss, abs_momenta: as_, masses: perm.map(|i| mm[i]), smax };
+    Some((Dispatch { routine, nearly_on_shell }, triangle))
+}
+
+/// Returns the routine OneLOop uses for `four_point` with the same
+/// arguments, or `None` if it returns zero with an error instead.
+#[allow(clippy::too_many_arguments)]
+pub fn four_point(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    p4: f64,
+    p12: f64,
+    p23: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+    m4: impl Into<Complex64>,
+) -> Option<Dispatch> {
+    let pp = [p1, p2, p3, p4, p12, p23];
+    let mm = [m1.into(), m2.into(), m3.into(), m4.into()].map(conjugate_if_positive);
+    let mut ap = pp.map(f64::abs);
+    let mut am = mm.map(|m| m.re.abs() + m.im.abs());
+    let small = ap.iter().fold(0.0, |a: f64, &b| a.max(b)) * DEFAULT_RELATIVE_TOLERANCE;
+    let threshold = Threshold::new(&ap, &am)?;
+    let mut nearly_on_shell = Vec::new();
+    for i in 0..4 {
+        threshold.apply(&mut ap[i], NearlyOnShell::Momentum(i), &mut nearly_on_shell);
+        threshold.apply(&mut am[i], NearlyOnShell::Mass(i), &mut nearly_on_shell);
+    }
+
+    // Without hard kinematics in the s- and t-channel, try another labeling
+    let min56 = ap[4].min(ap[5]);
+    let mut jj = 0;
+    if min56 < threshold.value {
+        let min13 = ap[0].min(ap[2]);
+        let min24 = ap[1].min(ap[3]);
+        if min13 > min24 && min13 > min56 {
+            jj = 1;
+        } else if min24 > min13 && min24 > min56 {
+            jj = 2;
+        }
+    }
+
+    let icase: usize = (0..4).filter(|&i| am[MASS_ORDERINGS[jj][i]] > 0.0).map(|i| 8 >> i).sum();
+    let legs = BOX_PERMUTATIONS[icase].map(|i| LEG_ORDERINGS[jj][i]);
+    let props = [0, 1, 2, 3].map(|i| MASS_ORDERINGS[jj][BOX_PERMUTATIONS[icase][i]]);
+    let ss = legs.map(|i| pp[i]);
+    let as_ = legs.map(|i| ap[i]);
+    let rr = props.map(|j| mm[j]);
+    let ar = props.map(|j| am[j]);
+
+    let mut distance = |i: usize, j: usize| {
+        let d = ss[i] - rr[j];
+        let mut d = d.re.abs() + d.im.abs();
+        let what = NearlyOnShell::Leg { momentum: legs[i], mass: props[j] };
+        threshold.apply(&mut d, what, &mut nearly_on_shell);
+        d
+    };
+    let s1r2 = distance(0, 1);
+    let s2r2 = distance(1, 1);
+    let s2r3 = distance(1, 2);
+    let s3r4 = distance(2, 3);
+    let s4r4 = distance(3, 3);
+
+    let use_boxc = (0..4).any(|j| ar[j] != 0.0 && rr[j].im != 0.0)
+        || ss[..4].iter().all(|&s| s >= -small)
+        || (ss[4] >= -small && ss[5] >= -small);
+
+    use Routine::*;
+    let routine = match BOX_CASES[icase] {
+        4 if use_boxc => BoxC,
+        4 => BoxF4,
+        3 if s1r2 == 0.0 && s4r4 == 0.0 => Box16,
+        3 if use_boxc => BoxC,
+        3 => BoxF3,
+        5 if s1r2 != 0.0 || s4r4 != 0.0 => {
+            if s2r2 != 0.0 || s3r4 != 0.0 { BoxF5 } else { Box15 }
+        }
+        5 if s2r2 != 0.0 || s3r4 != 0.0 => Box15,
+        5 => Box14,
+        2 if as_[0] != 0.0 => BoxF2,
+        2 if s2r3 != 0.0 && s4r4 != 0.0 => Box13,
+        2 if s2r3 != 0.0 || s4r4 != 0.0 => Box12,
+        2 => Box11,
+        1 if as_[0] != 0.0 && as_[1] != 0.0 => BoxF1,
+        1 if as_[0] != 0.0 => {
+            if s3r4 != 0.0 { Box10 } else { Box09 }
+        }
+        1 if as_[1] != 0.0 => {
+            if s4r4 != 0.0 { Box10 } else { Box09 }
+        }
+        1 if s3r4 != 0.0 && s4r4 != 0.0 => Box08,
+        1 if s3r4 != 0.0 || s4r4 != 0.0 => Box07,
+        1 => Box06,
+        _ => {
+            let icase: usize = (0..4).filter(|&i| as_[i] > 0.0).map(|i| 8 >> i).sum();
+            match BOX_CASES[icase] {
+                5 => Box03,
+                3 => Box05,
+                4 => BoxF0,
+                massive_legs => Box00 { massive_legs },
+            }
+        }
+    };
+    Some(Dispatch { routine, nearly_on_shell })
+}
@@ -0,0 +1,232 @@
+//! Results and inputs as Mathematica, FORM and LaTeX expressions, e.g. to
+//! compare with Package-X or FeynCalc without retyping numbers.
+//!
+//! A result is written as its Laurent series `c0 + c1/eps + c2/eps^2`,
+//! leaving out vanishing poles, and a point as the call of the scalar function
+//! in the notation of LoopTools, `A0`, `B0`, `C0` or `D0` with the arguments
+//! of `one_point` to `four_point`.
+//!
+//! - Mathematica: machine numbers with `*^` exponents, as `-1.25*^-7`, which
+//!   read back exactly, `I` and `eps`. Mantissas of 16 or more digits, which
+//!   Mathematica would read with arbitrary precision, are marked as machine
+//!   numbers with a backquote, as ``3.0000000000000004`*^-1``.
+//! - FORM: exact rationals, as FORM only computes with those, e.g. `0.1` as
+//!   `3602879701896397/36028797018963968`, `i_` and `ep`.
+//! - LaTeX: the shortest decimals that read back exactly, with `\times
+//!   10^{n}` for very large or small numbers, `i` and `\epsilon`.
+//!
+//! Numbers that are not finite are written as `Indeterminate`, `Infinity` or
+//! `-Infinity` for Mathematica, `\mathrm{NaN}` or `\infty` in LaTeX, and
+//! as `nan` or `inf` for FORM, which cannot represent them.
+use crate::OLOResult;
+use crate::input::Point;
+use num_complex::Complex64;
+
+/// A target language.
+#[derive(Clone, Copy, PartialEq)]
+enum Syntax {
+    Mathematica,
+    Form,
+    Latex,
+}
+
+impl OLOResult {
+    /// The result as a Mathematica expression in `eps`, e.g.
+    /// `(-1.5 + 3.14159*I) + 1./eps`.
+    pub fn to_mathematica(&self) -> String {
+        series(self, Syntax::Mathematica)
+    }
+
+    /// The result as a FORM expression in `ep` with exact rational
+    /// coefficients, e.g. `(-3/2 + 1/4*i_) + 1/ep`.
+    pub fn to_form(&self) -> String {
+        series(self, Syntax::Form)
+    }
+
+    /// The result as a LaTeX formula in `\epsilon`, without surrounding `$`,
+    /// e.g. `(-1.5 + 3.14159\,i) + \frac{1}{\epsilon}`.
+    pub fn to_latex(&self) -> String {
+        series(self, Syntax::Latex)
+    }
+}
+
+impl Point {
+    /// The scalar function call as a Mathematica expression, e.g.
+    /// `B0[1.5, 1. - 0.1*I, 0.]`.
+    pub fn to_mathematica(&self) -> String {
+        call(self, Syntax::Mathematica)
+    }
+
+    /// The scalar function call as a FORM expression with exact rational
+    /// arguments, e.g. `B0(3/2, 1 - 1/4*i_, 0)`.
+    pub fn to_form(&self) -> String {
+        call(self, Syntax::Form)
+    }
+
+    /// The scalar function call as a LaTeX formula, e.g.
+    /// `B_0(1.5, 1 - 0.25\,i, 0)`.
+    pub fn to_latex(&self) -> String {
+        call(self, Syntax::Latex)
+    }
+}
+
+fn series(result: &OLOResult, syntax: Syntax) -> String {
+    let mut out = complex(result.epsilon_0(), syntax);
+    let poles = [(result.epsilon_minus_1(), 1), (result.epsilon_minus_2(), 2)];
+    for (c, power) in poles {
+        if c == Complex64::new(0.0, 0.0) {
+            continue;
+        }
+        let c = complex(c, syntax);
+        let (sign, c) = match c.strip_prefix('-') {
+            Some(c) => (" - ", c.to_string()),
+            None => (" + ", c),
+        };
+        out += sign;
+        out += &match (syntax, power) {
+            (Syntax::Mathematica, 1) => format!("{}/eps", c),
+            (Syntax::Mathematica, _) => format!("{}/eps^2", c),
+            (Syntax::Form, 1) => format!("{}/ep", c),
+            (Syntax::Form, _) => format!("{}/ep^2", c),
+            (Syntax::Latex, 1) => format!("\\frac{{{}}}{{\\epsilon}}", c),
+            (Syntax::Latex, _) => format!("\\frac{{{}}}{{\\epsilon^2}}", c),
+        };
+    }
+    out
+}
+
+fn call(point: &Point, syntax: Syntax) -> String {
+    let args: Vec<String> = point
+        .momenta
+        .iter()
+        .map(|&p| real(p, syntax))
+        .chain(point.masses.iter().map(|&m| complex(m, syntax).trim_matches(['(', ')']).to_string()))
+        .collect();
+    let (open, close, name) = match syntax {
+        Syntax::Mathematica => ('[', ']', format!("{}0", ["A", "B", "C", "D"][point.legs - 1])),
+        Syntax::Form => ('(', ')', format!("{}0", ["A", "B", "C", "D"][point.legs - 1])),
+        Syntax::Latex => ('(', ')', format!("{}_0", ["A", "B", "C", "D"][point.legs - 1])),
+    };
+    format!("{}{}{}{}", name, open, args.join(", "), close)
+}
+
+/// A complex number, in parentheses if both parts are non-zero.
+fn complex(z: Complex64, syntax: Syntax) -> String {
+    let i = match syntax {
+        Syntax::Mathematica => "*I",
+        Syntax::Form => "*i_",
+        Syntax::Latex => "\\,i",
+    };
+    if z.im == 0.0 {
+        return real(z.re, syntax);
+    }
+    if z.re == 0.0 {
+        return format!("{}{}", real(z.im, syntax), i);
+    }
+    let sign = if z.im.is_sign_negative() { '-' } else { '+' };
+    format!("({} {} {}{})", real(z.re, syntax), sign, real(z.im.abs(), syntax), i)
+}
+
+fn real(x: f64, syntax: Syntax) -> String {
+    // Adding zero turns -0 into 0
+    let x = x + 0.0;
+    if !x.is_finite() {
+        let sign = if x < 0.0 { "-" } else { "" };
+        return match (syntax, x.is_nan()) {
+            (Syntax::Mathematica, true) => "Indeterminate".to_string(),
+            (Syntax::Mathematica, false) => format!("{}Infinity", sign),
+            (Syntax::Form, true) => "nan".to_string(),
+            (Syntax::Form, false) => format!("{}inf", sign),
+            (Syntax::Latex, true) => "\\mathrm{NaN}".to_string(),
+            (Syntax::Latex, false) => format!("{}\\infty", sign),
+        };
+    }
+    match syntax {
+        Syntax::Mathematica => {
+            let (mantissa, exponent) = shortest(x);
+            // A point makes the mantissa a machine number rather than exact,
+            // and a backquote one with more digits than machine precision
+            let point = if mantissa.contains('.') { "" } else { "." };
+            let digits = mantissa.chars().filter(char::is_ascii_digit).count();
+            let marker = if digits >= MACHINE_DIGITS { "`" } else { "" };
+            match exponent {
+                0 => format!("{}{}{}", mantissa, point, marker),
+                _ => format!("{}{}{}*^{}", mantissa, point, marker, exponent),
+            }
+        }
+        Syntax::Form => rational(x),
+        Syntax::Latex => {
+            let (mantissa, exponent) = shortest(x);
+            if (-3..6).contains(&exponent) {
+                x.to_string()
+            } else {
+                format!("{} \\times 10^{{{}}}", mantissa, exponent)
+            }
+        }
+    }
+}
+
+/// The number of significant digits from which Mathematica reads a number
+/// with arbitrary precision, the next integer above `$MachinePrecision`.
+const MACHINE_DIGITS: usize = 16;
+
+/// The shortest decimal mantissa in `[1, 10)` and exponent that read back as
+/// `x`.
+fn shortest(x: f64) -> (String, i32) {
+    let s = format!("{:e}", x);
+    let (mantissa, exponent) = s.split_once('e').unwrap();
+    (mantissa.to_string(), exponent.parse().unwrap())
+}
+
+/// The exact value of a finite `x` as an integer or a fraction with a power
+/// of two as denominator.
+fn rational(x: f64) -> String {
+    if x == 0.0 {
+        return "0".to_string();
+    }
+    let bits = x.abs().to_bits();
+    let biased = (bits >> 52) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+    // x = ±m 2^e
+    let (mut m, mut e) = match biased {
+        0 => (fraction, -1074),
+        _ => (fraction | (1 << 52), biased - 1075),
+    };
+    let zeros = m.trailing_zeros().min(e.unsigned_abs()) as i32;
+    if e < 0 {
+        m >>= zeros;
+        e += zeros;
+    }
+    let sign = if x < 0.0 { "-" } else { "" };
+    if e >= 0 {
+        format!("{}{}", sign, times_power_of_two(m, e as u32))
+    } else {
+        format!("{}{}/{}", sign, m, times_power_of_two(1, (-e) as u32))
+    }
+}
+
+/// `m 2^e` in decimal.
+fn times_power_of_two(m: u64, e: u32) -> String {
+    const BASE: u64 = 1_000_000_000;
+    // Little-endian digits in base 10⁹
+    let mut digits = vec![m % BASE, m / BASE % BASE, m / BASE / BASE];
+    for _ in 0..e {
+        let mut carry = 0;
+        for d in digits.iter_mut() {
+            let v = *d * 2 + carry;
+            *d = v % BASE;
+            carry = v / BASE;
+        }
+        if carry > 0 {
+            digits.push(carry);
+        }
+    }
+    while digits.len() > 1 && digits[digits.len() - 1] == 0 {
+        digits.pop();
+    }
+    let mut out = digits[digits.len() - 1].to_string();
+    for d in digits.iter().rev().skip(1) {
+        out += &format!("{:09}", d);
+    }
+    out
+}
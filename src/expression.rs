@@ -0,0 +1,465 @@
+//! Linear combinations of scalar and tensor integrals in the notation of
+//! LoopTools and FeynCalc, as in amplitudes exported from Mathematica:
+//!
+//! ```text
+//! 2*B0[s, 0, mt^2] - C0[0, 0, s, mt^2, mt^2, mt^2]/s
+//! ```
+//!
+//! The functions are `A0[m]`, `B0[p, m1, m2]`, `B1`, `B00`, `DB0 = ∂B0/∂p`
+//! with the arguments of `B0`, `C0[p1, p2, p3, m1, m2, m3]` and `D0[p1, p2,
+//! p3, p4, p12, p23, m1, m2, m3, m4]`, with squared momenta and masses as for
+//! `one_point` to `four_point`. Their names are case-insensitive, and the
+//! arguments may be in brackets or parentheses.
+//!
+//! Numbers are written as `1.5`, `1.5e-3`, `1.5*^-3` or, with Mathematica's
+//! machine-precision marker, ``1.5`*^-3``, `I` is the imaginary
+//! unit and `Pi` is π. Any other name is a symbol whose value is bound at
+//! evaluation, e.g. `s` and `mt` above. Expressions combine with `+`, `-`,
+//! `*`, `/`, `^` and parentheses, where `-a^2` is `-(a^2)`, but the result
+//! must be linear in the integrals: they may be added and multiplied by
+//! numbers, but not multiplied with each other, divided by or raised to a
+//! power. Numbers added to an integral contribute to its finite part.
+//!
+//! Integrals are evaluated in the normalization of OneLOop, which is that of
+//! LoopTools, at the current renormalization scale; `TO_FEYNMAN` is not
+//! applied. `DB0` comes from `derivative::two_point`; the other functions,
+//! including the 2-point tensor coefficients `B1` and `B00`, go through the
+//! backend.
+use crate::OLOResult;
+use crate::derivative::{self, Variable};
+use num_complex::Complex64;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::fmt;
+
+/// Errors of `Expression::parse` and `Expression::evaluate`, with the
+/// 1-based column at which they occur.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionError {
+    /// The character cannot start a token.
+    InvalidCharacter { column: usize, character: char },
+    /// The token is not expected here.
+    UnexpectedToken { column: usize, token: String },
+    /// The expression ends in the middle.
+    UnexpectedEnd,
+    /// A name followed by arguments is not one of the supported functions.
+    UnknownFunction { column: usize, name: String },
+    /// The function takes a different number of arguments.
+    WrongArgumentCount { column: usize, function: String, expected: usize, found: usize },
+    /// The symbol has no value.
+    UnboundSymbol { column: usize, name: String },
+    /// A squared momentum has an imaginary part, which the scalar functions
+    /// do not accept.
+    ComplexMomentum { column: usize, function: String },
+    /// Integrals are multiplied with each other, divided by or raised to a
+    /// power.
+    NotLinear { column: usize },
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpressionError::InvalidCharacter { column, character } => {
+                write!(f, "column {}: invalid character '{}'", column, character)
+            }
+            ExpressionError::UnexpectedToken { column, token } => {
+                write!(f, "column {}: unexpected '{}'", column, token)
+            }
+            ExpressionError::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            ExpressionError::UnknownFunction { column, name } => {
+                write!(f, "column {}: unknown function '{}', expected A0, B0, B1, B00, DB0, C0 or D0", column, name)
+            }
+            ExpressionError::WrongArgumentCount { column, function, expected, found } => {
+                write!(f, "column {}: {} takes {} arguments, got {}", column, function, expected, found)
+            }
+            ExpressionError::UnboundSymbol { column, name } => {
+                write!(f, "column {}: symbol '{}' has no value", column, name)
+            }
+            ExpressionError::ComplexMomentum { column, function } => {
+                write!(f, "column {}: squared momenta of {} must have a vanishing imaginary part", column, function)
+            }
+            ExpressionError::NotLinear { column } => {
+                write!(f, "column {}: the expression is not linear in the integrals", column)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExpressionError {}
+
+/// A supported function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Function {
+    A0,
+    B0,
+    B1,
+    B00,
+    DB0,
+    C0,
+    D0,
+}
+
+impl Function {
+    fn by_name(name: &str) -> Option<Self> {
+        match name.to_uppercase().as_str() {
+            "A0" => Some(Function::A0),
+            "B0" => Some(Function::B0),
+            "B1" => Some(Function::B1),
+            "B00" => Some(Function::B00),
+            "DB0" => Some(Function::DB0),
+            "C0" => Some(Function::C0),
+            "D0" => Some(Function::D0),
+            _ => None,
+        }
+    }
+
+    /// The numbers of squared momenta and masses.
+    fn arguments(self) -> (usize, usize) {
+        match self {
+            Function::A0 => (0, 1),
+            Function::B0 | Function::B1 | Function::B00 | Function::DB0 => (1, 2),
+            Function::C0 => (3, 3),
+            Function::D0 => (6, 4),
+        }
+    }
+
+    fn evaluate(self, p: &[f64], m: &[Complex64]) -> OLOResult {
+        match self {
+            Function::A0 => crate::one_point(m[0]),
+            Function::B0 => crate::two_point(p[0], m[0], m[1]),
+            Function::B1 => crate::two_point_coefficients(p[0], m[0], m[1]).b1,
+            Function::B00 => crate::two_point_coefficients(p[0], m[0], m[1]).b00,
+            Function::DB0 => derivative::two_point(p[0], m[0], m[1], Variable::Momentum(0)).value,
+            Function::C0 => crate::three_point(p[0], p[1], p[2], m[0], m[1], m[2]),
+            Function::D0 => crate::four_point(p[0], p[1], p[2], p[3], p[4], p[5], m[0], m[1], m[2], m[3]),
+        }
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// A node of the syntax tree, with the column of its operator, name or call.
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Number(Complex64),
+    Symbol { name: String, column: usize },
+    Call { function: Function, arguments: Vec<Node>, column: usize },
+    Negate(Box<Node>),
+    Binary { operator: char, left: Box<Node>, right: Box<Node>, column: usize },
+}
+
+/// A parsed expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    root: Node,
+}
+
+/// The value of a subexpression.
+#[derive(Clone, Copy)]
+enum Value {
+    Number(Complex64),
+    Integral(OLOResult),
+}
+
+impl Expression {
+    /// Parses `text`.
+    pub fn parse(text: &str) -> Result<Self, ExpressionError> {
+        let mut parser = Parser { tokens: tokenize(text)?, next: 0 };
+        let root = parser.sum()?;
+        match parser.tokens.get(parser.next) {
+            Some((token, column)) => {
+                Err(ExpressionError::UnexpectedToken { column: *column, token: token.to_string() })
+            }
+            None => Ok(Expression { root }),
+        }
+    }
+
+    /// The symbols of the expression, sorted and without duplicates.
+    pub fn symbols(&self) -> Vec<String> {
+        fn collect(node: &Node, out: &mut Vec<String>) {
+            match node {
+                Node::Number(_) => {}
+                Node::Symbol { name, .. } => out.push(name.clone()),
+                Node::Call { arguments, .. } => arguments.iter().for_each(|a| collect(a, out)),
+                Node::Negate(inner) => collect(inner, out),
+                Node::Binary { left, right, .. } => {
+                    collect(left, out);
+                    collect(right, out);
+                }
+            }
+        }
+        let mut out = Vec::new();
+        collect(&self.root, &mut out);
+        out.sort();
+        out.dedup();
+        out
+    }
+
+    /// Evaluates the expression with the symbols bound to `bindings`.
+    pub fn evaluate(&self, bindings: &HashMap<String, Complex64>) -> Result<OLOResult, ExpressionError> {
+        match value(&self.root, bindings)? {
+            Value::Number(z) => Ok(OLOResult::new(z, Complex64::new(0.0, 0.0), Complex64::new(0.0, 0.0))),
+            Value::Integral(result) => Ok(result),
+        }
+    }
+}
+
+/// Parses and evaluates `text`, see `Expression`.
+pub fn evaluate(text: &str, bindings: &HashMap<String, Complex64>) -> Result<OLOResult, ExpressionError> {
+    Expression::parse(text)?.evaluate(bindings)
+}
+
+fn value(node: &Node, bindings: &HashMap<String, Complex64>) -> Result<Value, ExpressionError> {
+    use Value::{Integral, Number};
+    match node {
+        Node::Number(z) => Ok(Number(*z)),
+        Node::Symbol { name, column } => match bindings.get(name) {
+            Some(z) => Ok(Number(*z)),
+            None => Err(ExpressionError::UnboundSymbol { column: *column, name: name.clone() }),
+        },
+        Node::Call { function, arguments, column } => {
+            let mut numbers = Vec::new();
+            for argument in arguments {
+                match value(argument, bindings)? {
+                    Number(z) => numbers.push(z),
+                    Integral(_) => return Err(ExpressionError::NotLinear { column: *column }),
+                }
+            }
+            let (momenta, _) = function.arguments();
+            if numbers[..momenta].iter().any(|p| p.im != 0.0) {
+                return Err(ExpressionError::ComplexMomentum { column: *column, function: function.to_string() });
+            }
+            let p: Vec<f64> = numbers[..momenta].iter().map(|p| p.re).collect();
+            Ok(Integral(function.evaluate(&p, &numbers[momenta..])))
+        }
+        Node::Negate(inner) => match value(inner, bindings)? {
+            Number(z) => Ok(Number(-z)),
+            Integral(r) => Ok(Integral(-r)),
+        },
+        Node::Binary { operator, left, right, column } => {
+            let zero = Complex64::new(0.0, 0.0);
+            let constant = |z| OLOResult::new(z, zero, zero);
+            let not_linear = Err(ExpressionError::NotLinear { column: *column });
+            match (*operator, value(left, bindings)?, value(right, bindings)?) {
+                ('+', Number(a), Number(b)) => Ok(Number(a + b)),
+                ('+', Integral(a), Integral(b)) => Ok(Integral(a + b)),
+                ('+', Integral(a), Number(b)) | ('+', Number(b), Integral(a)) => Ok(Integral(a + constant(b))),
+                ('-', Number(a), Number(b)) => Ok(Number(a - b)),
+                ('-', Integral(a), Integral(b)) => Ok(Integral(a - b)),
+                ('-', Integral(a), Number(b)) => Ok(Integral(a - constant(b))),
+                ('-', Number(a), Integral(b)) => Ok(Integral(constant(a) - b)),
+                ('*', Number(a), Number(b)) => Ok(Number(a * b)),
+                ('*', Integral(a), Number(b)) | ('*', Number(b), Integral(a)) => Ok(Integral(a * b)),
+                ('/', Number(a), Number(b)) => Ok(Number(a / b)),
+                ('/', Integral(a), Number(b)) => Ok(Integral(a * (1.0 / b))),
+                ('^', Number(a), Number(b)) => Ok(Number(power(a, b))),
+                _ => not_linear,
+            }
+        }
+    }
+}
+
+/// `a^b`, exact for integer `b`.
+fn power(a: Complex64, b: Complex64) -> Complex64 {
+    if b.im == 0.0 && b.re.fract() == 0.0 && b.re.abs() <= i32::MAX as f64 {
+        a.powi(b.re as i32)
+    } else {
+        a.powc(b)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Operator(char),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(x) => write!(f, "{}", x),
+            Token::Name(name) => write!(f, "{}", name),
+            Token::Operator(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+/// Splits `text` into tokens with their columns.
+fn tokenize(text: &str) -> Result<Vec<(Token, usize)>, ExpressionError> {
+    let chars: Vec<char> = text.chars().collect();
+    let digits = |mut i: usize| {
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+        i
+    };
+    // The end of an exponent of at least one digit starting at `i`, if any
+    let exponent = |i: usize| {
+        let start = if matches!(chars.get(i), Some('+' | '-')) { i + 1 } else { i };
+        let end = digits(start);
+        (end > start).then_some(end)
+    };
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i + 1;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || (c == '.' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit())) {
+            let mut end = digits(i);
+            if chars.get(end) == Some(&'.') {
+                end = digits(end + 1);
+            }
+            let mut mantissa: String = chars[i..end].iter().collect();
+            // Mathematica's machine-precision marker
+            if chars.get(end) == Some(&'`') {
+                end += 1;
+            }
+            if let (Some('e' | 'E'), Some(e)) = (chars.get(end), exponent(end + 1)) {
+                mantissa.extend(&chars[end..e]);
+                end = e;
+            }
+            // Mathematica's `*^` exponent
+            if let (Some('*'), Some('^'), Some(e)) = (chars.get(end), chars.get(end + 1), exponent(end + 2)) {
+                mantissa.push('e');
+                mantissa.extend(&chars[end + 2..e]);
+                end = e;
+            }
+            tokens.push((Token::Number(mantissa.parse().unwrap()), column));
+            i = end;
+        } else if c.is_alphabetic() || c == '$' {
+            let mut end = i;
+            while end < chars.len() && (chars[end].is_alphanumeric() || matches!(chars[end], '_' | '$')) {
+                end += 1;
+            }
+            tokens.push((Token::Name(chars[i..end].iter().collect()), column));
+            i = end;
+        } else if "+-*/^()[],".contains(c) {
+            tokens.push((Token::Operator(c), column));
+            i += 1;
+        } else {
+            return Err(ExpressionError::InvalidCharacter { column, character: c });
+        }
+    }
+    Ok(tokens)
+}
+
+/// A recursive-descent parser over the tokens.
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    next: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(token, _)| token)
+    }
+
+    fn advance(&mut self) -> Result<(Token, usize), ExpressionError> {
+        let token = self.tokens.get(self.next).cloned().ok_or(ExpressionError::UnexpectedEnd)?;
+        self.next += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, operator: char) -> Result<(), ExpressionError> {
+        match self.advance()? {
+            (Token::Operator(c), _) if c == operator => Ok(()),
+            (token, column) => Err(ExpressionError::UnexpectedToken { column, token: token.to_string() }),
+        }
+    }
+
+    /// `term (('+' | '-') term)*`
+    fn sum(&mut self) -> Result<Node, ExpressionError> {
+        let mut node = self.product()?;
+        while let Some(Token::Operator(operator @ ('+' | '-'))) = self.peek().cloned() {
+            let (_, column) = self.advance()?;
+            let right = self.product()?;
+            node = Node::Binary { operator, left: Box::new(node), right: Box::new(right), column };
+        }
+        Ok(node)
+    }
+
+    /// `unary (('*' | '/') unary)*`
+    fn product(&mut self) -> Result<Node, ExpressionError> {
+        let mut node = self.unary()?;
+        while let Some(Token::Operator(operator @ ('*' | '/'))) = self.peek().cloned() {
+            let (_, column) = self.advance()?;
+            let right = self.unary()?;
+            node = Node::Binary { operator, left: Box::new(node), right: Box::new(right), column };
+        }
+        Ok(node)
+    }
+
+    /// `('-' | '+') unary | primary ('^' unary)?`
+    fn unary(&mut self) -> Result<Node, ExpressionError> {
+        match self.peek() {
+            Some(Token::Operator('-')) => {
+                self.next += 1;
+                Ok(Node::Negate(Box::new(self.unary()?)))
+            }
+            Some(Token::Operator('+')) => {
+                self.next += 1;
+                self.unary()
+            }
+            _ => {
+                let node = self.primary()?;
+                if self.peek() != Some(&Token::Operator('^')) {
+                    return Ok(node);
+                }
+                let (_, column) = self.advance()?;
+                let exponent = self.unary()?;
+                Ok(Node::Binary { operator: '^', left: Box::new(node), right: Box::new(exponent), column })
+            }
+        }
+    }
+
+    /// A number, a symbol, a call or a parenthesized sum.
+    fn primary(&mut self) -> Result<Node, ExpressionError> {
+        match self.advance()? {
+            (Token::Number(x), _) => Ok(Node::Number(x.into())),
+            (Token::Operator('('), _) => {
+                let node = self.sum()?;
+                self.expect(')')?;
+                Ok(node)
+            }
+            (Token::Name(name), column) => {
+                let close = match self.peek() {
+                    Some(Token::Operator('[')) => ']',
+                    Some(Token::Operator('(')) => ')',
+                    _ => {
+                        return Ok(match name.as_str() {
+                            "I" => Node::Number(Complex64::new(0.0, 1.0)),
+                            "Pi" => Node::Number(PI.into()),
+                            _ => Node::Symbol { name, column },
+                        });
+                    }
+                };
+                let function = Function::by_name(&name).ok_or(ExpressionError::UnknownFunction { column, name })?;
+                self.next += 1;
+                let mut arguments = vec![self.sum()?];
+                while self.peek() == Some(&Token::Operator(',')) {
+                    self.next += 1;
+                    arguments.push(self.sum()?);
+                }
+                self.expect(close)?;
+                let (momenta, masses) = function.arguments();
+                if arguments.len() != momenta + masses {
+                    return Err(ExpressionError::WrongArgumentCount {
+                        column,
+                        function: function.to_string(),
+                        expected: momenta + masses,
+                        found: arguments.len(),
+                    });
+                }
+                Ok(Node::Call { function, arguments, column })
+            }
+            (token, column) => Err(ExpressionError::UnexpectedToken { column, token: token.to_string() }),
+        }
+    }
+}
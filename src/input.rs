@@ -0,0 +1,183 @@
+//! Parser for the test-point format of OneLOop's examples, as in
+//! `oneloop/example/input` and `irdiv_input`:
+//!
+//! ```text
+//! 3  0.1726d0     ! nleg  mu
+//! ( 0.0d0 , 0d0 ) ! p1^2
+//! ( 1.1d0 , 0d0 ) ! p2^2
+//! (-1.7d0 , 0d0 ) ! p3^2
+//! ( 1.2d0 , 0d0 ) ! m1^2
+//! ( 1.3d0 , 0d0 ) ! m2^2
+//! ( 1.5d0 , 0d0 ) ! m3^2
+//! next
+//! ```
+//!
+//! Each point starts with the number of legs and the renormalization scale,
+//! followed by the squared momenta and masses in the order of the arguments of
+//! `one_point` to `four_point`, one complex number `(re, im)` per line.
+//! Points are separated by `next`, and `stop` ends the input. Everything after
+//! `!` is a comment, and numbers may use Fortran's `d` exponent.
+use crate::{OLOResult, four_point, one_point, three_point, two_point};
+use num_complex::Complex64;
+use std::fmt;
+
+/// Errors reported by `parse`, with the 1-based line number.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputError {
+    /// The line does not start a point with `nleg mu`.
+    InvalidHeader { line: usize },
+    /// The number of legs is not between 1 and 4.
+    UnsupportedLegs { line: usize, legs: usize },
+    /// The line is not a number `(re, im)`.
+    InvalidValue { line: usize },
+    /// A squared momentum has an imaginary part, which the scalar functions
+    /// do not accept.
+    ComplexMomentum { line: usize },
+    /// The point ends before all values are given, or has too many.
+    WrongValueCount { line: usize, expected: usize, found: usize },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::InvalidHeader { line } => write!(f, "line {}: expected `nleg mu`", line),
+            InputError::UnsupportedLegs { line, legs } => {
+                write!(f, "line {}: {} legs, only 1 to 4 are supported", line, legs)
+            }
+            InputError::InvalidValue { line } => write!(f, "line {}: expected a number `(re, im)`", line),
+            InputError::ComplexMomentum { line } => {
+                write!(f, "line {}: squared momenta must have a vanishing imaginary part", line)
+            }
+            InputError::WrongValueCount { line, expected, found } => {
+                write!(f, "line {}: expected {} values, found {}", line, expected, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// A test point: a scalar function with its inputs and renormalization scale.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point {
+    /// The number of legs, 1 to 4.
+    pub legs: usize,
+    /// The renormalization scale `μ`.
+    pub mu: f64,
+    /// The squared momenta, as for the scalar function with `legs` legs.
+    pub momenta: Vec<f64>,
+    /// The squared masses.
+    pub masses: Vec<Complex64>,
+    /// The line of the header, counting from 1.
+    pub line: usize,
+}
+
+impl Point {
+    /// Evaluates the scalar function at the current renormalization scale,
+    /// which is not set to `mu`.
+    pub fn evaluate(&self) -> OLOResult {
+        let (p, m) = (&self.momenta, &self.masses);
+        match self.legs {
+            1 => one_point(m[0]),
+            2 => two_point(p[0], m[0], m[1]),
+            3 => three_point(p[0], p[1], p[2], m[0], m[1], m[2]),
+            _ => four_point(p[0], p[1], p[2], p[3], p[4], p[5], m[0], m[1], m[2], m[3]),
+        }
+    }
+}
+
+/// The number of squared momenta of a function with `legs` legs.
+fn momentum_count(legs: usize) -> usize {
+    match legs {
+        1 => 0,
+        2 => 1,
+        3 => 3,
+        _ => 6,
+    }
+}
+
+/// Parses all points of `text`.
+pub fn parse(text: &str) -> Result<Vec<Point>, InputError> {
+    let mut points = Vec::new();
+    let mut current: Option<(Point, usize)> = None;
+    for (index, line) in text.lines().enumerate() {
+        let number = index + 1;
+        let line = line.split('!').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line == "next" || line == "stop" {
+            if let Some((point, expected)) = current.take() {
+                points.push(finish(point, expected, number)?);
+            }
+            if line == "stop" {
+                return Ok(points);
+            }
+            continue;
+        }
+        match &mut current {
+            None => current = Some(header(line, number)?),
+            Some((point, expected)) => {
+                let value = complex(line).ok_or(InputError::InvalidValue { line: number })?;
+                let found = point.momenta.len() + point.masses.len();
+                if found == *expected {
+                    return Err(InputError::WrongValueCount { line: number, expected: *expected, found: found + 1 });
+                }
+                if point.momenta.len() < momentum_count(point.legs) {
+                    if value.im != 0.0 {
+                        return Err(InputError::ComplexMomentum { line: number });
+                    }
+                    point.momenta.push(value.re);
+                } else {
+                    point.masses.push(value);
+                }
+            }
+        }
+    }
+    if let Some((point, expected)) = current {
+        points.push(finish(point, expected, text.lines().count())?);
+    }
+    Ok(points)
+}
+
+/// Starts a point from its `nleg mu` line, returning the expected number of
+/// values.
+fn header(line: &str, number: usize) -> Result<(Point, usize), InputError> {
+    let invalid = InputError::InvalidHeader { line: number };
+    let mut fields = line.split_whitespace();
+    let legs: usize = fields.next().and_then(|f| f.parse().ok()).ok_or(invalid.clone())?;
+    let mu = fields.next().and_then(real).ok_or(invalid.clone())?;
+    if fields.next().is_some() {
+        return Err(invalid);
+    }
+    if !(1..=4).contains(&legs) {
+        return Err(InputError::UnsupportedLegs { line: number, legs });
+    }
+    let point = Point { legs, mu, momenta: Vec::new(), masses: Vec::new(), line: number };
+    Ok((point, momentum_count(legs) + legs))
+}
+
+fn finish(point: Point, expected: usize, number: usize) -> Result<Point, InputError> {
+    let found = point.momenta.len() + point.masses.len();
+    if found != expected {
+        return Err(InputError::WrongValueCount { line: number, expected, found });
+    }
+    Ok(point)
+}
+
+/// Parses `(re, im)` or a real number.
+fn complex(field: &str) -> Option<Complex64> {
+    match field.strip_prefix('(').and_then(|f| f.strip_suffix(')')) {
+        Some(inner) => {
+            let (re, im) = inner.split_once(',')?;
+            Some(Complex64::new(real(re)?, real(im)?))
+        }
+        None => real(field).map(Complex64::from),
+    }
+}
+
+/// Parses a real number, also with a Fortran `d` exponent.
+fn real(field: &str) -> Option<f64> {
+    field.trim().replace(['d', 'D'], "e").parse().ok()
+}
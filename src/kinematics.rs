@@ -0,0 +1,406 @@
+//! Kinematic matrices of one-loop integrals and classification of singular
+//! configurations.
+//!
+//! An `N`-point integral with propagators `[(q+P_i)² - m_i]`, `P_0 = 0`, is
+//! fully described by the squared masses `m_i` and the invariants
+//! `r_ij = (P_i - P_j)²`. From these we build
+//!
+//! ```text
+//! Y_ij = m_i + m_j - r_ij           (modified Cayley matrix, i, j = 0..N-1)
+//! Z_kl = r_0k + r_0l - r_kl = 2 P_k·P_l   (Gram matrix, k, l = 1..N-1)
+//! ```
+//!
+//! The integral has a Landau singularity where `det Y` of the full diagram or
+//! of a sub-diagram vanishes with a solution `Y α = 0`, `α_i > 0`. A vanishing
+//! Gram determinant does not make the scalar integral singular, but makes the
+//! standard tensor reduction unstable.
+use crate::onshell_threshold;
+use num_complex::Complex64;
+
+/// Relative tolerance used when no on-shell threshold has been set, the
+/// same as OneLOop's default for double precision.
+pub const DEFAULT_RELATIVE_TOLERANCE: f64 = 64.0 * f64::EPSILON;
+
+/// A singular feature of a kinematic configuration. Propagators are numbered
+/// by their position in `Kinematics::masses`, starting from zero.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Singularity {
+    /// A massless propagator between two on-shell legs: soft (IR) divergence.
+    Soft { propagator: usize },
+    /// A massless leg between two massless propagators: collinear (IR) divergence.
+    Collinear { propagators: (usize, usize) },
+    /// An invariant at the normal threshold `(√m_i + √m_j)²` of the two-particle
+    /// cut through the given propagators.
+    Threshold { propagators: (usize, usize) },
+    /// A leading Landau singularity of the sub-diagram with the given (three or
+    /// more) propagators.
+    AnomalousThreshold { propagators: Vec<usize> },
+}
+
+/// The masses and invariants of a one-loop integral.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kinematics {
+    masses: Vec<Complex64>,
+    invariants: Vec<Vec<f64>>,
+}
+
+impl Kinematics {
+    /// Creates the kinematics from the squared masses `m_i` and the symmetric
+    /// matrix of invariants `invariants[i][j] = (P_i - P_j)²`.
+    ///
+    /// # Panics
+    /// If `invariants` is not a square matrix of the same size as `masses`.
+    pub fn new(masses: Vec<impl Into<Complex64>>, invariants: Vec<Vec<f64>>) -> Self {
+        let masses: Vec<Complex64> = masses.into_iter().map(Into::into).collect();
+        assert!(
+            invariants.len() == masses.len() && invariants.iter().all(|r| r.len() == masses.len()),
+            "expected a {0}x{0} matrix of invariants",
+            masses.len()
+        );
+        Self { masses, invariants }
+    }
+
+    /// The kinematics of `two_point`.
+    pub fn two_point(p: f64, m1: impl Into<Complex64>, m2: impl Into<Complex64>) -> Self {
+        Self::new(vec![m1.into(), m2.into()], vec![vec![0.0, p], vec![p, 0.0]])
+    }
+
+    /// The kinematics of `three_point`.
+    pub fn three_point(
+        p1: f64,
+        p2: f64,
+        p3: f64,
+        m1: impl Into<Complex64>,
+        m2: impl Into<Complex64>,
+        m3: impl Into<Complex64>,
+    ) -> Self {
+        Self::new(
+            vec![m1.into(), m2.into(), m3.into()],
+            vec![
+                vec![0.0, p1, p3],
+                vec![p1, 0.0, p2],
+                vec![p3, p2, 0.0],
+            ],
+        )
+    }
+
+    /// The kinematics of `four_point`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn four_point(
+        p1: f64,
+        p2: f64,
+        p3: f64,
+        p4: f64,
+        p12: f64,
+        p23: f64,
+        m1: impl Into<Complex64>,
+        m2: impl Into<Complex64>,
+        m3: impl Into<Complex64>,
+        m4: impl Into<Complex64>,
+    ) -> Self {
+        Self::new(
+            vec![m1.into(), m2.into(), m3.into(), m4.into()],
+            vec![
+                vec![0.0, p1, p12, p4],
+                vec![p1, 0.0, p2, p23],
+                vec![p12, p2, 0.0, p3],
+                vec![p4, p23, p3, 0.0],
+            ],
+        )
+    }
+
+    /// The kinematics of `five_point`, with the invariants in the same order.
+    pub fn five_point(p: [f64; 10], m: [impl Into<Complex64>; 5]) -> Self {
+        let [p1, p2, p3, p4, p5, s12, s23, s34, s45, s51] = p;
+        Self::new(
+            m.map(Into::into).to_vec(),
+            vec![
+                vec![0.0, p1, s12, s45, p5],
+                vec![p1, 0.0, p2, s23, s51],
+                vec![s12, p2, 0.0, p3, s34],
+                vec![s45, s23, p3, 0.0, p4],
+                vec![p5, s51, s34, p4, 0.0],
+            ],
+        )
+    }
+
+    /// The kinematics of `six_point`, with the invariants in the same order.
+    pub fn six_point(p: [f64; 15], m: [impl Into<Complex64>; 6]) -> Self {
+        let [p1, p2, p3, p4, p5, p6, s12, s23, s34, s45, s56, s61, s123, s234, s345] = p;
+        Self::new(
+            m.map(Into::into).to_vec(),
+            vec![
+                vec![0.0, p1, s12, s123, s56, p6],
+                vec![p1, 0.0, p2, s23, s234, s61],
+                vec![s12, p2, 0.0, p3, s34, s345],
+                vec![s123, s23, p3, 0.0, p4, s45],
+                vec![s56, s234, s34, p4, 0.0, p5],
+                vec![p6, s61, s345, s45, p5, 0.0],
+            ],
+        )
+    }
+
+    /// Number of propagators.
+    pub fn size(&self) -> usize {
+        self.masses.len()
+    }
+
+    /// The squared masses of the propagators.
+    pub fn masses(&self) -> &[Complex64] {
+        &self.masses
+    }
+
+    /// The invariants `(P_i - P_j)²`.
+    pub fn invariants(&self) -> &[Vec<f64>] {
+        &self.invariants
+    }
+
+    /// The sub-diagram built from the given propagators, in the given order.
+    pub fn pinch(&self, keep: &[usize]) -> Self {
+        Self {
+            masses: keep.iter().map(|&i| self.masses[i]).collect(),
+            invariants: keep
+                .iter()
+                .map(|&i| keep.iter().map(|&j| self.invariants[i][j]).collect())
+                .collect(),
+        }
+    }
+
+    /// The largest absolute value among masses and invariants.
+    pub fn scale(&self) -> f64 {
+        self.masses
+            .iter()
+            .map(|m| m.norm())
+            .chain(self.invariants.iter().flatten().map(|r| r.abs()))
+            .fold(0.0, f64::max)
+    }
+
+    /// The modified Cayley matrix `Y_ij = m_i + m_j - (P_i - P_j)²`.
+    pub fn cayley_matrix(&self) -> Vec<Vec<Complex64>> {
+        let m = &self.masses;
+        (0..self.size())
+            .map(|i| (0..self.size()).map(|j| m[i] + m[j] - self.invariants[i][j]).collect())
+            .collect()
+    }
+
+    /// The determinant of the modified Cayley matrix.
+    pub fn cayley_determinant(&self) -> Complex64 {
+        complex_determinant(&self.cayley_matrix())
+    }
+
+    /// The Gram matrix `Z_kl = 2 P_k·P_l` for `k, l = 1..N-1`.
+    pub fn gram_matrix(&self) -> Vec<Vec<f64>> {
+        let r = &self.invariants;
+        (1..self.size())
+            .map(|k| (1..self.size()).map(|l| r[0][k] + r[0][l] - r[k][l]).collect())
+            .collect()
+    }
+
+    /// The determinant of the Gram matrix.
+    pub fn gram_determinant(&self) -> f64 {
+        determinant(&self.gram_matrix())
+    }
+
+    /// The absolute tolerance used by `classify`: the on-shell threshold if
+    /// one has been set with `set_onshell_threshold`, and otherwise
+    /// `DEFAULT_RELATIVE_TOLERANCE` times `scale`.
+    pub fn tolerance(&self) -> f64 {
+        onshell_threshold().unwrap_or(DEFAULT_RELATIVE_TOLERANCE * self.scale())
+    }
+
+    /// Lists the singular features of this configuration, see `classify_with_tolerance`.
+    pub fn classify(&self) -> Vec<Singularity> {
+        self.classify_with_tolerance(self.tolerance())
+    }
+
+    /// Returns `true` if `classify` finds no singular features.
+    pub fn is_regular(&self) -> bool {
+        self.classify().is_empty()
+    }
+
+    /// Lists the singular features of this configuration. Masses and
+    /// invariants within `tolerance` of a singular value count as singular;
+    /// determinants are compared with the same tolerance scaled by the
+    /// appropriate power of `scale`. An empty list means the configuration is
+    /// regular.
+    pub fn classify_with_tolerance(&self, tolerance: f64) -> Vec<Singularity> {
+        let n = self.size();
+        let m = &self.masses;
+        let r = &self.invariants;
+        let scale = self.scale().max(tolerance);
+        let mut singularities = Vec::new();
+
+        // IR divergences need an external leg between neighbouring propagators
+        if n >= 3 {
+            for k in 0..n {
+                let (before, after) = ((k + n - 1) % n, (k + 1) % n);
+                if m[k].norm() < tolerance
+                    && (r[before][k] - m[before]).norm() < tolerance
+                    && (r[k][after] - m[after]).norm() < tolerance
+                {
+                    singularities.push(Singularity::Soft { propagator: k });
+                }
+            }
+            for k in 0..n {
+                let after = (k + 1) % n;
+                if r[k][after].abs() < tolerance
+                    && m[k].norm() < tolerance
+                    && m[after].norm() < tolerance
+                {
+                    singularities.push(Singularity::Collinear { propagators: (k, after) });
+                }
+            }
+        }
+
+        // Landau singularities of all sub-diagrams with two or more propagators
+        let cayley = self.cayley_matrix();
+        for size in 2..=n {
+            for subset in subsets(n, size) {
+                let sub: Vec<Vec<Complex64>> = subset
+                    .iter()
+                    .map(|&i| subset.iter().map(|&j| cayley[i][j]).collect())
+                    .collect();
+                let det = complex_determinant(&sub);
+                if det.norm() >= tolerance * scale.powi(size as i32 - 1) {
+                    continue;
+                }
+                if !has_positive_solution(&sub, tolerance / scale) {
+                    continue;
+                }
+                singularities.push(if size == 2 {
+                    Singularity::Threshold { propagators: (subset[0], subset[1]) }
+                } else {
+                    Singularity::AnomalousThreshold { propagators: subset }
+                });
+            }
+        }
+        singularities
+    }
+
+    /// Returns `true` if the Gram determinant vanishes within `tolerance`,
+    /// scaled like in `classify`. The scalar integral is not singular there,
+    /// but its tensor reduction needs the expansions of the `pv` module.
+    pub fn has_vanishing_gram_determinant(&self) -> bool {
+        let tolerance = self.tolerance();
+        let scale = self.scale().max(tolerance);
+        self.size() >= 2
+            && self.gram_determinant().abs() < tolerance * scale.powi(self.size() as i32 - 2)
+    }
+}
+
+/// Checks whether the null space of a singular Cayley matrix contains a
+/// vector with all components non-zero and of the same sign.
+fn has_positive_solution(matrix: &[Vec<Complex64>], tolerance: f64) -> bool {
+    let adjugate = complex_adjugate(matrix);
+    let alpha = adjugate
+        .iter()
+        .max_by(|a, b| norm(a).total_cmp(&norm(b)))
+        .unwrap();
+    let largest = alpha.iter().map(|a| a.norm()).fold(0.0, f64::max);
+    if largest == 0.0 {
+        return false;
+    }
+    let sign = alpha[0].re.signum();
+    alpha
+        .iter()
+        .all(|a| a.norm() > tolerance * largest && a.re.signum() == sign)
+}
+
+fn norm(v: &[Complex64]) -> f64 {
+    v.iter().map(|a| a.norm_sqr()).sum()
+}
+
+/// All increasing sequences of length `len` over `0..n`.
+fn subsets(n: usize, len: usize) -> Vec<Vec<usize>> {
+    if len == 0 {
+        return vec![vec![]];
+    }
+    (len - 1..n)
+        .flat_map(|last| {
+            subsets(last, len - 1).into_iter().map(move |mut s| {
+                s.push(last);
+                s
+            })
+        })
+        .collect()
+}
+
+/// Determinant by Gaussian elimination with partial pivoting.
+pub(crate) fn determinant(matrix: &[Vec<f64>]) -> f64 {
+    let n = matrix.len();
+    let mut a = matrix.to_vec();
+    let mut determinant = 1.0;
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+            .unwrap();
+        if pivot != col {
+            a.swap(pivot, col);
+            determinant = -determinant;
+        }
+        let p = a[col][col];
+        determinant *= p;
+        if p == 0.0 {
+            return 0.0;
+        }
+        let pivot_row = a[col].clone();
+        for row in a.iter_mut().skip(col + 1) {
+            let factor = row[col] / p;
+            for (x, y) in row.iter_mut().zip(&pivot_row).skip(col) {
+                *x -= factor * y;
+            }
+        }
+    }
+    determinant
+}
+
+/// Complex determinant by Gaussian elimination with partial pivoting.
+pub(crate) fn complex_determinant(matrix: &[Vec<Complex64>]) -> Complex64 {
+    let n = matrix.len();
+    let mut a = matrix.to_vec();
+    let mut determinant = Complex64::new(1.0, 0.0);
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i][col].norm().total_cmp(&a[j][col].norm()))
+            .unwrap();
+        if pivot != col {
+            a.swap(pivot, col);
+            determinant = -determinant;
+        }
+        let p = a[col][col];
+        determinant *= p;
+        if p == Complex64::new(0.0, 0.0) {
+            return p;
+        }
+        let pivot_row = a[col].clone();
+        for row in a.iter_mut().skip(col + 1) {
+            let factor = row[col] / p;
+            for (x, y) in row.iter_mut().zip(&pivot_row).skip(col) {
+                *x -= factor * y;
+            }
+        }
+    }
+    determinant
+}
+
+/// The adjugate matrix, built from cofactors so that it stays finite for
+/// singular matrices.
+pub(crate) fn complex_adjugate(matrix: &[Vec<Complex64>]) -> Vec<Vec<Complex64>> {
+    let n = matrix.len();
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    let minor: Vec<Vec<Complex64>> = (0..n)
+                        .filter(|&row| row != j)
+                        .map(|row| {
+                            (0..n).filter(|&col| col != i).map(|col| matrix[row][col]).collect()
+                        })
+                        .collect();
+                    let sign = if (i + j) % 2 == 0 { 1.0 } else { -1.0 };
+                    complex_determinant(&minor) * sign
+                })
+                .collect()
+        })
+        .collect()
+}
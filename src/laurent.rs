@@ -0,0 +1,393 @@
+//! Laurent series in `ε` of arbitrary length, and the 1- and 2-point
+//! functions to any positive order in `ε`.
+//!
+//! In the normalization of OneLOop, with `r_Γ = Γ²(1-ε)Γ(1+ε)/Γ(1-2ε)`,
+//!
+//! ```text
+//! A0(m)         = m (m/μ²)^(-ε) Γ(1-2ε)/Γ²(1-ε) / (ε(1-ε))
+//! B0(p, m1, m2) = Γ(1-2ε)/Γ²(1-ε) / ε ∫₀¹ dx (Δ(x)/μ²)^(-ε)
+//! ```
+//!
+//! with `Δ(x) = x m2 + (1-x) m1 - x(1-x) p - i0`. The tadpole is expanded in
+//! closed form. For the bubble the integrals of `lnᵏ Δ` are computed by
+//! double-exponential quadrature, split at the zeros of `Δ`, which leaves
+//! the `ε⁻¹` and `ε⁰` coefficients equal to those of `two_point` up to
+//! about `1e-14`.
+use crate::{OLOResult, onshell_threshold, renormalization_scale};
+use num_complex::Complex64;
+use std::f64::consts::PI;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A truncated Laurent series `Σ cₖ εᵏ` for `k` from `leading_order()`
+/// through `order()`.
+///
+/// Sums and products are truncated at the lowest order known for both
+/// operands.
+#[derive(Clone, Default, PartialEq)]
+pub struct Laurent {
+    leading: i32,
+    coefficients: Vec<Complex64>,
+}
+
+impl Laurent {
+    /// Creates the series `Σ coefficients[i] ε^(leading + i)`.
+    pub fn new(leading: i32, coefficients: Vec<Complex64>) -> Self {
+        Self { leading, coefficients }
+    }
+
+    /// The power of `ε` of the first coefficient.
+    pub fn leading_order(&self) -> i32 {
+        self.leading
+    }
+
+    /// The highest power of `ε` that is known.
+    pub fn order(&self) -> i32 {
+        self.leading + self.coefficients.len() as i32 - 1
+    }
+
+    /// The coefficients from `leading_order()` through `order()`.
+    pub fn coefficients(&self) -> &[Complex64] {
+        &self.coefficients
+    }
+
+    /// The coefficient of `εᵏ`, zero outside the stored range.
+    pub fn coefficient(&self, k: i32) -> Complex64 {
+        usize::try_from(k - self.leading)
+            .ok()
+            .and_then(|i| self.coefficients.get(i).copied())
+            .unwrap_or_default()
+    }
+
+    /// Drops the terms beyond `εᵏ` for `k = order`.
+    pub fn truncate(mut self, order: i32) -> Self {
+        self.coefficients.truncate((order - self.leading + 1).max(0) as usize);
+        self
+    }
+
+    /// The series with coefficients `f(k)` for `k` in `leading..=order`.
+    fn from_fn(leading: i32, order: i32, f: impl FnMut(i32) -> Complex64) -> Self {
+        Self::new(leading, (leading..=order).map(f).collect())
+    }
+}
+
+impl From<OLOResult> for Laurent {
+    fn from(r: OLOResult) -> Self {
+        Laurent::new(-2, vec![r.epsilon_minus_2(), r.epsilon_minus_1(), r.epsilon_0()])
+    }
+}
+
+impl Add for Laurent {
+    type Output = Laurent;
+
+    fn add(self, rhs: Laurent) -> Laurent {
+        let order = self.order().min(rhs.order());
+        let leading = self.leading.min(rhs.leading);
+        Laurent::from_fn(leading, order, |k| self.coefficient(k) + rhs.coefficient(k))
+    }
+}
+
+impl Sub for Laurent {
+    type Output = Laurent;
+
+    fn sub(self, rhs: Laurent) -> Laurent {
+        self + -rhs
+    }
+}
+
+impl Neg for Laurent {
+    type Output = Laurent;
+
+    fn neg(self) -> Laurent {
+        self * -1.0
+    }
+}
+
+impl Mul for Laurent {
+    type Output = Laurent;
+
+    fn mul(self, rhs: Laurent) -> Laurent {
+        let leading = self.leading + rhs.leading;
+        let order = (self.order() + rhs.leading).min(rhs.order() + self.leading);
+        Laurent::from_fn(leading, order, |k| {
+            (self.leading..=k - rhs.leading)
+                .map(|i| self.coefficient(i) * rhs.coefficient(k - i))
+                .sum()
+        })
+    }
+}
+
+impl Mul<Complex64> for Laurent {
+    type Output = Laurent;
+
+    fn mul(mut self, rhs: Complex64) -> Laurent {
+        self.coefficients.iter_mut().for_each(|c| *c *= rhs);
+        self
+    }
+}
+
+impl Mul<f64> for Laurent {
+    type Output = Laurent;
+
+    fn mul(self, rhs: f64) -> Laurent {
+        self * Complex64::from(rhs)
+    }
+}
+
+impl fmt::Display for Laurent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, c) in self.coefficients.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "ε{}: {}", superscript(self.leading + i as i32), c)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Laurent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Laurent")
+            .field("leading", &self.leading)
+            .field("coefficients", &self.coefficients)
+            .finish()
+    }
+}
+
+fn superscript(k: i32) -> String {
+    const DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+    let decimal = k.unsigned_abs().to_string();
+    let digits = decimal.chars().map(|d| DIGITS[d as usize - '0' as usize]);
+    if k < 0 { std::iter::once('⁻').chain(digits).collect() } else { digits.collect() }
+}
+
+/// Computes the 1-point function, see `one_point`, through `ε^order`.
+pub fn one_point(m: impl Into<Complex64>, order: u32) -> Laurent {
+    let m = m.into();
+    let order = order as i32;
+    if m.norm() == 0.0 || onshell_threshold().is_some_and(|threshold| m.norm() < threshold) {
+        return Laurent::from_fn(-1, order, |_| Complex64::default());
+    }
+    let l = log(m / renormalization_scale().powi(2));
+    // m/(ε(1-ε)) (m/μ²)^(-ε)
+    let tadpole = Laurent::from_fn(-1, order, |_| m) * exponential(-l, order + 1);
+    tadpole * gamma_ratio(order + 1)
+}
+
+/// Computes the 2-point function, see `two_point`, through `ε^order`.
+pub fn two_point(p: f64, m1: impl Into<Complex64>, m2: impl Into<Complex64>, order: u32) -> Laurent {
+    let order = order as i32;
+    let (m1, m2): (Complex64, Complex64) = (m1.into(), m2.into());
+    // As OneLOop, flip the sign of a positive imaginary part
+    let mut m1 = Complex64::new(m1.re, -m1.im.abs());
+    let mut m2 = Complex64::new(m2.re, -m2.im.abs());
+    let mut p = p;
+    if let Some(threshold) = onshell_threshold() {
+        if p.abs() < threshold {
+            p = 0.0;
+        }
+        for m in [&mut m1, &mut m2] {
+            if m.re.abs() + m.im.abs() < threshold {
+                *m = Complex64::default();
+            }
+        }
+    }
+    let zero = Complex64::default();
+    if p == 0.0 && m1 == zero && m2 == zero {
+        return Laurent::from_fn(-1, order, |_| zero);
+    }
+
+    // ∫₀¹ dx (Δ/μ²)^(-ε)/ε = Σₖ (-1)ᵏ/k! εᵏ⁻¹ ∫₀¹ dx lnᵏ(Δ/μ²)
+    let delta = Quadratic::new(p, m1, m2);
+    let scale = renormalization_scale().powi(2);
+    let moments = delta.log_moments(scale, order as usize + 1);
+    let mut factorial = 1.0;
+    let integral = Laurent::new(
+        -1,
+        moments
+            .iter()
+            .enumerate()
+            .map(|(k, &moment)| {
+                factorial *= k.max(1) as f64;
+                moment * (-1.0f64).powi(k as i32) / factorial
+            })
+            .collect(),
+    );
+    // The ε⁻¹ of Γ(ε) is absorbed in the leading order of the integral
+    integral * gamma_ratio(order + 1)
+}
+
+/// `ln(x)`, taking a negative real `x` as `x - i0`.
+fn log(x: Complex64) -> Complex64 {
+    Complex64::new(x.re, -x.im.abs()).ln()
+}
+
+/// `exp(a ε)` through `ε^order`.
+fn exponential(a: Complex64, order: i32) -> Laurent {
+    let mut term = Complex64::from(1.0);
+    Laurent::from_fn(0, order, |k| {
+        if k > 0 {
+            term *= a / k as f64;
+        }
+        term
+    })
+}
+
+/// `Γ(1-2ε)/Γ²(1-ε) = exp(Σₖ ζ(k)(2ᵏ-2)/k εᵏ)` through `ε^order`.
+fn gamma_ratio(order: i32) -> Laurent {
+    let order = order.max(0) as usize;
+    let exponent: Vec<f64> = (0..=order)
+        .map(|k| if k < 2 { 0.0 } else { zeta(k as i32) * (2f64.powi(k as i32) - 2.0) / k as f64 })
+        .collect();
+    // y = exp(s) solves n yₙ = Σₖ k sₖ yₙ₋ₖ
+    let mut y = vec![1.0];
+    for n in 1..=order {
+        let sum: f64 = (1..=n).map(|k| k as f64 * exponent[k] * y[n - k]).sum();
+        y.push(sum / n as f64);
+    }
+    Laurent::new(0, y.into_iter().map(Complex64::from).collect())
+}
+
+/// The Riemann zeta function for `s ≥ 2`, by Borwein's alternating series.
+fn zeta(s: i32) -> f64 {
+    const N: usize = 24;
+    let mut term = 1.0;
+    let mut sum = 0.0;
+    let d: Vec<f64> = (0..=N)
+        .map(|i| {
+            if i > 0 {
+                term *= ((N + i - 1) * (N - i + 1)) as f64 * 4.0 / ((2 * i - 1) * (2 * i)) as f64;
+            }
+            sum += term;
+            sum
+        })
+        .collect();
+    let eta: f64 = (0..N)
+        .map(|k| if k % 2 == 0 { 1.0 } else { -1.0 } * (d[N] - d[k]) / ((k + 1) as f64).powi(s))
+        .sum::<f64>()
+        / d[N];
+    eta / (1.0 - 2f64.powi(1 - s))
+}
+
+/// `Δ(x) = c (x - r₁)(x - r₂)…` on `[0, 1]`.
+struct Quadratic {
+    c: Complex64,
+    roots: Vec<Complex64>,
+}
+
+impl Quadratic {
+    /// Factorizes `Δ(x) = p x² + (m2 - m1 - p) x + m1`, with the roots at the
+    /// endpoints exact for vanishing masses.
+    fn new(p: f64, m1: Complex64, m2: Complex64) -> Self {
+        let zero = Complex64::default();
+        let pp = Complex64::from(p);
+        if p == 0.0 {
+            if m1 == m2 {
+                return Quadratic { c: m1, roots: vec![] };
+            }
+            return Quadratic { c: m2 - m1, roots: vec![m1 / (m1 - m2)] };
+        }
+        let roots = if m1 == zero {
+            vec![zero, 1.0 - m2 / pp]
+        } else if m2 == zero {
+            vec![Complex64::from(1.0), m1 / pp]
+        } else {
+            let b = m2 - m1 - pp;
+            let d = (b * b - 4.0 * pp * m1).sqrt();
+            let q = if (b.conj() * d).re >= 0.0 { -(b + d) / 2.0 } else { -(b - d) / 2.0 };
+            vec![q / pp, m1 / q]
+        };
+        Quadratic { c: pp, roots }
+    }
+
+    /// `∫₀¹ dx lnᵏ(Δ(x)/scale)` for `k = 0..=n`.
+    fn log_moments(&self, scale: f64, n: usize) -> Vec<Complex64> {
+        let mut points: Vec<f64> = self
+            .roots
+            .iter()
+            .map(|r| r.re)
+            .filter(|&x| x > 0.0 && x < 1.0)
+            .collect();
+        points.extend([0.0, 1.0]);
+        points.sort_by(f64::total_cmp);
+        points.dedup();
+
+        let mut moments = vec![Complex64::default(); n + 1];
+        for interval in points.windows(2) {
+            let (a, b) = (interval[0], interval[1]);
+            // Measure the distance to a root at an endpoint exactly, and sum
+            // the logarithms of the factors, whose product may underflow
+            let integrand = |x: f64, da: f64, db: f64| {
+                let (mut modulus, mut phase) = ((self.c / scale).norm().ln(), self.c / self.c.norm());
+                for &r in &self.roots {
+                    let dx = if r == Complex64::from(a) {
+                        Complex64::from(da)
+                    } else if r == Complex64::from(b) {
+                        Complex64::from(-db)
+                    } else {
+                        x - r
+                    };
+                    modulus += dx.norm().ln();
+                    phase *= dx / dx.norm();
+                }
+                let l = modulus + log(phase);
+                let mut power = Complex64::from(1.0);
+                (0..=n)
+                    .map(|_| {
+                        let value = power;
+                        power *= l;
+                        value
+                    })
+                    .collect::<Vec<_>>()
+            };
+            for (moment, value) in moments.iter_mut().zip(tanh_sinh(a, b, integrand)) {
+                *moment += value;
+            }
+        }
+        moments
+    }
+}
+
+/// Integrates the components of `f(x, x - a, b - x)` over `[a, b]` by
+/// tanh-sinh quadrature, halving the step until it converges.
+fn tanh_sinh(a: f64, b: f64, f: impl Fn(f64, f64, f64) -> Vec<Complex64>) -> Vec<Complex64> {
+    const MAX_LEVEL: i32 = 12;
+    let half = (b - a) / 2.0;
+    let mut sum = f(a + half, half, half).into_iter().map(|v| v * PI / 2.0).collect::<Vec<_>>();
+    let mut previous: Option<Vec<Complex64>> = None;
+    let mut h = 1.0;
+    for level in 0..=MAX_LEVEL {
+        // New nodes: all k for level 0, odd k after that
+        let (start, stride) = if level == 0 { (1, 1) } else { (1, 2) };
+        let mut k = start;
+        loop {
+            let t = k as f64 * h;
+            let u = PI / 2.0 * t.sinh();
+            let e = (-2.0 * u).exp();
+            // 1 - tanh(u) and the weight π/2 cosh(t)/cosh²(u)
+            let distance = half * 2.0 * e / (1.0 + e);
+            let weight = PI / 2.0 * t.cosh() * 4.0 * e / ((1.0 + e) * (1.0 + e));
+            if distance == 0.0 || weight < 1e-300 {
+                break;
+            }
+            let left = f(a + distance, distance, 2.0 * half - distance);
+            let right = f(b - distance, 2.0 * half - distance, distance);
+            for ((s, l), r) in sum.iter_mut().zip(left).zip(right) {
+                *s += weight * (l + r);
+            }
+            k += stride;
+        }
+        let estimate: Vec<Complex64> = sum.iter().map(|s| s * h * half).collect();
+        if let Some(previous) = &previous {
+            // The error after a step is about the square of the change
+            let converged = estimate.iter().zip(previous).all(|(e, p)| (e - p).norm() <= 1e-10 * e.norm());
+            if converged && level > 3 {
+                return estimate;
+            }
+        }
+        previous = Some(estimate);
+        h /= 2.0;
+    }
+    previous.unwrap()
+}
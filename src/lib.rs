@@ -9,12 +9,61 @@
 //!   - `two_point` → 2-point (bubble) function
 //!   - `three_point` → 3-point (triangle) function
 //!   - `four_point` → 4-point (box) function
+//!   - `five_point`, `six_point` → 5- and 6-point functions, reduced to boxes
+//!   - `three_point_d6`, `four_point_d6` → triangle and box in `6 - 2ε` dimensions
 //! - Returns `OLOResult` with Laurent expansion coefficients:
 //!   - `ε⁰` → finite term
 //!   - `ε⁻¹` → first-order divergence (zero if finite)
 //!   - `ε⁻²` → second-order divergence (zero if finite)
+//! - Cayley and Gram matrices and classification of singular configurations
+//!   in the `kinematics` module.
+//! - The OneLOop routine (and hence the poles) a 3- or 4-point input maps to,
+//!   in the `dispatch` module.
+//! - Per-call on-shell snapping of the inputs in the `snap` module.
+//! - Passarino-Veltman reduction of 3- and 4-point tensor integrals in the `pv` module.
 //! - Conversion to standard Feynman-diagram normalization via `TO_FEYNMAN`.
-//! - Configurable logging, renormalization scale and on-shell thresholds.
+//! - Pure-Rust `one_point`, `two_point` and finite `three_point` in the
+//!   `native` module, used instead of the Fortran with the `pure-rust` feature.
+//! - `one_point` and `two_point` through any positive order in `ε`, as a
+//!   variable-length `Laurent` series, in the `laurent` module.
+//! - OneLOop's complex logarithm and dilogarithm with explicit sheet index
+//!   (`olog`, `dilog`, `dilog2`, `li2`, `bnlog`) in the `qmplx` module.
+//! - `B0`, `B1` and `B00` at complex `p²` on a chosen Riemann sheet, e.g. at a
+//!   complex pole `M² - iMΓ`, in the `complex_momentum` module.
+//! - Exchangeable evaluation of the 1- to 4-point functions through the
+//!   `ScalarIntegralBackend` trait in the `backend` module, e.g. for mocks or
+//!   caching decorators.
+//! - Derivatives of the 2- to 4-point functions with respect to any mass or
+//!   invariant, with an error estimate, in the `derivative` module.
+//! - Squared masses `m² - imΓ` of unstable propagators, checked for the sign
+//!   of the width, as the `Mass` type of the `mass` module, accepted by all
+//!   scalar functions.
+//! - Numerical evaluation of finite triangles and boxes from their
+//!   Feynman-parameter representation, with an error estimate, in the
+//!   `numeric` module.
+//! - The 1- to 4-point functions of the vendored ONI, with numerical
+//!   integration of the finite parts, in the `oni` module with the `oni`
+//!   feature.
+//! - Parsing of OneLOop's example input format in the `input` module.
+//! - Scans of the 1- to 4-point functions over one or more inputs on linear or
+//!   logarithmic grids, written as CSV or JSON lines, in the `scan` module.
+//! - Adaptive interpolation tables of the scalar functions along one or two
+//!   inputs, with an error check and direct evaluation near thresholds, in
+//!   the `table` module.
+//! - The `olo` command-line tool, evaluating one integral, a scan or a file
+//!   of points, with text, JSON or CSV output.
+//! - Configurable logging, renormalization scale and on-shell thresholds, with
+//!   snapshots of the settings as `Settings`.
+//! - Comparison of results within a relative tolerance with
+//!   `OLOResult::agrees_with`, reporting the pole order that disagrees, and
+//!   the `approx` traits with the `approx` feature.
+//! - Serialization of `OLOResult`, `OLOUnit`, `Settings` and the inputs
+//!   (`Mass`, `input::Point`, `scan::Range`) with the `serde` feature.
+//! - Results and points as Mathematica, FORM or LaTeX expressions, e.g.
+//!   `OLOResult::to_mathematica`, in the `export` module.
+//! - Evaluation of linear combinations of `A0`, `B0`, `B1`, `B00`, `DB0`, `C0`
+//!   and `D0` written as in LoopTools or FeynCalc, with symbols bound to
+//!   numbers, in the `expression` module.
 //! 
 //! ## Example
 //! 
@@ -36,6 +85,33 @@ use core::f64;
 use num_complex::Complex64;
 use std::{f64::consts::PI, fmt};
 use std::ffi::CString;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::sync::Mutex;
+
+use backend::{Builtin, ScalarIntegralBackend};
+use complex_momentum::TwoPointCoefficients;
+use kinematics::Kinematics;
+
+pub mod backend;
+pub mod complex_momentum;
+pub mod derivative;
+pub mod dispatch;
+pub mod export;
+pub mod expression;
+pub mod input;
+pub mod kinematics;
+pub mod laurent;
+pub mod mass;
+mod melrose;
+pub mod native;
+pub mod numeric;
+#[cfg(feature = "oni")]
+pub mod oni;
+pub mod pv;
+pub mod qmplx;
+pub mod scan;
+pub mod snap;
+pub mod table;
 
 #[cfg(feature = "python")]
 mod python;
@@ -50,6 +126,12 @@ mod python;
 /// Numerically, this is `-1/(16 π^2)`.
 pub const TO_FEYNMAN: f64 = -1.0 / (16.0 * PI * PI);
 
+/// Relative size of a pivot of the modified Cayley matrix below which
+/// `five_point` and `six_point` report the matrix as singular.
+///
+/// The pivot is compared to the largest entry of the matrix.
+pub const CAYLEY_THRESHOLD: f64 = 1e-10;
+
 /// Represents the Laurent expansion coefficients of a one-loop scalar function
 /// in dimensional regularization.
 ///
@@ -59,12 +141,52 @@ pub const TO_FEYNMAN: f64 = -1.0 / (16.0 * PI * PI);
 /// - `values[0]`  ε⁰ coefficient
 /// - `values[1]`  ε⁻¹ coefficient (vanishes for finite cases)
 /// - `values[2]`  ε⁻² coefficient (vanishes for finite cases)
+///
+/// With the `serde` feature it is (de)serialized as a struct with the fields
+/// `epsilon_0`, `epsilon_minus_1` and `epsilon_minus_2`, each a complex number
+/// `[re, im]`.
 #[derive(Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "Coefficients", into = "Coefficients"))]
 pub struct OLOResult {
     values: [Complex64; 3],
 }
 
+/// The serialized layout of `OLOResult`.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "OLOResult", deny_unknown_fields)]
+struct Coefficients {
+    epsilon_0: Complex64,
+    epsilon_minus_1: Complex64,
+    epsilon_minus_2: Complex64,
+}
+
+#[cfg(feature = "serde")]
+impl From<Coefficients> for OLOResult {
+    fn from(c: Coefficients) -> Self {
+        OLOResult::new(c.epsilon_0, c.epsilon_minus_1, c.epsilon_minus_2)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<OLOResult> for Coefficients {
+    fn from(r: OLOResult) -> Self {
+        Coefficients { epsilon_0: r.values[0], epsilon_minus_1: r.values[1], epsilon_minus_2: r.values[2] }
+    }
+}
+
 impl OLOResult {
+    /// Creates a result from its ε⁰, ε⁻¹ and ε⁻² coefficients
+    pub fn new(epsilon_0: Complex64, epsilon_minus_1: Complex64, epsilon_minus_2: Complex64) -> Self {
+        Self { values: [epsilon_0, epsilon_minus_1, epsilon_minus_2] }
+    }
+
+    /// Creates a result from its coefficients, ordered as `[ε⁰, ε⁻¹, ε⁻²]`
+    pub(crate) fn from_values(values: [Complex64; 3]) -> Self {
+        Self { values }
+    }
+
     /// Returns a mutable pointer to the internal values array
     fn as_mut_ptr(&mut self) -> *mut Complex64 {
         self.values.as_mut_ptr()
@@ -84,6 +206,169 @@ impl OLOResult {
     pub fn epsilon_minus_2(&self) -> Complex64 {
         self.values[2]
     }
+
+    /// Checks that every coefficient agrees with that of `other` within
+    /// `rel_tol` relative to the larger of the two, so that zero only agrees
+    /// with zero. Returns the disagreement of the highest pole order, if any.
+    pub fn agrees_with(&self, other: &OLOResult, rel_tol: f64) -> Result<(), Disagreement> {
+        for index in (0..3).rev() {
+            let (left, right) = (self.values[index], other.values[index]);
+            let difference = (left - right).norm();
+            let relative_difference = if difference == 0.0 { 0.0 } else { difference / left.norm().max(right.norm()) };
+            if relative_difference.is_nan() || relative_difference > rel_tol {
+                return Err(Disagreement { order: -(index as i32), left, right, relative_difference });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A coefficient on which two `OLOResult`s disagree, see `agrees_with`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Disagreement {
+    /// The power of `ε` of the coefficient, 0, -1 or -2.
+    pub order: i32,
+    /// The coefficient of the result `agrees_with` was called on.
+    pub left: Complex64,
+    /// The coefficient of the other result.
+    pub right: Complex64,
+    /// The difference relative to the larger coefficient.
+    pub relative_difference: f64,
+}
+
+impl fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let power = ["ε⁰", "ε⁻¹", "ε⁻²"][(-self.order) as usize];
+        write!(
+            f,
+            "{} coefficients differ: {} vs {} (relative difference {:e})",
+            power, self.left, self.right, self.relative_difference
+        )
+    }
+}
+
+impl std::error::Error for Disagreement {}
+
+/// Errors reported by `five_point` and `six_point`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReductionError {
+    /// The modified Cayley matrix of the integral, or of one of the pentagons
+    /// a hexagon is reduced to, is too close to singular for the reduction,
+    /// e.g. for degenerate external momenta.
+    ///
+    /// `relative_pivot` is the smallest pivot divided by the largest entry.
+    SingularCayleyMatrix { relative_pivot: f64 },
+}
+
+impl fmt::Display for ReductionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReductionError::SingularCayleyMatrix { relative_pivot } => write!(
+                f,
+                "modified Cayley matrix too close to singular for the reduction to boxes \
+                 (relative pivot {:e}, threshold {:e})",
+                relative_pivot, CAYLEY_THRESHOLD
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReductionError {}
+
+/// Compares the real and imaginary parts of all coefficients.
+#[cfg(feature = "approx")]
+impl approx::AbsDiffEq for OLOResult {
+    type Epsilon = f64;
+
+    fn default_epsilon() -> f64 {
+        f64::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: f64) -> bool {
+        self.parts().zip(other.parts()).all(|(a, b)| a.abs_diff_eq(&b, epsilon))
+    }
+}
+
+#[cfg(feature = "approx")]
+impl approx::RelativeEq for OLOResult {
+    fn default_max_relative() -> f64 {
+        f64::default_max_relative()
+    }
+
+    fn relative_eq(&self, other: &Self, epsilon: f64, max_relative: f64) -> bool {
+        self.parts().zip(other.parts()).all(|(a, b)| a.relative_eq(&b, epsilon, max_relative))
+    }
+}
+
+#[cfg(feature = "approx")]
+impl approx::UlpsEq for OLOResult {
+    fn default_max_ulps() -> u32 {
+        f64::default_max_ulps()
+    }
+
+    fn ulps_eq(&self, other: &Self, epsilon: f64, max_ulps: u32) -> bool {
+        self.parts().zip(other.parts()).all(|(a, b)| a.ulps_eq(&b, epsilon, max_ulps))
+    }
+}
+
+#[cfg(feature = "approx")]
+impl OLOResult {
+    /// The real and imaginary parts of the coefficients.
+    fn parts(&self) -> impl Iterator<Item = f64> + '_ {
+        self.values.iter().flat_map(|v| [v.re, v.im])
+    }
+}
+
+impl Add for OLOResult {
+    type Output = OLOResult;
+
+    fn add(self, rhs: OLOResult) -> OLOResult {
+        OLOResult::from_values(std::array::from_fn(|i| self.values[i] + rhs.values[i]))
+    }
+}
+
+impl AddAssign for OLOResult {
+    fn add_assign(&mut self, rhs: OLOResult) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for OLOResult {
+    type Output = OLOResult;
+
+    fn sub(self, rhs: OLOResult) -> OLOResult {
+        OLOResult::from_values(std::array::from_fn(|i| self.values[i] - rhs.values[i]))
+    }
+}
+
+impl SubAssign for OLOResult {
+    fn sub_assign(&mut self, rhs: OLOResult) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for OLOResult {
+    type Output = OLOResult;
+
+    fn neg(self) -> OLOResult {
+        OLOResult::from_values(self.values.map(|v| -v))
+    }
+}
+
+impl Mul<Complex64> for OLOResult {
+    type Output = OLOResult;
+
+    fn mul(self, rhs: Complex64) -> OLOResult {
+        OLOResult::from_values(self.values.map(|v| v * rhs))
+    }
+}
+
+impl Mul<f64> for OLOResult {
+    type Output = OLOResult;
+
+    fn mul(self, rhs: f64) -> OLOResult {
+        OLOResult::from_values(self.values.map(|v| v * rhs))
+    }
 }
 
 impl fmt::Display for OLOResult {
@@ -107,7 +392,8 @@ impl fmt::Debug for OLOResult {
 }
 
 /// Units that control OneLOop’s print/message behavior.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OLOUnit {
     PrintAll,
     Message,
@@ -117,6 +403,7 @@ pub enum OLOUnit {
 
 
 /// FFI declarations
+#[cfg(oneloop_linked)]
 mod ffi {
     use super::*;
     unsafe extern "C" {
@@ -131,6 +418,15 @@ mod ffi {
             m1: *const Complex64,
             m2: *const Complex64,
         );
+        pub fn __avh_olo_dp_MOD_b11cc(
+            b11: *mut Complex64,
+            b00: *mut Complex64,
+            b1: *mut Complex64,
+            b0: *mut Complex64,
+            p: *const Complex64,
+            m1: *const Complex64,
+            m2: *const Complex64,
+        );
         pub fn __avh_olo_dp_MOD_c0cc(
             r: *mut Complex64,
             p1: *const Complex64,
@@ -156,16 +452,90 @@ mod ffi {
     }
 }
 
+/// Stand-ins for the OneLOop routines when build.rs links without OneLOop,
+/// which it only does with the `pure-rust` feature. The settings are then only
+/// kept on the Rust side, and evaluating an integral with OneLOop panics.
+#[cfg(not(oneloop_linked))]
+#[allow(non_snake_case, clippy::missing_safety_doc, clippy::too_many_arguments)]
+mod ffi {
+    use super::*;
+
+    fn unlinked(routine: &str) -> ! {
+        panic!("{} needs OneLOop, which is not linked (built with `pure-rust` without libavh_olo.a)", routine)
+    }
+
+    pub unsafe fn __avh_olo_units_MOD_set_unit(_: *const i8, _: *const i32) {}
+    pub unsafe fn __avh_olo_dp_MOD_olo_onshell(_: *const f64) {}
+    pub unsafe fn __avh_olo_dp_MOD_olo_scale(_: *const f64) {}
+    pub unsafe fn __avh_olo_dp_MOD_a0_c(_: *mut Complex64, _: *const Complex64) {
+        unlinked("A0")
+    }
+    pub unsafe fn __avh_olo_dp_MOD_b0cc(
+        _: *mut Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+    ) {
+        unlinked("B0")
+    }
+    pub unsafe fn __avh_olo_dp_MOD_b11cc(
+        _: *mut Complex64,
+        _: *mut Complex64,
+        _: *mut Complex64,
+        _: *mut Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+    ) {
+        unlinked("The 2-point tensor coefficients")
+    }
+    pub unsafe fn __avh_olo_dp_MOD_c0cc(
+        _: *mut Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+    ) {
+        unlinked("This C0, e.g. an IR-divergent one,")
+    }
+    pub unsafe fn __avh_olo_dp_MOD_d0cc(
+        _: *mut Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+        _: *const Complex64,
+    ) {
+        unlinked("D0")
+    }
+}
+
+static RENORMALIZATION_SCALE: Mutex<f64> = Mutex::new(1.0);
+
 /// Sets the renormalization scale for OneLOop calculations.
 ///
 /// # Arguments
 /// * `mu` - The renormalization scale μ (f64).
 pub fn set_renormalization_scale(mu: f64) {
-    unsafe {
-        ffi::__avh_olo_dp_MOD_olo_scale(&mu);
+    *RENORMALIZATION_SCALE.lock().unwrap() = mu;
+    match backend::installed() {
+        Some(backend) => backend.set_renormalization_scale(mu),
+        None => Builtin.set_renormalization_scale(mu),
     }
 }
 
+/// Returns the renormalization scale μ, by default 1.
+pub fn renormalization_scale() -> f64 {
+    *RENORMALIZATION_SCALE.lock().unwrap()
+}
+
 /// Sets the output unit for OneLOop messages.
 ///
 /// # Arguments
@@ -187,13 +557,51 @@ pub fn set_log_level(unit: OLOUnit, fortran_unit_number: Option<i32>) {
 }
 
 
+/// The threshold passed to `set_onshell_threshold`, if any.
+static ONSHELL_THRESHOLD: Mutex<Option<f64>> = Mutex::new(None);
+
 /// Sets the on-shell threshold for OneLOop calculations.
 ///
 /// # Arguments
 /// * `threshold` - Threshold for treating values as on-shell.
 pub fn set_onshell_threshold(threshold: f64) {
-    unsafe {
-        ffi::__avh_olo_dp_MOD_olo_onshell(&threshold);
+    *ONSHELL_THRESHOLD.lock().unwrap() = Some(threshold);
+    match backend::installed() {
+        Some(backend) => backend.set_onshell_threshold(threshold),
+        None => Builtin.set_onshell_threshold(threshold),
+    }
+}
+
+/// Returns the threshold set with `set_onshell_threshold`, or `None` if
+/// OneLOop uses its default relative threshold.
+pub fn onshell_threshold() -> Option<f64> {
+    *ONSHELL_THRESHOLD.lock().unwrap()
+}
+
+/// A snapshot of the global settings, e.g. to store with results or to
+/// restore later.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settings {
+    /// The renormalization scale, see `set_renormalization_scale`.
+    pub renormalization_scale: f64,
+    /// The on-shell threshold, see `set_onshell_threshold`.
+    pub onshell_threshold: Option<f64>,
+}
+
+impl Settings {
+    /// The current settings.
+    pub fn current() -> Self {
+        Settings { renormalization_scale: renormalization_scale(), onshell_threshold: onshell_threshold() }
+    }
+
+    /// Makes these the current settings. A threshold of `None` leaves the
+    /// current one, as OneLOop cannot go back to its default.
+    pub fn apply(&self) {
+        set_renormalization_scale(self.renormalization_scale);
+        if let Some(threshold) = self.onshell_threshold {
+            set_onshell_threshold(threshold);
+        }
     }
 }
 
@@ -201,7 +609,7 @@ pub fn set_onshell_threshold(threshold: f64) {
 ///
 /// # Arguments
 /// * `m` - The squared mass of the propagator.
-///         The imaginary part should be non-positive.
+///   The imaginary part should be non-positive.
 ///
 /// # Returns
 /// A `ResultOLO` containing the evaluated complex scalar integral.
@@ -209,10 +617,14 @@ pub fn set_onshell_threshold(threshold: f64) {
 /// # Notes
 /// This uses the Ellis-Zanderighi normalization convention. To convert to
 /// standard Feynman-diagram normalization, multiply by `TO_FEYNMAN`.
-pub fn one_point(m: Complex64) -> OLOResult {
-    let mut r = OLOResult::default(); // stack-allocated, aligned
-    unsafe { ffi::__avh_olo_dp_MOD_a0_c(r.as_mut_ptr(), &m) }
-    r
+/// The integral is evaluated by the current backend, see `backend`. With the
+/// `pure-rust` feature the default one uses `native::one_point`.
+pub fn one_point(m: impl Into<Complex64>) -> OLOResult {
+    let m = m.into();
+    match backend::installed() {
+        Some(backend) => backend.one_point(m),
+        None => Builtin.one_point(m),
+    }
 }
 
 /// Computes the 2-point scalar (bubble) function for two propagators.
@@ -220,9 +632,9 @@ pub fn one_point(m: Complex64) -> OLOResult {
 /// # Arguments
 /// * `p`  - The squared momentum flowing through the propagator pair.
 /// * `m1` - The squared mass of the first propagator.
-///          The imaginary part should be non-positive.
+///   The imaginary part should be non-positive.
 /// * `m2` - The squared mass of the second propagator.
-///          The imaginary part should be non-positive
+///   The imaginary part should be non-positive
 ///
 /// # Returns
 /// A `ResultOLO` containing the evaluated complex scalar integral.
@@ -230,10 +642,24 @@ pub fn one_point(m: Complex64) -> OLOResult {
 /// # Notes
 /// This uses the Ellis-Zanderighi normalization convention. To convert to
 /// standard Feynman-diagram normalization, multiply by `TO_FEYNMAN`.
-pub fn two_point(p: f64, m1: Complex64, m2: Complex64) -> OLOResult {
-    let mut r = OLOResult::default();
-    unsafe { ffi::__avh_olo_dp_MOD_b0cc(r.as_mut_ptr(), &p.into(), &m1, &m2) }
-    r
+/// The integral is evaluated by the current backend, see `backend`. With the
+/// `pure-rust` feature the default one uses `native::two_point`.
+pub fn two_point(p: f64, m1: impl Into<Complex64>, m2: impl Into<Complex64>) -> OLOResult {
+    let (m1, m2) = (m1.into(), m2.into());
+    match backend::installed() {
+        Some(backend) => backend.two_point(p, m1, m2),
+        None => Builtin.two_point(p, m1, m2),
+    }
+}
+
+/// Computes the rank ≤ 2 Passarino-Veltman coefficients of the 2-point
+/// function with the current backend, see
+/// `complex_momentum::TwoPointCoefficients`.
+pub(crate) fn two_point_coefficients(p: f64, m1: Complex64, m2: Complex64) -> TwoPointCoefficients {
+    match backend::installed() {
+        Some(backend) => backend.two_point_coefficients(p, m1, m2),
+        None => Builtin.two_point_coefficients(p, m1, m2),
+    }
 }
 
 /// Computes the 3-point scalar (triangle) function for three propagators.
@@ -252,17 +678,22 @@ pub fn two_point(p: f64, m1: Complex64, m2: Complex64) -> OLOResult {
 /// # Notes
 /// This uses the Ellis-Zanderighi normalization convention. To convert to
 /// standard Feynman-diagram normalization, multiply by `TO_FEYNMAN`.
+/// The integral is evaluated by the current backend, see `backend`. With the
+/// `pure-rust` feature the default one computes finite triangles with
+/// `native::three_point`, and only the IR-divergent ones with OneLOop.
 pub fn three_point(
     p1: f64,
     p2: f64,
     p3: f64,
-    m1: Complex64,
-    m2: Complex64,
-    m3: Complex64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
 ) -> OLOResult {
-    let mut r = OLOResult::default();
-    unsafe { ffi::__avh_olo_dp_MOD_c0cc(r.as_mut_ptr(), &p1.into(), &p2.into(), &p3.into(), &m1, &m2, &m3) }
-    r
+    let (m1, m2, m3) = (m1.into(), m2.into(), m3.into());
+    match backend::installed() {
+        Some(backend) => backend.three_point(p1, p2, p3, m1, m2, m3),
+        None => Builtin.three_point(p1, p2, p3, m1, m2, m3),
+    }
 }
 
 /// Computes the 4-point scalar function D0(p1², p2², p3², p4², p12², p23², mm1², mm2², mm3², mm4²)
@@ -287,6 +718,8 @@ pub fn three_point(
 /// # Notes
 /// This uses the Ellis-Zanderighi normalization convention. To convert to
 /// standard Feynman-diagram normalization, multiply by `TO_FEYNMAN`.
+/// The integral is evaluated by the current backend, see `backend`.
+#[allow(clippy::too_many_arguments)]
 pub fn four_point(
     p1:  f64,
     p2:  f64,
@@ -294,26 +727,121 @@ pub fn four_point(
     p4:  f64,
     p12: f64,
     p23: f64,
-    m1: Complex64,
-    m2: Complex64,
-    m3: Complex64,
-    m4: Complex64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+    m4: impl Into<Complex64>,
 ) -> OLOResult {
-    let mut r = OLOResult::default();
-    unsafe {
-        ffi::__avh_olo_dp_MOD_d0cc(
-            r.as_mut_ptr(),
-            &p1.into(),
-            &p2.into(),
-            &p3.into(),
-            &p4.into(),
-            &p12.into(),
-            &p23.into(),
-            &m1,
-            &m2,
-            &m3,
-            &m4,
-        )
+    let (m1, m2, m3, m4) = (m1.into(), m2.into(), m3.into(), m4.into());
+    match backend::installed() {
+        Some(backend) => backend.four_point(p1, p2, p3, p4, p12, p23, m1, m2, m3, m4),
+        None => Builtin.four_point(p1, p2, p3, p4, p12, p23, m1, m2, m3, m4),
     }
-    r
-}
\ No newline at end of file
+}
+/// Computes the 5-point scalar (pentagon) function for five propagators by
+/// reduction to 4-point functions.
+///
+/// # Arguments
+/// * `p` - The invariants `[p1², p2², p3², p4², p5², s12, s23, s34, s45, s51]`,
+///   where `p_i` are the external momenta with `p1 + ... + p5 = 0` and
+///   `s_ij = (p_i + p_j)²`.
+/// * `m` - The squared masses of the five propagators. Imaginary parts should be non-positive.
+///
+/// # Returns
+/// A `ResultOLO` containing the evaluated complex scalar integral, or a
+/// `ReductionError` if the modified Cayley matrix is singular.
+///
+/// # Notes
+/// The propagators are `[q²-m1][(q+p1)²-m2][(q+p1+p2)²-m3]...`. The reduction
+/// uses the modified Cayley matrix (Melrose method) and neglects terms of
+/// order `ε`, so the poles and the finite part are exact. The external momenta
+/// must be four-dimensional. This uses the Ellis-Zanderighi normalization
+/// convention. To convert to standard Feynman-diagram normalization, multiply
+/// by `TO_FEYNMAN`.
+pub fn five_point(p: [f64; 10], m: [impl Into<Complex64>; 5]) -> Result<OLOResult, ReductionError> {
+    melrose::reduce(&Kinematics::five_point(p, m))
+}
+
+/// Computes the 6-point scalar (hexagon) function for six propagators by
+/// reduction to 5-point functions.
+///
+/// # Arguments
+/// * `p` - The invariants
+///   `[p1², ..., p6², s12, s23, s34, s45, s56, s61, s123, s234, s345]`,
+///   where `p_i` are the external momenta with `p1 + ... + p6 = 0`,
+///   `s_ij = (p_i + p_j)²` and `s_ijk = (p_i + p_j + p_k)²`.
+/// * `m` - The squared masses of the six propagators. Imaginary parts should be non-positive.
+///
+/// # Returns
+/// A `ResultOLO` containing the evaluated complex scalar integral, or a
+/// `ReductionError` if a modified Cayley matrix is singular.
+///
+/// # Notes
+/// The propagators are `[q²-m1][(q+p1)²-m2][(q+p1+p2)²-m3]...`. As for
+/// `five_point`, the external momenta must be four-dimensional and terms of
+/// order `ε` are neglected. This uses the Ellis-Zanderighi normalization
+/// convention. To convert to standard Feynman-diagram normalization, multiply
+/// by `TO_FEYNMAN`.
+pub fn six_point(p: [f64; 15], m: [impl Into<Complex64>; 6]) -> Result<OLOResult, ReductionError> {
+    melrose::reduce(&Kinematics::six_point(p, m))
+}
+
+/// Computes the 3-point scalar (triangle) function in `d = 6 - 2ε` dimensions.
+///
+/// # Arguments
+/// As for `three_point`.
+///
+/// # Returns
+/// A `ResultOLO` containing the evaluated complex scalar integral, with the
+/// UV pole `-1/(2ε)`.
+///
+/// # Notes
+/// The integral is normalized as the four-dimensional one, with
+/// `∫ d^d q / (iπ^(d/2))` and the same factor `μ^(2ε)/r_Γ`. It is obtained
+/// from `three_point` and the three bubbles by the dimensional recurrence
+/// `(N - d - 1) B I_N^(d+2) = I_N^(d) + Σ_i b_i I_{N-1}^(d)(i)`, where
+/// `Σ_j Y_ij b_j = 1` and `B = Σ_i b_i`, which requires a non-vanishing Gram
+/// determinant.
+pub fn three_point_d6(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+) -> OLOResult {
+    melrose::shift_dimension(&Kinematics::three_point(p1, p2, p3, m1.into(), m2.into(), m3.into()))
+}
+
+/// Computes the 4-point scalar (box) function in `d = 6 - 2ε` dimensions.
+///
+/// # Arguments
+/// As for `four_point`.
+///
+/// # Returns
+/// A `ResultOLO` containing the evaluated complex scalar integral, which is
+/// finite.
+///
+/// # Notes
+/// The integral is normalized as the four-dimensional one, with
+/// `∫ d^d q / (iπ^(d/2))` and the same factor `μ^(2ε)/r_Γ`. It is obtained
+/// from `four_point` and the four triangles by the dimensional recurrence
+/// `(N - d - 1) B I_N^(d+2) = I_N^(d) + Σ_i b_i I_{N-1}^(d)(i)`, where
+/// `Σ_j Y_ij b_j = 1` and `B = Σ_i b_i`, which requires a non-vanishing Gram
+/// determinant.
+#[allow(clippy::too_many_arguments)]
+pub fn four_point_d6(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    p4: f64,
+    p12: f64,
+    p23: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+    m4: impl Into<Complex64>,
+) -> OLOResult {
+    let (m1, m2, m3, m4) = (m1.into(), m2.into(), m3.into(), m4.into());
+    melrose::shift_dimension(&Kinematics::four_point(p1, p2, p3, p4, p12, p23, m1, m2, m3, m4))
+}
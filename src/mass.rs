@@ -0,0 +1,121 @@
+//! Squared propagator masses in the complex-mass scheme.
+//!
+//! The scalar functions take the squared mass `m² - imΓ` of each propagator,
+//! whose imaginary part must not be positive. `Mass` builds it from the mass
+//! and width, or checks a given squared mass, and converts into the
+//! `Complex64` the scalar functions accept:
+//!
+//! ```rust,no_run
+//! use oneloop_bridge::mass::Mass;
+//! use oneloop_bridge::two_point;
+//!
+//! let w = Mass::with_width(80.4, 2.1).unwrap();
+//! let r = two_point(91.2f64.powi(2), w, Mass::MASSLESS);
+//! ```
+use num_complex::Complex64;
+use std::fmt;
+
+/// Errors reported when constructing a `Mass`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MassError {
+    /// The mass is negative or not finite.
+    InvalidMass { mass: f64 },
+    /// The width is negative or not finite.
+    InvalidWidth { width: f64 },
+    /// The squared mass is not finite or has a positive imaginary part, i.e.
+    /// the sign of the width is flipped.
+    InvalidSquaredMass { squared: Complex64 },
+}
+
+impl fmt::Display for MassError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MassError::InvalidMass { mass } => write!(f, "mass {} is negative or not finite", mass),
+            MassError::InvalidWidth { width } => write!(f, "width {} is negative or not finite", width),
+            MassError::InvalidSquaredMass { squared } => write!(
+                f,
+                "squared mass {} is not finite or has a positive imaginary part",
+                squared
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MassError {}
+
+/// The squared mass `m² - imΓ` of a propagator.
+///
+/// With the `serde` feature it is (de)serialized as the squared mass
+/// `[re, im]`, which is checked as in `from_squared`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "Complex64", try_from = "Complex64"))]
+pub struct Mass {
+    squared: Complex64,
+}
+
+impl Mass {
+    /// A massless propagator.
+    pub const MASSLESS: Mass = Mass { squared: Complex64::new(0.0, 0.0) };
+
+    /// A stable propagator of mass `mass`.
+    pub fn new(mass: f64) -> Result<Self, MassError> {
+        Self::with_width(mass, 0.0)
+    }
+
+    /// An unstable propagator of mass `mass` and width `width`, with squared
+    /// mass `mass² - i mass width`.
+    pub fn with_width(mass: f64, width: f64) -> Result<Self, MassError> {
+        if !(mass.is_finite() && mass >= 0.0) {
+            return Err(MassError::InvalidMass { mass });
+        }
+        if !(width.is_finite() && width >= 0.0) {
+            return Err(MassError::InvalidWidth { width });
+        }
+        Ok(Mass { squared: Complex64::new(mass * mass, -mass * width) })
+    }
+
+    /// A propagator of squared mass `squared`, whose imaginary part must not
+    /// be positive. A negative real part is allowed.
+    pub fn from_squared(squared: Complex64) -> Result<Self, MassError> {
+        if !(squared.re.is_finite() && squared.im.is_finite() && squared.im <= 0.0) {
+            return Err(MassError::InvalidSquaredMass { squared });
+        }
+        Ok(Mass { squared })
+    }
+
+    /// The squared mass `m² - imΓ`.
+    pub fn squared(&self) -> Complex64 {
+        self.squared
+    }
+
+    /// The complex pole mass `√(m² - imΓ)`.
+    pub fn pole(&self) -> Complex64 {
+        self.squared.sqrt()
+    }
+
+    /// Whether the propagator is massless.
+    pub fn is_massless(&self) -> bool {
+        self.squared == Complex64::new(0.0, 0.0)
+    }
+}
+
+impl From<Mass> for Complex64 {
+    fn from(mass: Mass) -> Self {
+        mass.squared
+    }
+}
+
+impl TryFrom<Complex64> for Mass {
+    type Error = MassError;
+
+    fn try_from(squared: Complex64) -> Result<Self, MassError> {
+        Mass::from_squared(squared)
+    }
+}
+
+impl fmt::Display for Mass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.squared)
+    }
+}
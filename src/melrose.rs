@@ -0,0 +1,125 @@
+//! Reduction of scalar integrals with more than four propagators to boxes.
+//!
+//! In four dimensions the momenta of an `N ≥ 5`-point integral are linearly
+//! dependent, which gives (Melrose; Bern, Dixon and Kosower)
+//!
+//! ```text
+//! I_N = -Σ_i b_i I_{N-1}^(i) + O(ε),    Σ_j Y_ij b_j = 1
+//! ```
+//!
+//! where `I_{N-1}^(i)` is the integral with propagator `i` removed and
+//! `Y_ij = m_i + m_j - (P_i - P_j)²` is the modified Cayley matrix. The
+//! remainder is `ε` times a finite integral in `6 - 2ε` dimensions for `N = 5`,
+//! and vanishes for `N = 6` because `Σ_i b_i = 0` for four-dimensional momenta.
+//!
+//! The same relation, kept to all orders in `ε`, shifts a triangle or box to
+//! `d + 2` dimensions:
+//!
+//! ```text
+//! (N - d - 1) B I_N^(d+2) = I_N^(d) + Σ_i b_i I_{N-1}^(d)(i),    B = Σ_i b_i
+//! ```
+//!
+//! With `d = 4 - 2ε` the factor `1/(N - 5 + 2ε)` mixes the poles of the
+//! right-hand side into the finite part, which produces the UV pole of the
+//! six-dimensional triangle.
+use crate::kinematics::{Kinematics, complex_adjugate, complex_determinant};
+use crate::{CAYLEY_THRESHOLD, OLOResult, ReductionError, four_point, three_point, two_point};
+use num_complex::Complex64;
+
+/// Reduces a scalar integral with four or more propagators down to
+/// `four_point` calls.
+pub(crate) fn reduce(kinematics: &Kinematics) -> Result<OLOResult, ReductionError> {
+    let n = kinematics.size();
+    if n == 4 {
+        return Ok(box_integral(kinematics));
+    }
+    let b = solve(kinematics.cayley_matrix(), vec![Complex64::new(1.0, 0.0); n])?;
+
+    let mut result = OLOResult::default();
+    for (k, b_k) in b.iter().enumerate() {
+        let keep: Vec<usize> = (0..n).filter(|&i| i != k).collect();
+        result -= reduce(&kinematics.pinch(&keep))? * *b_k;
+    }
+    Ok(result)
+}
+
+/// Evaluates a box with `four_point`.
+fn box_integral(kinematics: &Kinematics) -> OLOResult {
+    let m = kinematics.masses();
+    let r = kinematics.invariants();
+    four_point(
+        r[0][1], r[1][2], r[2][3], r[0][3], r[0][2], r[1][3],
+        m[0], m[1], m[2], m[3],
+    )
+}
+
+/// Computes a triangle or box in `6 - 2ε` dimensions from the one in
+/// `4 - 2ε` dimensions and its pinched integrals.
+///
+/// To stay finite where `det Y` vanishes, as for many IR-divergent
+/// configurations, `b_i = a_i/det Y` is written with `a = adj(Y)·(1, ..., 1)`.
+pub(crate) fn shift_dimension(kinematics: &Kinematics) -> OLOResult {
+    let n = kinematics.size();
+    let y = kinematics.cayley_matrix();
+    let adjugate = complex_adjugate(&y);
+    let a: Vec<Complex64> = adjugate.iter().map(|row| row.iter().sum()).collect();
+    let a_sum: Complex64 = a.iter().sum();
+
+    let mut numerator = scalar(kinematics) * complex_determinant(&y);
+    for (k, a_k) in a.iter().enumerate() {
+        let keep: Vec<usize> = (0..n).filter(|&i| i != k).collect();
+        numerator += scalar(&kinematics.pinch(&keep)) * *a_k;
+    }
+
+    // 1/(c + 2ε) = Σ_k (-2ε/c)^k / c
+    let c = n as f64 - 5.0;
+    let [n0, n1, n2] = [numerator.epsilon_0(), numerator.epsilon_minus_1(), numerator.epsilon_minus_2()];
+    let x = -2.0 / c;
+    OLOResult::from_values([n0 + x * n1 + x * x * n2, n1 + x * n2, n2]) * (1.0 / (c * a_sum))
+}
+
+/// Evaluates a 2- to 4-point scalar integral.
+fn scalar(kinematics: &Kinematics) -> OLOResult {
+    let m = kinematics.masses();
+    let r = kinematics.invariants();
+    match kinematics.size() {
+        2 => two_point(r[0][1], m[0], m[1]),
+        3 => three_point(r[0][1], r[1][2], r[0][2], m[0], m[1], m[2]),
+        _ => box_integral(kinematics),
+    }
+}
+
+/// Solves a linear system by Gaussian elimination with partial pivoting.
+///
+/// Fails if a pivot is smaller than `CAYLEY_THRESHOLD` times the largest
+/// entry of the matrix.
+fn solve(mut a: Vec<Vec<Complex64>>, mut b: Vec<Complex64>) -> Result<Vec<Complex64>, ReductionError> {
+    let n = b.len();
+    let scale = a.iter().flatten().fold(0.0_f64, |acc, v| acc.max(v.norm()));
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i][col].norm().total_cmp(&a[j][col].norm()))
+            .unwrap();
+        let relative_pivot = if scale > 0.0 { a[pivot][col].norm() / scale } else { 0.0 };
+        if relative_pivot.is_nan() || relative_pivot < CAYLEY_THRESHOLD {
+            return Err(ReductionError::SingularCayleyMatrix { relative_pivot });
+        }
+        a.swap(pivot, col);
+        b.swap(pivot, col);
+        let pivot_row = a[col].clone();
+        for row in col + 1..n {
+            let factor = a[row][col] / pivot_row[col];
+            for (x, y) in a[row].iter_mut().zip(&pivot_row).skip(col) {
+                *x -= factor * y;
+            }
+            let v = b[col];
+            b[row] -= factor * v;
+        }
+    }
+    let mut x = vec![Complex64::new(0.0, 0.0); n];
+    for row in (0..n).rev() {
+        let sum: Complex64 = (row + 1..n).map(|j| a[row][j] * x[j]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Ok(x)
+}
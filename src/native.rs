@@ -0,0 +1,556 @@
+//! Pure-Rust implementations of the scalar 1- and 2-point functions and of
+//! the finite 3-point function.
+//!
+//! These follow OneLOop's `tadp`, `bub0` and `trif0`..`trif3`, including the
+//! way the on-shell threshold and the renormalization scale enter, so that
+//! they agree with the Fortran up to rounding. With the `pure-rust` feature
+//! they are used by `one_point`, `two_point` and, for finite triangles,
+//! `three_point`.
+use crate::dispatch::{self, Routine};
+use crate::qmplx::qonv;
+use crate::{OLOResult, onshell_threshold, renormalization_scale};
+use num_complex::Complex64;
+use std::f64::consts::PI;
+
+pub(crate) const EPSN: f64 = f64::EPSILON;
+/// The infinitesimal imaginary part OneLOop adds to select the side of a cut.
+pub(crate) const IEPS: Complex64 = Complex64::new(0.0, EPSN * EPSN);
+/// OneLOop's relative threshold for negligible quantities.
+const NEGLIG: f64 = EPSN * 64.0;
+
+/// Computes the 1-point scalar (tadpole) function, see `one_point`.
+pub fn one_point(m: impl Into<Complex64>) -> OLOResult {
+    let m = m.into();
+    let mu2 = renormalization_scale().powi(2);
+    let mut am = m.norm();
+    if let Some(threshold) = onshell_threshold()
+        && am < threshold
+    {
+        am = 0.0;
+    }
+    if am == 0.0 {
+        return OLOResult::default();
+    }
+    OLOResult::from_values([m - m * log(m / mu2), m, Complex64::new(0.0, 0.0)])
+}
+
+/// Computes the 2-point scalar (bubble) function, see `two_point`.
+pub fn two_point(p: f64, m1: impl Into<Complex64>, m2: impl Into<Complex64>) -> OLOResult {
+    let (m1, m2): (Complex64, Complex64) = (m1.into(), m2.into());
+    let mu2 = renormalization_scale().powi(2);
+    // OneLOop flips the sign of a positive imaginary part
+    let m1 = Complex64::new(m1.re, -m1.im.abs());
+    let m2 = Complex64::new(m2.re, -m2.im.abs());
+    let mut ap = p.abs();
+    let mut am1 = m1.re.abs() + m1.im.abs();
+    let mut am2 = m2.re.abs() + m2.im.abs();
+    if let Some(threshold) = onshell_threshold() {
+        for a in [&mut ap, &mut am1, &mut am2] {
+            if *a < threshold {
+                *a = 0.0;
+            }
+        }
+    }
+    if ap == 0.0 && am1 == 0.0 && am2 == 0.0 {
+        return OLOResult::default();
+    }
+
+    // Let m1 be the larger mass
+    let (m0, am0, m1, am1) = if am2 >= am1 { (m1, am1, m2, am2) } else { (m2, am2, m1, am1) };
+    let pp = Complex64::from(p);
+    let finite = if ap == 0.0 {
+        let lna = -log(m1 / mu2);
+        if (m0 - m1).norm() <= am1 * EPSN * 10.0 {
+            lna
+        } else {
+            lna - bnlog0((m1 - IEPS * am1) / (m1 - m0))
+        }
+    } else if am0 == 0.0 {
+        if (pp - m1).norm() <= am1 * EPSN * 10.0 {
+            -log(m1 / mu2) + 2.0
+        } else {
+            -log((m1 - pp) / mu2) - bnlog0((pp - m1 + IEPS * am1) / pp) + 1.0
+        }
+    } else {
+        let (x1, x2) = solve_quadratic(pp, (m1 - m0) - pp, m0 - IEPS * am0);
+        -log(m0 / mu2) - bnlog0(x1) - bnlog0(x2)
+    };
+    OLOResult::from_values([finite, Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)])
+}
+
+/// Computes the 3-point scalar (triangle) function, see `three_point`, if
+/// it is finite. Returns `None` for the IR-divergent configurations, which
+/// are left to the Fortran.
+pub fn three_point(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+) -> Option<OLOResult> {
+    let (dispatch, t) = dispatch::triangle(p1, p2, p3, m1.into(), m2.into(), m3.into())?;
+    let [s1, s2, s3] = t.momenta.map(Complex64::from);
+    let [r1, r2, r3] = t.masses;
+    let finite = match dispatch.routine {
+        Routine::TriF0 => trif0(s1, s2, s3),
+        Routine::TriF1 => trif1(s1, s2, s3, r3),
+        Routine::TriF2 => trif2(s1, s2, s3, r2, r3),
+        Routine::TriF3 => trif3(s1, s2, s3, r1, r2, r3),
+        Routine::TriF3HV => trif3_hv([s1, s2, s3], t.masses, t.abs_momenta, t.smax),
+        _ => return None,
+    };
+    let zero = Complex64::new(0.0, 0.0);
+    Some(OLOResult::from_values([finite, zero, zero]))
+}
+
+/// The massless triangle, from the box of Denner, Nierste and Scharf with one
+/// mass sent to infinity.
+fn trif0(p1: Complex64, p2: Complex64, p3: Complex64) -> Complex64 {
+    let (r23, r24, r34) = (-p1, -p3, -p2);
+    let aa = r34 * r24;
+    let bb = r24 + r34 - r23;
+    let cc = Complex64::from(1.0);
+    let hh = r23.re;
+    let dd = sqrt_with_sign(bb * bb - 4.0 * aa * cc, -aa.re * hh);
+    let (x1, x2) = solve_quadratic_with(aa, bb, cc, dd);
+    let (x1, x2) = (-x1, -x2);
+
+    let qx1 = qonv(x1, hh);
+    let qx2 = qonv(x2, -hh);
+    let q23 = qonv(r23, -1.0);
+    let q24 = qonv(r24, -1.0);
+    let q34 = qonv(r34, -1.0);
+
+    let l12 = (qx1 / qx2).ln_over_one_minus();
+    let r = (qx1 * q34).li2_difference(qx2 * q34) * r34 + (qx1 * q24).li2_difference(qx2 * q24) * r24
+        - l12 * (qx1 * qx2).ln() / (x2 * 2.0)
+        - l12 * q23.ln() / x2;
+    r / aa
+}
+
+/// The triangle with one internal mass, the third.
+fn trif1(p1: Complex64, p2: Complex64, p3: Complex64, m3: Complex64) -> Complex64 {
+    let zero = Complex64::new(0.0, 0.0);
+    let (p2, p3, p23, m4) = (p1, p2, p3, m3);
+    let sm4 = sqrt_with_sign(m4, -1.0);
+    let mhh = sm4.norm();
+    let (sm2, sm3) = (Complex64::from(mhh), Complex64::from(mhh));
+
+    let r23 = (-p2 - p2 * IEPS) / (sm2 * sm3);
+    let r24 = (m4 - p23 - p23 * IEPS) / (sm2 * sm4);
+    let r34 = (m4 - p3 - p3 * IEPS) / (sm3 * sm4);
+    let small = 16.0 * NEGLIG;
+    let r24_not_0 = r24.re.abs() + r24.im.abs() >= small;
+    let r34_not_0 = r34.re.abs() + r34.im.abs() >= small;
+
+    let aa = r34 * r24 - r23;
+    if aa == zero {
+        // A threshold singularity, for which OneLOop returns zero
+        return zero;
+    }
+    let bb = r24 / sm3 + r34 / sm2 - r23 / sm4;
+    let cc = 1.0 / (sm2 * sm3);
+    let (x1, x2) = solve_quadratic(aa, bb, cc);
+    let (x1, x2) = (-x1, -x2);
+
+    let qx1 = qonv(x1, 1.0);
+    let qx2 = qonv(x2, 1.0);
+    let q23 = qonv(r23, -1.0);
+    let q24 = qonv(r24, -1.0);
+    let q34 = qonv(r34, -1.0);
+    let qm4 = qonv(sm4, -1.0);
+
+    let mut r = (qx1 * qx2 / (qm4 * qm4)).ln() / 2.0 + (q23 * (mhh * mhh)).ln();
+    r = -r * (qx1 / qx2).ln_over_one_minus() / x2 - (qx1 * qm4).li2_difference(qx2 * qm4) * sm4;
+    if r34_not_0 {
+        let qss = q34 * mhh;
+        r += (qx1 * qss).li2_difference(qx2 * qss) * r34 * sm3;
+    }
+    if r24_not_0 {
+        let qss = q24 * mhh;
+        r += (qx1 * qss).li2_difference(qx2 * qss) * r24 * sm2;
+    }
+    r / (aa * sm2 * sm3 * sm4)
+}
+
+/// The triangle with the second and third internal mass non-zero.
+fn trif2(p1: Complex64, p2: Complex64, p3: Complex64, m2: Complex64, m3: Complex64) -> Complex64 {
+    let zero = Complex64::new(0.0, 0.0);
+    let (p2, p3, p23, m2, m4) = (p3, p1, p2, m3, m2);
+    let sm2 = sqrt_with_sign(m2, -1.0);
+    let sm3 = Complex64::from(sm2.norm());
+    let sm4 = sqrt_with_sign(m4, -1.0);
+
+    let r23 = (m2 - p2 - p2 * IEPS) / (sm2 * sm3);
+    let k24 = (m2 + m4 - p23 - p23 * IEPS) / (sm2 * sm4);
+    let r34 = (m4 - p3 - p3 * IEPS) / (sm3 * sm4);
+    let small = 16.0 * NEGLIG;
+    let r23_not_0 = r23.re.abs() + r23.im.abs() >= small;
+    let r34_not_0 = r34.re.abs() + r34.im.abs() >= small;
+    let (r24, d24) = rfun(k24);
+
+    let aa = r34 / r24 - r23;
+    if aa == zero {
+        return zero;
+    }
+    let bb = -d24 / sm3 + r34 / sm2 - r23 / sm4;
+    let cc = (sm4 / sm2 - r24) / (sm3 * sm4);
+    let (x1, x2) = solve_quadratic(aa, bb, cc);
+    let (x1, x2) = (-x1, -x2);
+
+    let qx1 = qonv(x1, 1.0);
+    let qx2 = qonv(x2, 1.0);
+    let q23 = qonv(r23, -1.0);
+    let q24 = qonv(r24, -1.0);
+    let q34 = qonv(r34, -1.0);
+    let qm2 = qonv(sm2, -1.0);
+    let qm3 = qonv(sm3, -1.0);
+    let qm4 = qonv(sm4, -1.0);
+
+    let qy1 = qx1 / q24;
+    let qy2 = qx2 / q24;
+    let mut r = (qy1 * qm2).li2_difference(qy2 * qm2) / r24 * sm2;
+    if x2 != zero {
+        r += ((qy1 / qy2).ln_over_one_minus() * (qy1 * qy2 / (qm2 * qm2)).ln()
+            - (qx1 / qx2).ln_over_one_minus() * (qx1 * qx2 / (qm4 * qm4)).ln())
+            / (x2 * 2.0);
+    }
+    r -= (qx1 * qm4).li2_difference(qx2 * qm4) * sm4;
+    if r23_not_0 {
+        let qss = q23 * qm3 / q24;
+        r -= (qx1 * qss).li2_difference(qx2 * qss) * r23 * sm3 / r24;
+    }
+    if r34_not_0 {
+        let qss = q34 * qm3;
+        r += (qx1 * qss).li2_difference(qx2 * qss) * r34 * sm3;
+    }
+    r / (aa * sm2 * sm3 * sm4)
+}
+
+/// The triangle with three internal masses.
+fn trif3(p1: Complex64, p2: Complex64, p3: Complex64, m1: Complex64, m2: Complex64, m3: Complex64) -> Complex64 {
+    let zero = Complex64::new(0.0, 0.0);
+    let (h1, h2, h3) = (-m1.im, -m2.im, -m3.im);
+    let (p1, p2, p3, m1, m2, m3) =
+        if h2 >= h1 && h2 >= h3 { (p3, p1, p2, m3, m1, m2) } else { (p1, p2, p3, m1, m2, m3) };
+    let sm1 = sqrt_with_sign(m1, -1.0);
+    let sm2 = sqrt_with_sign(m2, -1.0);
+    let sm3 = sqrt_with_sign(m3, -1.0);
+
+    let k = |ma: Complex64, mb: Complex64, p: Complex64, sa: Complex64, sb: Complex64| {
+        if ma + mb != p { (ma + mb - p - p * IEPS) / (sa * sb) } else { zero }
+    };
+    let k12 = k(m1, m2, p1, sm1, sm2);
+    let k13 = k(m1, m3, p3, sm1, sm3);
+    let k23 = k(m2, m3, p2, sm2, sm3);
+    let (r12, _) = rfun(k12);
+    let (r13, d13) = rfun(k13);
+    let (r23, _) = rfun(k23);
+
+    let aa = sm2 / sm3 - k23 + r13 * (k12 - sm2 / sm1);
+    if aa == zero {
+        return zero;
+    }
+    let bb = d13 / sm2 + k12 / sm3 - k23 / sm1;
+    let cc = (sm1 / sm3 - 1.0 / r13) / (sm1 * sm2);
+    let (x1, x2) = solve_quadratic(aa, bb, cc);
+    let (x1, x2) = (-x1, -x2);
+
+    let qx1 = qonv(x1, 1.0);
+    let qx2 = qonv(x2, 1.0);
+    let q12 = qonv(r12, -1.0);
+    let q13 = qonv(r13, -1.0);
+    let q23 = qonv(r23, -1.0);
+    let qm1 = qonv(sm1, -1.0);
+    let qm2 = qonv(sm2, -1.0);
+    let qm3 = qonv(sm3, -1.0);
+
+    let (qz1, qz2) = (qx1 * qm2, qx2 * qm2);
+    let mut r = ((qz1 * q12).li2_difference(qz2 * q12) * r12 + (qz1 / q12).li2_difference(qz2 / q12) / r12) * sm2;
+    let qtt = q13 * qm2;
+    let (qz1, qz2) = (qx1 * qtt, qx2 * qtt);
+    r -= ((qz1 * q23).li2_difference(qz2 * q23) * r23 + (qz1 / q23).li2_difference(qz2 / q23) / r23) * r13 * sm2;
+    let (qz1, qz2) = (qx1 * q13, qx2 * q13);
+    r += (qz1 * qm3).li2_difference(qz2 * qm3) * r13 * sm3 - (qx1 * qm1).li2_difference(qx2 * qm1) * sm1;
+    if x2 != zero {
+        r += ((qz1 / qz2).ln_over_one_minus() * (qz1 * qz2 / (qm3 * qm3)).ln()
+            - (qx1 / qx2).ln_over_one_minus() * (qx1 * qx2 / (qm1 * qm1)).ln())
+            / (x2 * 2.0);
+    }
+    r / (aa * sm1 * sm2 * sm3)
+}
+
+/// The triangle with three internal masses following 't Hooft and Veltman,
+/// used when the Källén function of the momenta is negative.
+fn trif3_hv(pp: [Complex64; 3], mm: [Complex64; 3], ap: [f64; 3], smax: f64) -> Complex64 {
+    let zero = Complex64::new(0.0, 0.0);
+    // Order the momenta by size, smallest first
+    let (a, p, m) = if ap[0] <= ap[1] && ap[0] <= ap[2] {
+        if ap[1] <= ap[2] {
+            ([ap[0], ap[1], ap[2]], [pp[0], pp[1], pp[2]], [mm[0], mm[1], mm[2]])
+        } else {
+            ([ap[0], ap[2], ap[1]], [pp[0], pp[2], pp[1]], [mm[1], mm[0], mm[2]])
+        }
+    } else if ap[1] <= ap[2] && ap[1] <= ap[0] {
+        if ap[2] <= ap[0] {
+            ([ap[1], ap[2], ap[0]], [pp[1], pp[2], pp[0]], [mm[1], mm[2], mm[0]])
+        } else {
+            ([ap[1], ap[0], ap[2]], [pp[1], pp[0], pp[2]], [mm[2], mm[1], mm[0]])
+        }
+    } else if ap[0] <= ap[1] {
+        ([ap[2], ap[0], ap[1]], [pp[2], pp[0], pp[1]], [mm[2], mm[0], mm[1]])
+    } else {
+        ([ap[2], ap[1], ap[0]], [pp[2], pp[1], pp[0]], [mm[0], mm[2], mm[1]])
+    };
+    let [p1, p2, p3] = p;
+    let thrs = smax * NEGLIG;
+    // An infinitesimal imaginary part for the masses
+    let [m1, m2, m3] = m.map(|m| m - m.re.abs() * IEPS / EPSN);
+
+    if a[0] > thrs {
+        let slam = kallen(p1, p2, p3);
+        if slam == zero {
+            return zero;
+        }
+        let slam = sqrt_with_sign(slam, 1.0);
+        let [sm1, sm2, sm3] = [m1, m2, m3].map(|m| sqrt_with_sign(m, -1.0));
+        let r = s3fun(p1, sm1, sm2, (m2 - m3) + p2, Some((p3 - p1 - p2, p2, slam)))
+            - s3fun(p3, sm1, sm3, -(m1 - m2) + p3 - p2, Some((p2 - p1 - p3, p1, slam)))
+            + s3fun(p2, sm2, sm3, -(m1 - m2) + p3 - p2, Some((p1 + p2 - p3, p1, slam)));
+        -r / slam
+    } else if a[1] > thrs {
+        if p2 == p3 {
+            return zero;
+        }
+        let [sm1, sm2, sm3] = [m1, m2, m3].map(|m| sqrt_with_sign(m, -1.0));
+        let yy = ((m1 - m2) - p3 + p2) / (p2 - p3);
+        (s3fun(p3, sm1, sm3, yy, None) - s3fun(p2, sm2, sm3, yy, None)) / (p2 - p3)
+    } else if a[2] > thrs {
+        let sm1 = sqrt_with_sign(m1, -1.0);
+        let sm3 = sqrt_with_sign(m3, -1.0);
+        let yy = -((m1 - m2) - p3) / p3;
+        -(s3fun(p3, sm1, sm3, yy, None) - s2fun(m2 - m3, m3, yy)) / p3
+    } else {
+        let (a12, a23, a31) = ((m1 - m2).norm(), (m2 - m3).norm(), (m3 - m1).norm());
+        let q = |m: Complex64| qonv(m, 1.0);
+        if a12 >= a23 && a12 >= a31 {
+            if a12 == 0.0 {
+                return -1.0 / (2.0 * m3);
+            }
+            ((q(m3) / q(m1)).ln_over_one_minus() - (q(m3) / q(m2)).ln_over_one_minus()) / (m1 - m2)
+        } else if a23 >= a12 && a23 >= a31 {
+            if a23 == 0.0 {
+                return -1.0 / (2.0 * m1);
+            }
+            ((q(m1) / q(m2)).ln_over_one_minus() - (q(m1) / q(m3)).ln_over_one_minus()) / (m2 - m3)
+        } else {
+            if a31 == 0.0 {
+                return -1.0 / (2.0 * m2);
+            }
+            ((q(m2) / q(m3)).ln_over_one_minus() - (q(m2) / q(m1)).ln_over_one_minus()) / (m3 - m1)
+        }
+    }
+}
+
+/// `∫₀¹ dy (ln(a y² + b y + c) - ln(a y0² + b y0 + c))/(y - y0)` with
+/// `b = s1² - s2² - a` and `c = s2²`. With `t = (t2, t3, √λ(a, t2, t3))` the
+/// point `y0` is obtained from `t1` as in 't Hooft-Veltman, otherwise `y0 = t1`.
+fn s3fun(
+    aa: Complex64,
+    s1: Complex64,
+    s2: Complex64,
+    t1: Complex64,
+    t: Option<(Complex64, Complex64, Complex64)>,
+) -> Complex64 {
+    let bb = (s1 + s2) * (s1 - s2) - aa;
+    let cc = s2 * s2;
+    let dd = ((aa - (s1 + s2).powi(2)) * (aa - (s1 - s2).powi(2))).sqrt();
+    let (y1, y2) = solve_quadratic_with(aa, bb, cc, dd);
+    let y0 = match t {
+        Some((t2, t3, t4)) => {
+            let (alpha, _) = solve_quadratic_with(aa, t2, t3, t4);
+            -(t1 + bb * alpha) / t4
+        }
+        None => t1,
+    };
+
+    let q1 = qonv(y0 - y1, 1.0);
+    let q2 = qonv(y0 - y2, 1.0);
+    let mut r = (qonv(-y1, 1.0) / q1).li2() - (qonv(1.0 - y1, 1.0) / q1).li2() + (qonv(-y2, 1.0) / q2).li2()
+        - (qonv(1.0 - y2, 1.0) / q2).li2();
+    // The imaginary part of a y0² + b y0 + c may be pure noise from cancellations
+    let mut zz = y0 * (aa * y0 + bb);
+    let (rez, aimz) = (zz.re, zz.im.abs());
+    let arez = rez.abs();
+    if arez * EPSN * EPSN <= aimz * NEGLIG && aimz <= arez * NEGLIG {
+        zz = (rez + cc) / aa;
+    } else {
+        zz = (zz + cc) / aa;
+    }
+    let mut hh = eta3(-y1, -y2, cc / aa) - eta3(y0 - y1, y0 - y2, zz);
+    if aa.re < 0.0 && zz.im < 0.0 {
+        hh -= Complex64::new(0.0, 2.0 * PI);
+    }
+    if hh != Complex64::new(0.0, 0.0) {
+        r += hh * qonv((y0 - 1.0) / y0, 1.0).ln();
+    }
+    r
+}
+
+/// `∫₀¹ dy (ln(a y + b) - ln(a y0 + b))/(y - y0)`.
+fn s2fun(aa: Complex64, bb: Complex64, y0: Complex64) -> Complex64 {
+    let y1 = -bb / aa;
+    let q1 = qonv(y0 - y1, 1.0);
+    let mut r = (qonv(-y1, -1.0) / q1).li2() - (qonv(1.0 - y1, -1.0) / q1).li2();
+    let hh = eta5(aa, -y1, bb, y0 - y1, aa * (y0 - y1));
+    if hh != Complex64::new(0.0, 0.0) {
+        r += hh * qonv((y0 - 1.0) / y0, 1.0).ln();
+    }
+    r
+}
+
+/// The logarithm with a negative real argument below the cut, `ln(x - i0)`.
+pub(crate) fn log(x: Complex64) -> Complex64 {
+    if x.im == 0.0 && x.re < 0.0 {
+        Complex64::new((-x.re).ln(), -PI)
+    } else {
+        x.ln()
+    }
+}
+
+/// `∫₀¹ dt ln(1 - t/x) = (1 - x) ln(1 - 1/x) - 1`. An imaginary part of `x`
+/// that is negligible compared to its real part only selects the side of the
+/// cut for `0 < x < 1`.
+fn bnlog0(x: Complex64) -> Complex64 {
+    if x.im.abs() <= EPSN * x.re.abs() {
+        if x.re == 0.0 {
+            return Complex64::new(0.0, 0.0);
+        }
+        if (x.re - 1.0).abs() <= EPSN * 8.0 {
+            return Complex64::new(-1.0, 0.0);
+        }
+        let y = 1.0 - 1.0 / x.re;
+        if y < 0.0 {
+            let sign = if x.im < 0.0 { -1.0 } else { 1.0 };
+            let omx = 1.0 - x.re;
+            return Complex64::new(omx * (-y).ln() - 1.0, sign * omx * PI);
+        }
+        return bnlog0_from_log(Complex64::from(y.ln()), Complex64::from(x.re));
+    }
+    if (x - 1.0).norm() <= EPSN * 8.0 {
+        return Complex64::new(-1.0, 0.0);
+    }
+    bnlog0_from_log((1.0 - 1.0 / x).ln(), x)
+}
+
+/// `bnlog0(x)` given `y = ln(1 - 1/x)`. For large `|x|` the direct formula
+/// cancels, and we use `-x Σ_{n≥2} (n-1) yⁿ / n!` instead.
+fn bnlog0_from_log(y: Complex64, x: Complex64) -> Complex64 {
+    if y.norm() >= 1.0 {
+        return (1.0 - x) * y - 1.0;
+    }
+    let mut sum = Complex64::new(0.0, 0.0);
+    let mut power = y * y / 2.0;
+    for n in 2..30 {
+        let term = power * (n - 1) as f64;
+        sum += term;
+        if term.norm() <= EPSN * sum.norm() {
+            break;
+        }
+        power *= y / (n + 1) as f64;
+    }
+    -x * sum
+}
+
+/// The solutions of `a x² + b x + c = 0`, computed without cancellations.
+pub(crate) fn solve_quadratic(a: Complex64, b: Complex64, c: Complex64) -> (Complex64, Complex64) {
+    solve_quadratic_with(a, b, c, (b * b - 4.0 * a * c).sqrt())
+}
+
+/// `solve_quadratic` with the square root `d` of the discriminant given, which
+/// fixes the branch.
+fn solve_quadratic_with(a: Complex64, b: Complex64, c: Complex64, d: Complex64) -> (Complex64, Complex64) {
+    let zero = Complex64::new(0.0, 0.0);
+    if a == zero {
+        if b == zero {
+            return (zero, zero);
+        }
+        return (-c / b, -c / b);
+    }
+    if c == zero {
+        return (-b / a, zero);
+    }
+    let (q, h) = (-b + d, -b - d);
+    if q.norm() >= h.norm() {
+        (q / (2.0 * a), 2.0 * c / q)
+    } else {
+        (2.0 * c / h, h / (2.0 * a))
+    }
+}
+
+/// The square root, which for a negative real `x` is imaginary with the sign
+/// of `sign`.
+fn sqrt_with_sign(x: Complex64, sign: f64) -> Complex64 {
+    if x.im != 0.0 {
+        x.sqrt()
+    } else if x.re >= 0.0 {
+        Complex64::from(x.re.sqrt())
+    } else {
+        Complex64::new(0.0, (-x.re).sqrt().copysign(sign))
+    }
+}
+
+/// Returns `r` with `q = r + 1/r`, with `Im(r)` of the same sign as `Im(q)`,
+/// or non-positive if `q` is real, and `|r| > 1` if `r` is real. Also returns
+/// `r - 1/r`.
+fn rfun(q: Complex64) -> (Complex64, Complex64) {
+    let mut d = (q * q - 4.0).sqrt();
+    let mut r = q + d;
+    let r2 = q - d;
+    if r2.norm() > r.norm() {
+        r = r2;
+        d = -d;
+    }
+    let keep = if q.im == 0.0 { r.im <= 0.0 } else { (q.im >= 0.0) == (r.im >= 0.0) };
+    if keep { (r / 2.0, d) } else { (2.0 / r, -d) }
+}
+
+/// The Källén function `λ(p1, p2, p3)`, evaluated to avoid cancellations.
+fn kallen(p1: Complex64, p2: Complex64, p3: Complex64) -> Complex64 {
+    let (y1, y2, y3) = (p2 * p3, p3 * p1, p1 * p2);
+    let (b1, b2, b3) = (y1.re, y2.re, y3.re);
+    let first = (p1 - p2 - p3).powi(2) - 4.0 * y1;
+    let second = (p2 - p3 - p1).powi(2) - 4.0 * y2;
+    let third = (p3 - p1 - p2).powi(2) - 4.0 * y3;
+    if b1 <= 0.0 {
+        first
+    } else if b2 <= 0.0 {
+        second
+    } else if b3 <= 0.0 {
+        third
+    } else if b1 <= b2 && b1 <= b3 {
+        first
+    } else if b2 <= b3 && b2 <= b1 {
+        second
+    } else {
+        third
+    }
+}
+
+/// `2πi (θ(-Im a)θ(-Im b)θ(Im c) - θ(Im a)θ(Im b)θ(-Im c))`, with a vanishing
+/// imaginary part counting as positive.
+fn eta3(a: Complex64, b: Complex64, c: Complex64) -> Complex64 {
+    let (sa, sb, sc) = (a.im >= 0.0, b.im >= 0.0, c.im >= 0.0);
+    if sa == sb && sa != sc { two_pi_i(sc) } else { Complex64::new(0.0, 0.0) }
+}
+
+/// `eta3(a, b1, c1) - eta3(a, b2, c2)`.
+fn eta5(a: Complex64, b1: Complex64, c1: Complex64, b2: Complex64, c2: Complex64) -> Complex64 {
+    eta3(a, b1, c1) - eta3(a, b2, c2)
+}
+
+/// `±2πi` for a positive or negative imaginary part.
+fn two_pi_i(positive: bool) -> Complex64 {
+    Complex64::new(0.0, if positive { 2.0 * PI } else { -2.0 * PI })
+}
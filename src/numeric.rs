@@ -0,0 +1,360 @@
+//! Numerical evaluation of finite triangles and boxes from their
+//! Feynman-parameter representation, as an independent cross-check of
+//! OneLOop.
+//!
+//! In the normalization of `three_point` and `four_point`, the finite
+//! integrals are
+//!
+//! ```text
+//! C0 = -∫ d³x δ(1 - Σx) 1/F,    D0 = ∫ d⁴x δ(1 - Σx) 1/F²,    F = ½ xᵀ Y x - i0
+//! ```
+//!
+//! with the modified Cayley matrix `Y`, see `kinematics`. The simplex is
+//! mapped onto the unit cube, where a globally adaptive cubature with the
+//! degree 7/5 rule pair of Genz and Malik subdivides the region with the
+//! largest error estimate until the requested precision is reached.
+//!
+//! Where `F` vanishes inside the domain, which happens in the physical region,
+//! the cube variables are deformed into the complex plane as
+//! `z = t - iλ t(1-t) ∂Re F/∂t`, which gives `F` the negative imaginary part
+//! of the `-i0` prescription. `λ` is halved until `Im F ≤ 0` at all sampled
+//! points.
+use crate::kinematics::{Kinematics, complex_determinant};
+use crate::dispatch::{self, conjugate_if_positive};
+use crate::OLOResult;
+use num_complex::Complex64;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// The largest number of integrand evaluations per integral.
+pub const MAX_EVALUATIONS: usize = 2_000_000;
+
+/// The initial deformation `λ` relative to the inverse of the largest input.
+const DEFORMATION: f64 = 4.0;
+
+/// The number of times `λ` is halved before giving up.
+const MAX_HALVINGS: usize = 12;
+
+/// A numerically integrated scalar function.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Integral {
+    /// The integral, with vanishing poles.
+    pub value: OLOResult,
+    /// The estimated absolute error of the finite part.
+    pub error: f64,
+    /// The number of integrand evaluations.
+    pub evaluations: usize,
+}
+
+/// Computes `three_point` numerically to the relative precision `tolerance`,
+/// or returns `None` if it is IR divergent, rejected by OneLOop or the
+/// integrand is singular at a sample point, e.g. at threshold.
+///
+/// The estimate may miss `tolerance` after `MAX_EVALUATIONS`, as seen from
+/// `Integral::error`.
+pub fn three_point(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+    tolerance: f64,
+) -> Option<Integral> {
+    let [m1, m2, m3] = [m1.into(), m2.into(), m3.into()].map(conjugate_if_positive);
+    if dispatch::three_point(p1, p2, p3, m1, m2, m3)?.routine.is_divergent() {
+        return None;
+    }
+    integrate(&Kinematics::three_point(p1, p2, p3, m1, m2, m3), tolerance)
+}
+
+/// Computes `four_point` numerically to the relative precision `tolerance`,
+/// or returns `None` if it is IR divergent, rejected by OneLOop or the
+/// integrand is singular at a sample point, e.g. at threshold.
+///
+/// The estimate may miss `tolerance` after `MAX_EVALUATIONS`, as seen from
+/// `Integral::error`.
+#[allow(clippy::too_many_arguments)]
+pub fn four_point(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    p4: f64,
+    p12: f64,
+    p23: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+    m4: impl Into<Complex64>,
+    tolerance: f64,
+) -> Option<Integral> {
+    let [m1, m2, m3, m4] = [m1.into(), m2.into(), m3.into(), m4.into()].map(conjugate_if_positive);
+    if dispatch::four_point(p1, p2, p3, p4, p12, p23, m1, m2, m3, m4)?.routine.is_divergent() {
+        return None;
+    }
+    integrate(&Kinematics::four_point(p1, p2, p3, p4, p12, p23, m1, m2, m3, m4), tolerance)
+}
+
+/// Integrates `(-1)^N Γ(N-2) F^(2-N)` over the simplex.
+fn integrate(kinematics: &Kinematics, tolerance: f64) -> Option<Integral> {
+    let integrand = Integrand::new(kinematics);
+    // In the Euclidean region F ≥ Σ x_i Re m_i does not vanish
+    let euclidean = kinematics.masses().iter().all(|m| m.re >= 0.0)
+        && kinematics.invariants().iter().flatten().all(|&r| r <= 0.0);
+    let mut lambda = if euclidean { 0.0 } else { DEFORMATION / kinematics.scale() };
+    let mut evaluations = 0;
+    for _ in 0..=MAX_HALVINGS {
+        let f = |t: &[f64]| integrand.evaluate(t, lambda);
+        match cubature(integrand.dimension, f, tolerance, 0.0, MAX_EVALUATIONS - evaluations) {
+            Ok(result) if !result.error.is_finite() => return None,
+            Ok(result) => {
+                let zero = Complex64::new(0.0, 0.0);
+                let value = OLOResult::from_values([result.value, zero, zero]);
+                return Some(Integral { value, error: result.error, evaluations: evaluations + result.evaluations });
+            }
+            Err(n) => {
+                evaluations += n;
+                lambda /= 2.0;
+            }
+        }
+    }
+    None
+}
+
+/// The Feynman-parameter integrand on the unit cube.
+struct Integrand {
+    /// The number of propagators `N`.
+    size: usize,
+    /// The dimension `N - 1` of the cube.
+    dimension: usize,
+    cayley: Vec<Vec<Complex64>>,
+    /// `(-1)^N Γ(N-2)`.
+    prefactor: f64,
+}
+
+impl Integrand {
+    fn new(kinematics: &Kinematics) -> Self {
+        let size = kinematics.size();
+        let gamma: f64 = (1..size - 2).map(|k| k as f64).product();
+        let sign = if size.is_multiple_of(2) { 1.0 } else { -1.0 };
+        Self { size, dimension: size - 1, cayley: kinematics.cayley_matrix(), prefactor: sign * gamma }
+    }
+
+    /// The factors of `x_i = Π_j f_ij(t_j)` with `x_0 = t_0`,
+    /// `x_i = (1-t_0)...(1-t_{i-1}) t_i` and `x_{N-1} = (1-t_0)...(1-t_{N-2})`,
+    /// as `(j, sign)` with `f_ij = t_j` for `sign = 1` and `1 - t_j` for `-1`.
+    fn factors(&self, i: usize) -> Vec<(usize, f64)> {
+        let mut factors: Vec<(usize, f64)> = (0..i).map(|j| (j, -1.0)).collect();
+        if i < self.dimension {
+            factors.push((i, 1.0));
+        }
+        factors
+    }
+
+    /// `F(x(t))`.
+    fn cayley_form(&self, x: &[Complex64]) -> Complex64 {
+        let y = &self.cayley;
+        (0..self.size).map(|i| (0..self.size).map(|j| x[i] * x[j] * y[i][j]).sum::<Complex64>()).sum::<Complex64>() / 2.0
+    }
+
+    /// The integrand at the deformed point `z(t)`, times the Jacobians, or
+    /// `None` where the deformation gives `F` a positive imaginary part.
+    fn evaluate(&self, t: &[f64], lambda: f64) -> Option<Complex64> {
+        let n = self.dimension;
+        let y = &self.cayley;
+        let factor = |t: f64, sign: f64| if sign > 0.0 { t } else { 1.0 - t };
+
+        let mut z: Vec<Complex64> = t.iter().map(|&t| Complex64::from(t)).collect();
+        let mut jacobian = Complex64::new(1.0, 0.0);
+        if lambda != 0.0 {
+            // x, ∂x/∂t_k and ∂²x/∂t_k∂t_l
+            let mut x = vec![0.0; self.size];
+            let mut dx = vec![vec![0.0; n]; self.size];
+            let mut ddx = vec![vec![vec![0.0; n]; n]; self.size];
+            for i in 0..self.size {
+                let factors = self.factors(i);
+                let product = |skip: &[usize]| -> f64 {
+                    factors.iter().filter(|(j, _)| !skip.contains(j)).map(|&(j, s)| factor(t[j], s)).product()
+                };
+                x[i] = product(&[]);
+                for &(k, sk) in &factors {
+                    dx[i][k] = sk * product(&[k]);
+                    for &(l, sl) in &factors {
+                        if l != k {
+                            ddx[i][k][l] = sk * sl * product(&[k, l]);
+                        }
+                    }
+                }
+            }
+            let yx: Vec<Complex64> = (0..self.size).map(|i| (0..self.size).map(|j| y[i][j] * x[j]).sum()).collect();
+            let gradient: Vec<f64> = (0..n).map(|k| (0..self.size).map(|i| yx[i].re * dx[i][k]).sum()).collect();
+            let mut matrix = vec![vec![Complex64::new(0.0, 0.0); n]; n];
+            for k in 0..n {
+                for l in 0..n {
+                    let mut hessian: f64 = (0..self.size).map(|i| yx[i].re * ddx[i][k][l]).sum();
+                    for i in 0..self.size {
+                        for j in 0..self.size {
+                            hessian += dx[i][k] * y[i][j].re * dx[j][l];
+                        }
+                    }
+                    let mut a = t[k] * (1.0 - t[k]) * hessian;
+                    if k == l {
+                        a += (1.0 - 2.0 * t[k]) * gradient[k];
+                    }
+                    matrix[k][l] = Complex64::new(if k == l { 1.0 } else { 0.0 }, -lambda * a);
+                }
+                z[k] -= Complex64::new(0.0, lambda * t[k] * (1.0 - t[k]) * gradient[k]);
+            }
+            jacobian = complex_determinant(&matrix);
+        }
+
+        let x: Vec<Complex64> = (0..self.size)
+            .map(|i| self.factors(i).iter().map(|&(j, s)| if s > 0.0 { z[j] } else { 1.0 - z[j] }).product())
+            .collect();
+        let f = self.cayley_form(&x);
+        if f.im > 0.0 && lambda != 0.0 && f.im > 1e-12 * f.norm() {
+            return None;
+        }
+        // The Jacobian of the map onto the cube
+        let simplex: Complex64 = (0..n).map(|k| (1.0 - z[k]).powi((n - 1 - k) as i32)).product();
+        Some(self.prefactor * f.powi(2 - self.size as i32) * simplex * jacobian)
+    }
+}
+
+/// A subregion of the cube with its rule estimates.
+struct Region {
+    center: Vec<f64>,
+    half_width: Vec<f64>,
+    value: Complex64,
+    error: f64,
+    /// The axis with the largest fourth difference.
+    split: usize,
+}
+
+impl PartialEq for Region {
+    fn eq(&self, other: &Self) -> bool {
+        self.error == other.error
+    }
+}
+
+impl Eq for Region {}
+
+impl PartialOrd for Region {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Region {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.error.total_cmp(&other.error)
+    }
+}
+
+/// The result of `cubature`.
+pub(crate) struct Cubature {
+    pub(crate) value: Complex64,
+    pub(crate) error: f64,
+    pub(crate) evaluations: usize,
+}
+
+/// Globally adaptive cubature of `f` over the unit cube of `dimension ≥ 2`,
+/// until the error is below `relative` times the value or below `absolute`.
+/// Returns the number of evaluations up to the first point where `f`
+/// returned `None` as error.
+pub(crate) fn cubature(
+    dimension: usize,
+    f: impl Fn(&[f64]) -> Option<Complex64>,
+    relative: f64,
+    absolute: f64,
+    max_evaluations: usize,
+) -> Result<Cubature, usize> {
+    let per_region = 1 + 4 * dimension + 2 * dimension * (dimension - 1) + (1 << dimension);
+    let mut evaluations = per_region;
+    let mut regions = BinaryHeap::new();
+    regions.push(genz_malik(vec![0.5; dimension], vec![0.5; dimension], &f).ok_or(evaluations)?);
+    loop {
+        let value: Complex64 = regions.iter().map(|r| r.value).sum();
+        let error: f64 = regions.iter().map(|r| r.error).sum();
+        let done = error <= (relative * value.norm()).max(absolute) || !error.is_finite();
+        if done || evaluations + 2 * per_region > max_evaluations {
+            return Ok(Cubature { value, error, evaluations });
+        }
+        let worst: Region = regions.pop().unwrap();
+        let mut half_width = worst.half_width.clone();
+        half_width[worst.split] /= 2.0;
+        for sign in [-1.0, 1.0] {
+            let mut center = worst.center.clone();
+            center[worst.split] += sign * half_width[worst.split];
+            evaluations += per_region;
+            regions.push(genz_malik(center, half_width.clone(), &f).ok_or(evaluations)?);
+        }
+    }
+}
+
+/// The degree 7 rule of Genz and Malik on a region, with the difference to
+/// the embedded degree 5 rule as error estimate.
+fn genz_malik(center: Vec<f64>, half_width: Vec<f64>, f: &impl Fn(&[f64]) -> Option<Complex64>) -> Option<Region> {
+    let n = center.len();
+    let nf = n as f64;
+    let (l2, l3, l4, l5) = ((9.0f64 / 70.0).sqrt(), (9.0f64 / 10.0).sqrt(), (9.0f64 / 10.0).sqrt(), (9.0f64 / 19.0).sqrt());
+    let at = |offset: &[f64]| {
+        let point: Vec<f64> = (0..n).map(|i| center[i] + half_width[i] * offset[i]).collect();
+        f(&point)
+    };
+    let axis = |i: usize, l: f64| {
+        let mut offset = vec![0.0; n];
+        offset[i] = l;
+        offset
+    };
+
+    let f1 = at(&vec![0.0; n])?;
+    let (mut f2, mut f3) = (Complex64::new(0.0, 0.0), Complex64::new(0.0, 0.0));
+    let mut split = 0;
+    let mut largest = -1.0;
+    for i in 0..n {
+        let a = at(&axis(i, l2))? + at(&axis(i, -l2))?;
+        let b = at(&axis(i, l3))? + at(&axis(i, -l3))?;
+        f2 += a;
+        f3 += b;
+        let difference = (a - 2.0 * f1 - (l2 * l2 / (l3 * l3)) * (b - 2.0 * f1)).norm();
+        if difference > largest {
+            largest = difference;
+            split = i;
+        }
+    }
+    let mut f4 = Complex64::new(0.0, 0.0);
+    for i in 0..n {
+        for j in i + 1..n {
+            for (si, sj) in [(1.0, 1.0), (1.0, -1.0), (-1.0, 1.0), (-1.0, -1.0)] {
+                let mut offset = vec![0.0; n];
+                offset[i] = si * l4;
+                offset[j] = sj * l4;
+                f4 += at(&offset)?;
+            }
+        }
+    }
+    let mut f5 = Complex64::new(0.0, 0.0);
+    for signs in 0..1usize << n {
+        let offset: Vec<f64> = (0..n).map(|i| if signs >> i & 1 == 1 { l5 } else { -l5 }).collect();
+        f5 += at(&offset)?;
+    }
+
+    let volume: f64 = half_width.iter().map(|h| 2.0 * h).product();
+    let degree7 = f1 * (12824.0 - 9120.0 * nf + 400.0 * nf * nf) / 19683.0
+        + f2 * (980.0 / 6561.0)
+        + f3 * (1820.0 - 400.0 * nf) / 19683.0
+        + f4 * (200.0 / 19683.0)
+        + f5 * (6859.0 / 19683.0 / (1 << n) as f64);
+    let degree5 = f1 * (729.0 - 950.0 * nf + 50.0 * nf * nf) / 729.0
+        + f2 * (245.0 / 486.0)
+        + f3 * (265.0 - 100.0 * nf) / 1458.0
+        + f4 * (25.0 / 729.0);
+    Some(Region {
+        center,
+        half_width,
+        value: degree7 * volume,
+        error: ((degree7 - degree5) * volume).norm(),
+        split,
+    })
+}
@@ -0,0 +1,196 @@
+//! The 1- to 4-point functions of ONI ("Oneloop with Numerical
+//! Integration", A. van Hameren, arXiv:1007.4716), vendored in `oneloop/ONI`
+//! and built with the `oni` feature, as a reference independent of OneLOop.
+//!
+//! ONI evaluates the IR-divergent parts analytically and the remaining finite
+//! integrals numerically, in the normalization of OneLOop. Instead of Cuba,
+//! its calls to `cuhre` go to the adaptive cubature of the `numeric` module,
+//! with at most `maxeval` integrand evaluations per integral.
+//!
+//! The renormalization scale and on-shell threshold are taken from
+//! `renormalization_scale` and `onshell_threshold` at every call, and ONI's
+//! messages are switched off.
+use crate::numeric::cubature;
+use crate::{OLOResult, onshell_threshold, renormalization_scale};
+use num_complex::Complex64;
+use std::sync::Mutex;
+
+/// The integrand of `cuhre`, `f(ndim, x, ncomp, f)` with Fortran conventions.
+type Integrand = extern "C" fn(*const i32, *const f64, *const i32, *mut f64);
+
+/// ONI keeps its settings in common blocks, so calls are serialized. Holds
+/// whether ONI has been initialized.
+static ONI: Mutex<bool> = Mutex::new(false);
+
+mod ffi {
+    use super::*;
+    unsafe extern "C" {
+        pub fn avh_oni_a0c_(r: *mut Complex64, m: *const Complex64);
+        pub fn avh_oni_b0c_(r: *mut Complex64, p: *const Complex64, m1: *const Complex64, m2: *const Complex64);
+        pub fn avh_oni_c0c_(
+            r: *mut Complex64,
+            p1: *const Complex64,
+            p2: *const Complex64,
+            p3: *const Complex64,
+            m1: *const Complex64,
+            m2: *const Complex64,
+            m3: *const Complex64,
+        );
+        pub fn avh_oni_d0c_(
+            r: *mut Complex64,
+            p1: *const Complex64,
+            p2: *const Complex64,
+            p3: *const Complex64,
+            p4: *const Complex64,
+            p12: *const Complex64,
+            p23: *const Complex64,
+            m1: *const Complex64,
+            m2: *const Complex64,
+            m3: *const Complex64,
+            m4: *const Complex64,
+        );
+        pub fn avh_oni_maxeval_set_(maxeval: *const i32);
+        pub fn avh_oni_maxeval_() -> i32;
+        pub fn avh_oni_mu_set_(mu: *const f64);
+        pub fn avh_oni_onshell_(threshold: *const f64);
+        pub fn avh_oni_unit_(unit: *const i32);
+        pub fn avh_oni_unitcuba_set_(unit: *const i32);
+    }
+}
+
+/// Computes the 1-point function with ONI, see `one_point`.
+pub fn one_point(m: impl Into<Complex64>) -> OLOResult {
+    let m = m.into();
+    call(|r| unsafe { ffi::avh_oni_a0c_(r, &m) })
+}
+
+/// Computes the 2-point function with ONI, see `two_point`.
+pub fn two_point(p: f64, m1: impl Into<Complex64>, m2: impl Into<Complex64>) -> OLOResult {
+    let (m1, m2) = (m1.into(), m2.into());
+    call(|r| unsafe { ffi::avh_oni_b0c_(r, &p.into(), &m1, &m2) })
+}
+
+/// Computes the 3-point function with ONI, see `three_point`.
+pub fn three_point(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+) -> OLOResult {
+    let (m1, m2, m3) = (m1.into(), m2.into(), m3.into());
+    call(|r| unsafe { ffi::avh_oni_c0c_(r, &p1.into(), &p2.into(), &p3.into(), &m1, &m2, &m3) })
+}
+
+/// Computes the 4-point function with ONI, see `four_point`.
+#[allow(clippy::too_many_arguments)]
+pub fn four_point(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    p4: f64,
+    p12: f64,
+    p23: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+    m4: impl Into<Complex64>,
+) -> OLOResult {
+    let (m1, m2, m3, m4) = (m1.into(), m2.into(), m3.into(), m4.into());
+    call(|r| unsafe {
+        ffi::avh_oni_d0c_(r, &p1.into(), &p2.into(), &p3.into(), &p4.into(), &p12.into(), &p23.into(), &m1, &m2, &m3, &m4)
+    })
+}
+
+/// Sets the largest number of integrand evaluations per numerical integral,
+/// by default 1000000.
+pub fn set_maxeval(maxeval: usize) {
+    let maxeval = maxeval.min(i32::MAX as usize) as i32;
+    let _lock = lock();
+    unsafe { ffi::avh_oni_maxeval_set_(&maxeval) }
+}
+
+/// Returns the largest number of integrand evaluations per numerical integral.
+pub fn maxeval() -> usize {
+    let _lock = lock();
+    unsafe { ffi::avh_oni_maxeval_() as usize }
+}
+
+/// Locks ONI, silencing it on first use.
+fn lock() -> std::sync::MutexGuard<'static, bool> {
+    let mut initialized = ONI.lock().unwrap();
+    if !*initialized {
+        *initialized = true;
+        unsafe {
+            ffi::avh_oni_unit_(&0);
+            ffi::avh_oni_unitcuba_set_(&0);
+        }
+    }
+    initialized
+}
+
+/// Calls an ONI routine with the current settings.
+fn call(f: impl FnOnce(*mut Complex64)) -> OLOResult {
+    let _lock = lock();
+    // Keep `cuhre_` in the link, as it is only referenced from ONI
+    std::hint::black_box(cuhre_ as *const ());
+    let mu = renormalization_scale();
+    unsafe {
+        ffi::avh_oni_mu_set_(&mu);
+        if let Some(threshold) = onshell_threshold() {
+            ffi::avh_oni_onshell_(&threshold);
+        }
+    }
+    let mut r = OLOResult::default();
+    f(r.as_mut_ptr());
+    r
+}
+
+/// Stand-in for the Fortran interface of Cuba 2's `cuhre`, for the integrands
+/// of ONI with two components, the real and imaginary part.
+///
+/// `flags`, `mineval` and `key` are ignored, and the χ² probabilities are zero.
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+extern "C" fn cuhre_(
+    ndim: *const i32,
+    ncomp: *const i32,
+    integrand: Integrand,
+    _userdata: *const i32,
+    epsrel: *const f64,
+    epsabs: *const f64,
+    _flags: *const i32,
+    _mineval: *const i32,
+    maxeval: *const i32,
+    _key: *const i32,
+    nregions: *mut i32,
+    neval: *mut i32,
+    fail: *mut i32,
+    integral: *mut f64,
+    error: *mut f64,
+    prob: *mut f64,
+) {
+    // SAFETY: Fortran passes every argument by reference, and the arrays
+    // have `ncomp` elements.
+    unsafe {
+        let (dimension, components) = (*ndim as usize, *ncomp as usize);
+        let f = |x: &[f64]| {
+            let mut value = vec![0.0; components];
+            integrand(ndim, x.as_ptr(), ncomp, value.as_mut_ptr());
+            Some(Complex64::new(value[0], value[1]))
+        };
+        let result = cubature(dimension, f, *epsrel, *epsabs, (*maxeval).max(0) as usize)
+            .unwrap_or_else(|_| unreachable!("the integrand is defined everywhere"));
+        // The initial region plus two per subdivision
+        let per_region = 1 + 4 * dimension + 2 * dimension * (dimension - 1) + (1 << dimension);
+        *nregions = (result.evaluations / per_region).div_ceil(2) as i32;
+        *neval = result.evaluations as i32;
+        *fail = (result.error > (*epsrel * result.value.norm()).max(*epsabs)) as i32;
+        for (i, value) in [result.value.re, result.value.im].into_iter().enumerate().take(components) {
+            *integral.add(i) = value;
+            *error.add(i) = result.error;
+            *prob.add(i) = 0.0;
+        }
+    }
+}
@@ -0,0 +1,735 @@
+//! Passarino-Veltman reduction of one-loop tensor integrals.
+//!
+//! Tensor integrals are decomposed into the metric tensor and the momenta
+//! `P_k` flowing through the propagators, following the Denner / LoopTools
+//! conventions. For the triangle with propagators
+//! `[q²-m1][(q+P1)²-m2][(q+P2)²-m3]`, `P1 = k1` and `P2 = k1 + k2`:
+//!
+//! ```text
+//! C^μ   = P1^μ C1 + P2^μ C2
+//! C^μν  = g^μν C00 + Σ_ij P_i^μ P_j^ν C_ij
+//! C^μνρ = Σ_i (g^μν P_i^ρ + g^νρ P_i^μ + g^ρμ P_i^ν) C00i + Σ_ijk P_i^μ P_j^ν P_k^ρ C_ijk
+//! ```
+//!
+//! and likewise for the box with `P3 = k1 + k2 + k3`, up to `D0000` and `D_ijkl`.
+//!
+//! The coefficients are reduced to the scalar functions of this crate by
+//! inverting the Gram matrix `Z_kl = 2 P_k·P_l`. When its determinant is small,
+//! the reduction switches to an expansion in the Gram determinant (Denner and
+//! Dittmaier, Nucl. Phys. B 734 (2006) 62), which needs lower-point coefficients
+//! of higher rank but no inverse Gram matrix. Every coefficient is returned
+//! as an `OLOResult` in the same normalization as the scalar functions; the
+//! rational terms that arise from the `D`-dependence of the recursion are
+//! included.
+//!
+//! The scalar functions and the 2-point tensor coefficients are evaluated by
+//! the current backend, see `backend`. With the `pure-rust` feature and
+//! without OneLOop, the reduction of finite triangles therefore works, while
+//! `four_point` panics, as `crate::four_point` does.
+use crate::kinematics::{Kinematics, determinant};
+use crate::{OLOResult, two_point_coefficients};
+use num_complex::Complex64;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Relative size of the Gram determinant below which the standard reduction
+/// is replaced by the expansion in the Gram determinant.
+///
+/// The determinant is compared to `max|Z_kl|^n` for an `n × n` Gram matrix.
+pub const GRAM_THRESHOLD: f64 = 1e-6;
+
+/// Highest tensor rank supported for the 3-point function.
+pub const MAX_THREE_POINT_RANK: usize = 3;
+
+/// Highest tensor rank supported for the 4-point function.
+pub const MAX_FOUR_POINT_RANK: usize = 4;
+
+/// Largest expansion parameter `|det(Z)| / |Σ_k Z̃_lk f_k|` for which the Gram
+/// expansion is used.
+const MAX_EXPANSION_PARAMETER: f64 = 1e-2;
+
+/// Maximal number of iterations of the Gram expansion.
+const MAX_EXPANSION_ORDER: usize = 6;
+
+/// Relative truncation error aimed at by the Gram expansion.
+const EXPANSION_PRECISION: f64 = 1e-15;
+
+/// Errors reported by the Passarino-Veltman reduction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PVError {
+    /// The Gram determinant is too small for the inversion to be reliable and
+    /// the expansion in the Gram determinant does not converge.
+    ///
+    /// `relative_determinant` is the determinant divided by `max|Z_kl|^n`.
+    SmallGramDeterminant { relative_determinant: f64 },
+    /// The requested tensor rank is not supported.
+    UnsupportedRank { rank: usize, max_rank: usize },
+}
+
+impl fmt::Display for PVError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PVError::SmallGramDeterminant { relative_determinant } => write!(
+                f,
+                "Gram determinant too small for Passarino-Veltman reduction \
+                 (relative value {:e}, threshold {:e})",
+                relative_determinant, GRAM_THRESHOLD
+            ),
+            PVError::UnsupportedRank { rank, max_rank } => write!(
+                f,
+                "tensor rank {} is not supported (maximum rank {})",
+                rank, max_rank
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PVError {}
+
+/// The tensor coefficients of a one-loop integral up to a given rank.
+///
+/// Coefficients are addressed by their index list in the usual notation, where
+/// each pair of zeros stands for a metric tensor: `&[]` is the scalar
+/// function, `&[1]` is `C1`, `&[0, 0]` is `C00` and `&[0, 0, 2]` is `C002`.
+/// The order of the indices does not matter.
+#[derive(Debug, Clone)]
+pub struct TensorCoefficients {
+    rank: usize,
+    values: BTreeMap<Vec<usize>, OLOResult>,
+}
+
+impl TensorCoefficients {
+    /// The highest rank contained in this set of coefficients.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// Returns the coefficient with the given indices, or `None` if it is
+    /// not part of the decomposition.
+    pub fn get(&self, indices: &[usize]) -> Option<OLOResult> {
+        let mut key = indices.to_vec();
+        key.sort_unstable();
+        self.values.get(&key).copied()
+    }
+
+    /// Iterates over all coefficients with their (sorted) index lists.
+    pub fn iter(&self) -> impl Iterator<Item = (&[usize], &OLOResult)> {
+        self.values.iter().map(|(k, v)| (k.as_slice(), v))
+    }
+}
+
+/// Computes the tensor coefficients of the 3-point function up to `rank`.
+///
+/// # Arguments
+/// * `p1` - The squared momentum of the first leg, `k1²`.
+/// * `p2` - The squared momentum of the second leg, `k2²`.
+/// * `p3` - The squared momentum of the third leg, `(k1 + k2)²`.
+/// * `m1` - The squared mass of the first propagator. Imaginary part should be non-positive.
+/// * `m2` - The squared mass of the second propagator. Imaginary part should be non-positive.
+/// * `m3` - The squared mass of the third propagator. Imaginary part should be non-positive.
+/// * `rank` - The highest tensor rank to compute, at most `MAX_THREE_POINT_RANK`.
+///
+/// # Returns
+/// All coefficients `C0`, `C1`, `C2`, `C00`, `C11`, ..., `C222` up to `rank`, or
+/// a `PVError` if the kinematics is too degenerate or the rank is unsupported.
+///
+/// # Notes
+/// The momenta in the decomposition are `P1 = k1` and `P2 = k1 + k2`.
+/// The normalization is that of `three_point`.
+pub fn three_point(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+    rank: usize,
+) -> Result<TensorCoefficients, PVError> {
+    if rank > MAX_THREE_POINT_RANK {
+        return Err(PVError::UnsupportedRank { rank, max_rank: MAX_THREE_POINT_RANK });
+    }
+    let (m1, m2, m3) = (m1.into(), m2.into(), m3.into());
+    Integral::new(Kinematics::three_point(p1, p2, p3, m1, m2, m3)).coefficients(rank)
+}
+
+/// Computes the tensor coefficients of the 4-point function up to `rank`.
+///
+/// # Arguments
+/// * `p1` - The squared momentum of the first leg, `k1²`.
+/// * `p2` - The squared momentum of the second leg, `k2²`.
+/// * `p3` - The squared momentum of the third leg, `k3²`.
+/// * `p4` - The squared momentum of the fourth leg, `(k1 + k2 + k3)²`.
+/// * `p12` - The squared momentum `(k1 + k2)²`.
+/// * `p23` - The squared momentum `(k2 + k3)²`.
+/// * `m1` - The squared mass of the first propagator. Imaginary part should be non-positive.
+/// * `m2` - The squared mass of the second propagator. Imaginary part should be non-positive.
+/// * `m3` - The squared mass of the third propagator. Imaginary part should be non-positive.
+/// * `m4` - The squared mass of the fourth propagator. Imaginary part should be non-positive.
+/// * `rank` - The highest tensor rank to compute, at most `MAX_FOUR_POINT_RANK`.
+///
+/// # Returns
+/// All coefficients `D0`, `D1`, ..., `D00`, `D11`, ..., `D0000`, ..., `D3333` up to
+/// `rank`, or a `PVError` if the kinematics is too degenerate or the rank is
+/// unsupported.
+///
+/// # Notes
+/// The momenta in the decomposition are `P1 = k1`, `P2 = k1 + k2` and
+/// `P3 = k1 + k2 + k3`. The normalization is that of `four_point`.
+/// The scalar box is evaluated by the current backend, so with the default
+/// one this panics if OneLOop is not linked, see `pure-rust`.
+#[allow(clippy::too_many_arguments)]
+pub fn four_point(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    p4: f64,
+    p12: f64,
+    p23: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+    m4: impl Into<Complex64>,
+    rank: usize,
+) -> Result<TensorCoefficients, PVError> {
+    if rank > MAX_FOUR_POINT_RANK {
+        return Err(PVError::UnsupportedRank { rank, max_rank: MAX_FOUR_POINT_RANK });
+    }
+    let (m1, m2, m3, m4) = (m1.into(), m2.into(), m3.into(), m4.into());
+    let kinematics = Kinematics::four_point(p1, p2, p3, p4, p12, p23, m1, m2, m3, m4);
+    Integral::new(kinematics).coefficients(rank)
+}
+
+/// A one-loop integral with propagators `[(q+P_i)² - m_i]`, `P_0 = 0`, together
+/// with the integrals obtained by removing one of its propagators.
+struct Integral {
+    kinematics: Kinematics,
+    /// `pinched[k]` is this integral with propagator `k` removed. For `k = 0`
+    /// the loop momentum is shifted such that `P_1` becomes the new origin.
+    pinched: Vec<Integral>,
+    reduction: Option<Result<Reduction, PVError>>,
+    cache: HashMap<Vec<usize>, OLOResult>,
+    /// Coefficients of the Gram expansion, by index list and iteration.
+    expanded: HashMap<(Vec<usize>, usize), OLOResult>,
+}
+
+/// How momentum indices are removed from the coefficients of an integral.
+#[derive(Debug, Clone)]
+enum Reduction {
+    /// Standard reduction with the inverse Gram matrix.
+    Inverse(Vec<Vec<f64>>),
+    /// Expansion in the Gram determinant for nearly singular Gram matrices.
+    Expansion(Expansion),
+}
+
+/// Data of the expansion in the Gram determinant. With the adjugate `Z̃` of
+/// the Gram matrix and `u_l = Σ_k Z̃_lk f_k`, the reduction formula becomes
+///
+/// `u_l T_I = Σ_k Z̃_lk S_{k I} - 2 Σ_r Z̃_{l i_r} T_{00 I\i_r} - det(Z) T_{l I}`
+///
+/// which is solved iteratively, each iteration gaining a factor `det(Z) / u_l`.
+#[derive(Debug, Clone)]
+struct Expansion {
+    /// The label `l` with the largest `|u_l|`.
+    label: usize,
+    /// Row `l` of the adjugate Gram matrix.
+    adjugate: Vec<f64>,
+    u: Complex64,
+    determinant: f64,
+    /// Number of iterations.
+    order: usize,
+}
+
+impl Integral {
+    fn new(kinematics: Kinematics) -> Self {
+        let n = kinematics.size();
+        let pinched = if n > 1 {
+            (0..n)
+                .map(|k| {
+                    let keep: Vec<usize> = (0..n).filter(|&i| i != k).collect();
+                    Integral::new(kinematics.pinch(&keep))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Self {
+            kinematics,
+            pinched,
+            reduction: None,
+            cache: HashMap::new(),
+            expanded: HashMap::new(),
+        }
+    }
+
+    /// Number of propagators.
+    fn size(&self) -> usize {
+        self.kinematics.size()
+    }
+
+    fn masses(&self) -> &[Complex64] {
+        self.kinematics.masses()
+    }
+
+    fn invariants(&self) -> &[Vec<f64>] {
+        self.kinematics.invariants()
+    }
+
+    /// `f_k = P_k² - m_k + m_0`
+    fn f(&self, k: usize) -> Complex64 {
+        self.invariants()[0][k] - self.masses()[k] + self.masses()[0]
+    }
+
+    /// Computes every coefficient up to `rank`.
+    fn coefficients(mut self, rank: usize) -> Result<TensorCoefficients, PVError> {
+        let mut values = BTreeMap::new();
+        for key in keys(self.size(), rank) {
+            let value = self.coefficient(&key)?;
+            values.insert(key, value);
+        }
+        Ok(TensorCoefficients { rank, values })
+    }
+
+    /// Returns the coefficient for a sorted index list.
+    fn coefficient(&mut self, key: &[usize]) -> Result<OLOResult, PVError> {
+        if let Some(value) = self.cache.get(key) {
+            return Ok(*value);
+        }
+        let value = if self.size() == 2 && key.len() <= 2 {
+            self.two_point(key)
+        } else if key.is_empty() {
+            self.scalar()
+        } else if self.size() == 2
+            && self.invariants()[0][1] == 0.0
+            && self.masses()[0] == self.masses()[1]
+        {
+            self.degenerate_two_point(key)?
+        } else {
+            match self.reduction()? {
+                Reduction::Inverse(inverse) if key[key.len() - 1] > 0 => {
+                    self.reduce_index(key, &inverse)?
+                }
+                Reduction::Inverse(_) => self.reduce_metric(key)?,
+                Reduction::Expansion(expansion) => {
+                    self.expanded(key, expansion.order, &expansion)?
+                }
+            }
+        };
+        self.cache.insert(key.to_vec(), value);
+        Ok(value)
+    }
+
+    /// The scalar integral, for more than two propagators.
+    fn scalar(&self) -> OLOResult {
+        let m = self.masses();
+        let r = self.invariants();
+        match self.size() {
+            1 => crate::one_point(m[0]),
+            3 => crate::three_point(r[0][1], r[1][2], r[0][2], m[0], m[1], m[2]),
+            4 => crate::four_point(
+                r[0][1], r[1][2], r[2][3], r[0][3], r[0][2], r[1][3],
+                m[0], m[1], m[2], m[3],
+            ),
+            n => unreachable!("no scalar {}-point function", n),
+        }
+    }
+
+    /// The rank ≤ 2 coefficients of the 2-point function, straight from the
+    /// backend.
+    fn two_point(&mut self, key: &[usize]) -> OLOResult {
+        let b = two_point_coefficients(self.invariants()[0][1], self.masses()[0], self.masses()[1]);
+        self.cache.insert(vec![], b.b0);
+        self.cache.insert(vec![1], b.b1);
+        self.cache.insert(vec![0, 0], b.b00);
+        self.cache.insert(vec![1, 1], b.b11);
+        self.cache[key]
+    }
+
+    /// The 2-point function at `p² = 0` with equal masses, where neither the
+    /// standard reduction nor the expansion applies. With `a` metric pairs and
+    /// `r` momentum indices, the Feynman-parameter integral gives
+    ///
+    /// `B_{0..0 1..1} = (-1)^r m^a / (2^a a! (r+1)) (B0 + H_a)`
+    ///
+    /// where `H_a` is the harmonic number.
+    fn degenerate_two_point(&mut self, key: &[usize]) -> Result<OLOResult, PVError> {
+        let pairs = key.iter().filter(|&&i| i == 0).count() / 2;
+        let ones = key.len() - 2 * pairs;
+        let harmonic: f64 = (1..=pairs).map(|j| 1.0 / j as f64).sum();
+        let denominator: f64 = (1..=pairs).map(|j| 2.0 * j as f64).product::<f64>()
+            * (ones + 1) as f64;
+        let sign = if ones % 2 == 1 { -1.0 } else { 1.0 };
+        let b0 = self.coefficient(&[])?;
+        let constant = OLOResult::from_values([harmonic.into(), 0.0.into(), 0.0.into()]);
+        Ok((b0 + constant) * (self.masses()[0].powi(pairs as i32) * (sign / denominator)))
+    }
+
+    /// Removes the first momentum index by contracting with `2 P_k`:
+    ///
+    /// `T_{k I} = Σ_n Z⁻¹_kn [S_{n I} - 2 Σ_r δ_{n i_r} T_{00 I\i_r}]`
+    fn reduce_index(&mut self, key: &[usize], inverse: &[Vec<f64>]) -> Result<OLOResult, PVError> {
+        let position = key.iter().position(|&i| i > 0).unwrap();
+        let k = key[position];
+        let mut rest = key.to_vec();
+        rest.remove(position);
+
+        let mut result = OLOResult::default();
+        for n in 1..self.size() {
+            let mut s = self.pinched_coefficient(n, &rest)?
+                - self.shifted_coefficient(&rest)?
+                - self.coefficient(&rest)? * self.f(n);
+            for r in 0..rest.len() {
+                if rest[r] == n {
+                    let mut lowered = rest.clone();
+                    lowered.remove(r);
+                    lowered.splice(0..0, [0, 0]);
+                    s -= self.coefficient(&lowered)? * 2.0;
+                }
+            }
+            result += s * inverse[k - 1][n - 1];
+        }
+        Ok(result)
+    }
+
+    /// Removes a pair of zero indices by contracting with the metric:
+    ///
+    /// `2(D+P-N-1) T_{00 J} = 2 m_0 T_J + 2 T_J(0) + Σ_i [T_{J i}(0) + f_i T_{J i}]`
+    fn reduce_metric(&mut self, key: &[usize]) -> Result<OLOResult, PVError> {
+        let rest = key[2..].to_vec();
+        let mut rhs = self.coefficient(&rest)? * (2.0 * self.masses()[0])
+            + self.shifted_coefficient(&rest)? * 2.0;
+        for i in 1..self.size() {
+            let raised = with_index(&rest, i);
+            rhs += self.shifted_coefficient(&raised)? + self.coefficient(&raised)? * self.f(i);
+        }
+        // 1/(2(D+P-N-1)) with D = 4-2ε, expanded in ε
+        let c = (3 + key.len()) as f64 - self.size() as f64;
+        Ok(times_series(rhs, [0.5 / c, 1.0 / (c * c), 2.0 / (c * c * c)]))
+    }
+
+    /// Returns a coefficient of the Gram expansion after `level` iterations.
+    fn expanded(
+        &mut self,
+        key: &[usize],
+        level: usize,
+        expansion: &Expansion,
+    ) -> Result<OLOResult, PVError> {
+        if key.is_empty() || (self.size() == 2 && key.len() <= 2) {
+            return self.coefficient(key);
+        }
+        if let Some(value) = self.expanded.get(&(key.to_vec(), level)) {
+            return Ok(*value);
+        }
+        if key[0] > 0 {
+            let value = self.expand_index(key, level, expansion)?;
+            self.expanded.insert((key.to_vec(), level), value);
+        } else {
+            self.expand_metric(key, level, expansion)?;
+        }
+        Ok(self.expanded[&(key.to_vec(), level)])
+    }
+
+    /// `Σ_k Z̃_lk S_{k I}` without the `f_k T_I` part of `S`.
+    fn adjugate_source(
+        &mut self,
+        key: &[usize],
+        expansion: &Expansion,
+    ) -> Result<OLOResult, PVError> {
+        let shifted = self.shifted_coefficient(key)?;
+        let mut result = OLOResult::default();
+        for k in 1..self.size() {
+            result += (self.pinched_coefficient(k, key)? - shifted) * expansion.adjugate[k - 1];
+        }
+        Ok(result)
+    }
+
+    /// Expands a coefficient without metric indices:
+    ///
+    /// `u_l T_I = Σ_k Z̃_lk S_{k I} - 2 Σ_r Z̃_{l i_r} T_{00 I\i_r} - det(Z) T_{l I}`
+    fn expand_index(
+        &mut self,
+        key: &[usize],
+        level: usize,
+        expansion: &Expansion,
+    ) -> Result<OLOResult, PVError> {
+        let mut result = self.adjugate_source(key, expansion)?;
+        for r in 0..key.len() {
+            let mut lowered = key.to_vec();
+            lowered.remove(r);
+            lowered.splice(0..0, [0, 0]);
+            let weight = 2.0 * expansion.adjugate[key[r] - 1];
+            result -= self.expanded(&lowered, level, expansion)? * weight;
+        }
+        if level > 0 {
+            let raised = with_index(key, expansion.label);
+            result -= self.expanded(&raised, level - 1, expansion)? * expansion.determinant;
+        }
+        Ok(result * expansion.u.inv())
+    }
+
+    /// Expands all coefficients with the metric pairs and number of momentum
+    /// indices of `key`. Inserting the expansion of `T_{J i}` into the metric
+    /// contraction gives
+    ///
+    /// `2(D+P-N) T_{00 J} + (2/u_l) Σ_r Z̃_{l j_r} Σ_i f_i T_{00 J\j_r i} = R_J`
+    ///
+    /// which couples coefficients with the same number of indices. The system
+    /// is solved exactly by successive contractions with `f`.
+    fn expand_metric(
+        &mut self,
+        key: &[usize],
+        level: usize,
+        expansion: &Expansion,
+    ) -> Result<(), PVError> {
+        let n = self.size() - 1;
+        let pairs = key.iter().filter(|&&i| i == 0).count() / 2;
+        let labels = key.len() - 2 * pairs;
+        let u = expansion.u;
+
+        let mut rhs = HashMap::new();
+        for set in multisets(n, labels) {
+            let mut rest = vec![0; 2 * pairs - 2];
+            rest.extend(&set);
+            let mut r = self.expanded(&rest, level, expansion)? * (2.0 * self.masses()[0])
+                + self.shifted_coefficient(&rest)? * 2.0;
+            for i in 1..=n {
+                let raised = with_index(&rest, i);
+                r += self.shifted_coefficient(&raised)?
+                    + self.adjugate_source(&raised, expansion)? * (self.f(i) / u);
+                if level > 0 {
+                    let next = with_index(&raised, expansion.label);
+                    r -= self.expanded(&next, level - 1, expansion)?
+                        * (self.f(i) * expansion.determinant / u);
+                }
+            }
+            rhs.insert(set, r);
+        }
+
+        let mut contracted = vec![rhs];
+        for j in 1..=labels {
+            let previous = &contracted[j - 1];
+            let next = multisets(n, labels - j)
+                .into_iter()
+                .map(|set| {
+                    let value = (1..=n).fold(OLOResult::default(), |acc, t| {
+                        acc + previous[&with_index(&set, t)] * self.f(t)
+                    });
+                    (set, value)
+                })
+                .collect();
+            contracted.push(next);
+        }
+
+        // The j-fold contraction has 2(D+P-N+j) on the diagonal.
+        let base = (4 + key.len()) as f64 - self.size() as f64;
+        let mut solved: HashMap<Vec<usize>, OLOResult> = HashMap::new();
+        for j in (0..=labels).rev() {
+            let c = base + j as f64;
+            solved = multisets(n, labels - j)
+                .into_iter()
+                .map(|set| {
+                    let mut value = contracted[j][&set];
+                    for r in 0..set.len() {
+                        let mut lowered = set.clone();
+                        lowered.remove(r);
+                        value -= solved[&lowered] * (2.0 * expansion.adjugate[set[r] - 1] / u);
+                    }
+                    (set, times_series(value, [0.5 / c, 1.0 / (c * c), 2.0 / (c * c * c)]))
+                })
+                .collect();
+        }
+        for (set, value) in solved {
+            let mut key = vec![0; 2 * pairs];
+            key.extend(set);
+            self.expanded.insert((key, level), value);
+        }
+        Ok(())
+    }
+
+    /// The coefficient of the integral with propagator `n ≥ 1` removed,
+    /// expressed in the tensor basis of this integral.
+    fn pinched_coefficient(&mut self, n: usize, key: &[usize]) -> Result<OLOResult, PVError> {
+        if key.contains(&n) {
+            return Ok(OLOResult::default());
+        }
+        let key: Vec<usize> = key.iter().map(|&i| if i > n { i - 1 } else { i }).collect();
+        self.pinched[n].coefficient(&key)
+    }
+
+    /// The coefficient of the integral with propagator `0` removed, expressed in
+    /// the tensor basis of this integral. Undoing the shift `q → q - P_1` of the
+    /// loop momentum maps every index `1` to `-(1 + Σ_j j)` in the shifted basis.
+    fn shifted_coefficient(&mut self, key: &[usize]) -> Result<OLOResult, PVError> {
+        if self.size() == 1 {
+            return Ok(OLOResult::default());
+        }
+        let ones = key.iter().filter(|&&i| i == 1).count();
+        let base: Vec<usize> = key
+            .iter()
+            .filter(|&&i| i != 1)
+            .map(|&i| if i > 1 { i - 1 } else { i })
+            .collect();
+        let labels = self.size() - 2;
+
+        let mut result = OLOResult::default();
+        for direct in 0..=ones {
+            let weight = binomial(ones, direct) as f64;
+            for tuple in tuples(labels, ones - direct) {
+                let mut shifted = base.clone();
+                shifted.extend(tuple);
+                shifted.sort_unstable();
+                result += self.pinched[0].coefficient(&shifted)? * weight;
+            }
+        }
+        Ok(if ones % 2 == 1 { -result } else { result })
+    }
+
+    /// Chooses between the inverse Gram matrix and the Gram expansion.
+    fn reduction(&mut self) -> Result<Reduction, PVError> {
+        if self.reduction.is_none() {
+            let gram = self.kinematics.gram_matrix();
+            self.reduction = Some(self.select_reduction(&gram));
+        }
+        self.reduction.clone().unwrap()
+    }
+
+    fn select_reduction(&self, gram: &[Vec<f64>]) -> Result<Reduction, PVError> {
+        let n = gram.len();
+        if n == 0 {
+            return Ok(Reduction::Inverse(Vec::new()));
+        }
+        let determinant = determinant(gram);
+        let adjugate = adjugate(gram);
+        let scale = gram.iter().flatten().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+        let relative_determinant = if scale > 0.0 {
+            determinant / scale.powi(n as i32)
+        } else {
+            0.0
+        };
+        if relative_determinant.abs() >= GRAM_THRESHOLD {
+            let inverse = adjugate
+                .iter()
+                .map(|row| row.iter().map(|v| v / determinant).collect())
+                .collect();
+            return Ok(Reduction::Inverse(inverse));
+        }
+
+        let u: Vec<Complex64> = adjugate
+            .iter()
+            .map(|row| (1..=n).map(|k| self.f(k) * row[k - 1]).sum())
+            .collect();
+        let label = (0..n).max_by(|&a, &b| u[a].norm().total_cmp(&u[b].norm())).unwrap();
+        let parameter = determinant.abs() / u[label].norm();
+        if parameter.is_nan() || parameter > MAX_EXPANSION_PARAMETER {
+            return Err(PVError::SmallGramDeterminant { relative_determinant });
+        }
+        let order = if parameter > 0.0 {
+            let needed = (EXPANSION_PRECISION.ln() / parameter.ln()).ceil() as usize;
+            needed.saturating_sub(1).min(MAX_EXPANSION_ORDER)
+        } else {
+            0
+        };
+        Ok(Reduction::Expansion(Expansion {
+            label: label + 1,
+            adjugate: adjugate[label].clone(),
+            u: u[label],
+            determinant,
+            order,
+        }))
+    }
+}
+
+/// Multiplies a Laurent series by `a[0] + a[1] ε + a[2] ε²`, keeping orders up to ε⁰.
+fn times_series(r: OLOResult, a: [f64; 3]) -> OLOResult {
+    let [v0, v1, v2] = [r.epsilon_0(), r.epsilon_minus_1(), r.epsilon_minus_2()];
+    OLOResult::from_values([
+        v0 * a[0] + v1 * a[1] + v2 * a[2],
+        v1 * a[0] + v2 * a[1],
+        v2 * a[0],
+    ])
+}
+
+/// The adjugate matrix, built from cofactors so that it stays finite for
+/// singular matrices.
+fn adjugate(matrix: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = matrix.len();
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    let minor: Vec<Vec<f64>> = (0..n)
+                        .filter(|&row| row != j)
+                        .map(|row| {
+                            (0..n).filter(|&col| col != i).map(|col| matrix[row][col]).collect()
+                        })
+                        .collect();
+                    let sign = if (i + j) % 2 == 0 { 1.0 } else { -1.0 };
+                    sign * determinant(&minor)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Inserts index `i` into a sorted index list.
+fn with_index(key: &[usize], i: usize) -> Vec<usize> {
+    let mut key = key.to_vec();
+    let position = key.partition_point(|&j| j <= i);
+    key.insert(position, i);
+    key
+}
+
+/// All sorted index lists of an `n`-point integral up to `rank`.
+fn keys(n: usize, rank: usize) -> Vec<Vec<usize>> {
+    let mut keys = Vec::new();
+    for r in 0..=rank {
+        for pairs in 0..=r / 2 {
+            for labels in multisets(n - 1, r - 2 * pairs) {
+                let mut key = vec![0; 2 * pairs];
+                key.extend(labels);
+                keys.push(key);
+            }
+        }
+    }
+    keys
+}
+
+/// All non-decreasing sequences of length `len` over `1..=labels`.
+fn multisets(labels: usize, len: usize) -> Vec<Vec<usize>> {
+    let mut result = vec![vec![]];
+    for _ in 0..len {
+        result = result
+            .into_iter()
+            .flat_map(|s| {
+                let start = s.last().copied().unwrap_or(1);
+                (start..=labels).map(move |i| {
+                    let mut s = s.clone();
+                    s.push(i);
+                    s
+                })
+            })
+            .collect();
+    }
+    result
+}
+
+/// All sequences of length `len` over `1..=labels`.
+fn tuples(labels: usize, len: usize) -> Vec<Vec<usize>> {
+    let mut result = vec![vec![]];
+    for _ in 0..len {
+        result = result
+            .into_iter()
+            .flat_map(|s| {
+                (1..=labels).map(move |i| {
+                    let mut s = s.clone();
+                    s.push(i);
+                    s
+                })
+            })
+            .collect();
+    }
+    result
+}
+
+fn binomial(n: usize, k: usize) -> usize {
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
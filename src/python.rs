@@ -1,10 +1,15 @@
 use crate::TO_FEYNMAN;
+use crate::input::Point;
+use crate::scan::{Range, Scan, scan as run_scan, validate, variable_by_name};
 use crate::{
     OLOResult, OLOUnit
 };
 use num_complex::Complex;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::types::PyTuple;
 use pyo3::wrap_pyfunction;
+use std::collections::HashMap;
 
 #[pyclass]
 pub struct PyOLOResult {
@@ -41,6 +46,61 @@ impl PyOLOResult {
             self.inner.epsilon_minus_2()
         )
     }
+
+    fn to_mathematica(&self) -> String {
+        self.inner.to_mathematica()
+    }
+
+    fn to_form(&self) -> String {
+        self.inner.to_form()
+    }
+
+    fn to_latex(&self) -> String {
+        self.inner.to_latex()
+    }
+
+    /// Renders the result as a formula in Jupyter.
+    fn _repr_latex_(&self) -> String {
+        format!("${}$", self.inner.to_latex())
+    }
+}
+
+#[pyclass]
+pub struct PyScan {
+    inner: Scan,
+}
+
+#[pymethods]
+impl PyScan {
+    #[getter]
+    fn values(&self) -> Vec<Vec<f64>> {
+        self.inner.samples.iter().map(|s| s.values.clone()).collect()
+    }
+
+    #[getter]
+    fn results(&self) -> Vec<PyOLOResult> {
+        self.inner.samples.iter().map(|s| s.result.into()).collect()
+    }
+
+    fn to_csv(&self) -> String {
+        let mut out = Vec::new();
+        self.inner.write_csv(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn to_json_lines(&self) -> String {
+        let mut out = Vec::new();
+        self.inner.write_json_lines(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.samples.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("PyScan({} samples)", self.inner.samples.len())
+    }
 }
 
 #[pyfunction]
@@ -66,6 +126,7 @@ fn three_point(
 }
 
 #[pyfunction]
+#[allow(clippy::too_many_arguments)]
 fn four_point(
     p1: f64,
     p2: f64,
@@ -81,6 +142,56 @@ fn four_point(
     crate::four_point(p1, p2, p3, p4, p12, p23, m1, m2, m3, m4).into()
 }
 
+/// A range `(name, start, end, points)` or `(name, start, end, points, "log")`.
+fn range(legs: usize, tuple: &Bound<'_, PyTuple>) -> PyResult<Range> {
+    let (name, start, end, points): (String, f64, f64, usize) = match tuple.len() {
+        4 => tuple.extract()?,
+        5 => tuple.get_slice(0, 4).extract()?,
+        _ => return Err(PyValueError::new_err("expected a range (name, start, end, points[, spacing])")),
+    };
+    let variable = variable_by_name(legs, &name)
+        .ok_or_else(|| PyValueError::new_err(format!("unknown input '{}'", name)))?;
+    let spacing = match tuple.len() {
+        4 => "linear".to_string(),
+        _ => tuple.get_item(4)?.extract::<String>()?.to_lowercase(),
+    };
+    match spacing.as_str() {
+        "linear" => Ok(Range::linear(variable, start, end, points)),
+        "log" => Ok(Range::logarithmic(variable, start, end, points)),
+        _ => Err(PyValueError::new_err(format!("unknown spacing '{}', expected 'linear' or 'log'", spacing))),
+    }
+}
+
+#[pyfunction]
+fn scan(
+    function: &str,
+    momenta: Vec<f64>,
+    masses: Vec<Complex<f64>>,
+    ranges: Vec<Bound<'_, PyTuple>>,
+) -> PyResult<PyScan> {
+    let legs = match function.to_uppercase().as_str() {
+        "A0" => 1,
+        "B0" => 2,
+        "C0" => 3,
+        "D0" => 4,
+        _ => return Err(PyValueError::new_err(format!("unknown function '{}'", function))),
+    };
+    if momenta.len() != [0, 1, 3, 6][legs - 1] || masses.len() != legs {
+        return Err(PyValueError::new_err(format!("wrong number of momenta or masses for {}", function)));
+    }
+    let ranges = ranges.iter().map(|t| range(legs, t)).collect::<PyResult<Vec<_>>>()?;
+    let point = Point { legs, mu: crate::renormalization_scale(), momenta, masses, line: 0 };
+    validate(&point, &ranges).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(PyScan { inner: run_scan(&point, &ranges) })
+}
+
+#[pyfunction(signature = (expression, bindings = HashMap::new()))]
+fn evaluate(expression: &str, bindings: HashMap<String, Complex<f64>>) -> PyResult<PyOLOResult> {
+    crate::expression::evaluate(expression, &bindings)
+        .map(PyOLOResult::from)
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
 #[pyfunction]
 fn set_renormalization_scale(mu: f64) {
     crate::set_renormalization_scale(mu);
@@ -111,6 +222,8 @@ fn oneloop_bridge(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(two_point, m)?)?;
     m.add_function(wrap_pyfunction!(three_point, m)?)?;
     m.add_function(wrap_pyfunction!(four_point, m)?)?;
+    m.add_function(wrap_pyfunction!(scan, m)?)?;
+    m.add_function(wrap_pyfunction!(evaluate, m)?)?;
     m.add_function(wrap_pyfunction!(set_renormalization_scale, m)?)?;
     m.add_function(wrap_pyfunction!(set_log_level, m)?)?;
     m.add_function(wrap_pyfunction!(set_onshell_threshold, m)?)?;
@@ -0,0 +1,353 @@
+//! Scans of the scalar functions over one or more inputs, e.g. `B0` across
+//! a threshold in `p²` or a box across a Landau singularity in `s` and `t`.
+//!
+//! Each `Range` varies one input, numbered as in `derivative`, over linearly
+//! or logarithmically spaced values. The scan evaluates the function at every
+//! combination of values, with the last range varying fastest, at the current
+//! renormalization scale. A scanned mass is given as its real part and keeps
+//! the imaginary part of the base point.
+//!
+//! `Scan::write_csv` and `Scan::write_json_lines` export all inputs and
+//! Laurent coefficients of the samples. In both, the inputs are named as in
+//! the arguments of `one_point` to `four_point` (`p`, `p1`, ..., `p12`,
+//! `p23`, `m`, `m1`, ...), see `variable_name`.
+use crate::OLOResult;
+use crate::derivative::Variable;
+use crate::input::Point;
+use num_complex::Complex64;
+use std::fmt;
+use std::io::{self, Write};
+
+/// The names of the squared momenta of the functions with 1 to 4 legs.
+const MOMENTA: [&[&str]; 4] = [&[], &["p"], &["p1", "p2", "p3"], &["p1", "p2", "p3", "p4", "p12", "p23"]];
+
+/// Errors reported by `validate`, with the name of the input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScanError {
+    /// The function has no such input.
+    UnknownVariable { name: String },
+    /// Two ranges vary the same input.
+    ScannedTwice { name: String },
+    /// The range has no values.
+    NoPoints { name: String },
+    /// A logarithmic range has a vanishing bound or bounds of opposite sign.
+    LogarithmicBounds { name: String, start: f64, end: f64 },
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanError::UnknownVariable { name } => write!(f, "{} is not an input of the function", name),
+            ScanError::ScannedTwice { name } => write!(f, "{} is scanned twice", name),
+            ScanError::NoPoints { name } => write!(f, "the range of {} has no points", name),
+            ScanError::LogarithmicBounds { name, start, end } => write!(
+                f,
+                "the logarithmic range of {} needs non-zero bounds of the same sign, got {} and {}",
+                name, start, end
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ScanError {}
+
+/// The spacing of the values of a `Range`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Spacing {
+    /// Equal differences.
+    Linear,
+    /// Equal ratios, for bounds of the same sign.
+    Logarithmic,
+}
+
+/// The values an input takes in a scan.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Range {
+    /// The scanned input.
+    pub variable: Variable,
+    /// The first value.
+    pub start: f64,
+    /// The last value.
+    pub end: f64,
+    /// The number of values, including both bounds.
+    pub points: usize,
+    /// The spacing of the values.
+    pub spacing: Spacing,
+}
+
+impl Range {
+    /// `points` equally spaced values from `start` to `end`.
+    pub fn linear(variable: Variable, start: f64, end: f64, points: usize) -> Self {
+        Range { variable, start, end, points, spacing: Spacing::Linear }
+    }
+
+    /// `points` values from `start` to `end` with equal ratios.
+    pub fn logarithmic(variable: Variable, start: f64, end: f64, points: usize) -> Self {
+        Range { variable, start, end, points, spacing: Spacing::Logarithmic }
+    }
+
+    /// The values of the range, with both bounds exact.
+    ///
+    /// A logarithmic range with a vanishing bound or bounds of opposite sign
+    /// gives `NaN`, see `validate`.
+    pub fn values(&self) -> Vec<f64> {
+        let last = self.points.saturating_sub(1);
+        (0..self.points)
+            .map(|i| match i {
+                0 => self.start,
+                _ if i == last => self.end,
+                _ => {
+                    let t = i as f64 / last as f64;
+                    match self.spacing {
+                        Spacing::Linear => self.start + (self.end - self.start) * t,
+                        Spacing::Logarithmic => self.start * (self.end / self.start).powf(t),
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// A point of a scan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    /// The values of the scanned inputs, in the order of the ranges.
+    pub values: Vec<f64>,
+    /// All squared momenta.
+    pub momenta: Vec<f64>,
+    /// All squared masses.
+    pub masses: Vec<Complex64>,
+    /// The value of the function.
+    pub result: OLOResult,
+}
+
+/// The result of a scan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scan {
+    /// The number of legs of the scanned function.
+    pub legs: usize,
+    /// The scanned inputs, in the order of the ranges.
+    pub variables: Vec<Variable>,
+    /// The renormalization scale of the evaluation.
+    pub mu: f64,
+    /// The samples, with the last range varying fastest.
+    pub samples: Vec<Sample>,
+}
+
+/// Scans `one_point` over the mass.
+///
+/// # Panics
+/// See `scan`.
+pub fn one_point(m: impl Into<Complex64>, ranges: &[Range]) -> Scan {
+    scan(&base(1, vec![], vec![m.into()]), ranges)
+}
+
+/// Scans `two_point` over `ranges`.
+///
+/// # Panics
+/// See `scan`.
+pub fn two_point(p: f64, m1: impl Into<Complex64>, m2: impl Into<Complex64>, ranges: &[Range]) -> Scan {
+    scan(&base(2, vec![p], vec![m1.into(), m2.into()]), ranges)
+}
+
+/// Scans `three_point` over `ranges`.
+///
+/// # Panics
+/// See `scan`.
+pub fn three_point(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+    ranges: &[Range],
+) -> Scan {
+    scan(&base(3, vec![p1, p2, p3], vec![m1.into(), m2.into(), m3.into()]), ranges)
+}
+
+/// Scans `four_point` over `ranges`.
+///
+/// # Panics
+/// See `scan`.
+#[allow(clippy::too_many_arguments)]
+pub fn four_point(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    p4: f64,
+    p12: f64,
+    p23: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+    m4: impl Into<Complex64>,
+    ranges: &[Range],
+) -> Scan {
+    let masses = vec![m1.into(), m2.into(), m3.into(), m4.into()];
+    scan(&base(4, vec![p1, p2, p3, p4, p12, p23], masses), ranges)
+}
+
+fn base(legs: usize, momenta: Vec<f64>, masses: Vec<Complex64>) -> Point {
+    Point { legs, mu: crate::renormalization_scale(), momenta, masses, line: 0 }
+}
+
+/// Scans the function of `point` over `ranges`, starting from its inputs.
+/// The scale `point.mu` is not used.
+///
+/// # Panics
+/// If `validate` fails.
+pub fn scan(point: &Point, ranges: &[Range]) -> Scan {
+    if let Err(error) = validate(point, ranges) {
+        panic!("{}", error);
+    }
+    let values: Vec<Vec<f64>> = ranges.iter().map(Range::values).collect();
+    let total = values.iter().map(Vec::len).product();
+    let mut samples = Vec::with_capacity(total);
+    let mut point = point.clone();
+    for index in 0..total {
+        // Split the index into one per range, the last varying fastest
+        let mut rest = index;
+        let mut sample_values = vec![0.0; ranges.len()];
+        for (i, range) in ranges.iter().enumerate().rev() {
+            let value = values[i][rest % values[i].len()];
+            rest /= values[i].len();
+            sample_values[i] = value;
+            set(&mut point, range.variable, value);
+        }
+        samples.push(Sample {
+            values: sample_values,
+            momenta: point.momenta.clone(),
+            masses: point.masses.clone(),
+            result: point.evaluate(),
+        });
+    }
+    Scan {
+        legs: point.legs,
+        variables: ranges.iter().map(|r| r.variable).collect(),
+        mu: crate::renormalization_scale(),
+        samples,
+    }
+}
+
+/// Sets an input of `point`, keeping the imaginary part of a mass.
+pub(crate) fn set(point: &mut Point, variable: Variable, value: f64) {
+    match variable {
+        Variable::Momentum(j) => point.momenta[j] = value,
+        Variable::Mass(j) => point.masses[j].re = value,
+    }
+}
+
+/// Checks that every range varies a different input of the function of
+/// `point` over at least one value, with valid bounds if logarithmic.
+pub fn validate(point: &Point, ranges: &[Range]) -> Result<(), ScanError> {
+    for (i, range) in ranges.iter().enumerate() {
+        let known = match range.variable {
+            Variable::Momentum(j) => j < point.momenta.len(),
+            Variable::Mass(j) => j < point.masses.len(),
+        };
+        if !known {
+            return Err(ScanError::UnknownVariable { name: format!("{:?}", range.variable) });
+        }
+        let name = variable_name(point.legs, range.variable);
+        if ranges[..i].iter().any(|r| r.variable == range.variable) {
+            return Err(ScanError::ScannedTwice { name });
+        }
+        if range.points == 0 {
+            return Err(ScanError::NoPoints { name });
+        }
+        let same_sign = range.start * range.end > 0.0;
+        if range.spacing == Spacing::Logarithmic && !same_sign {
+            return Err(ScanError::LogarithmicBounds { name, start: range.start, end: range.end });
+        }
+    }
+    Ok(())
+}
+
+/// The name of an input of the function with `legs` legs, as in the
+/// arguments of `one_point` to `four_point`.
+///
+/// # Panics
+/// If `legs` is not between 1 and 4 or the function has no such input.
+pub fn variable_name(legs: usize, variable: Variable) -> String {
+    match variable {
+        Variable::Momentum(i) => MOMENTA[legs - 1][i].to_string(),
+        Variable::Mass(0) if legs == 1 => "m".to_string(),
+        Variable::Mass(i) => {
+            assert!(i < legs, "mass index {} out of range 0..{}", i, legs);
+            format!("m{}", i + 1)
+        }
+    }
+}
+
+/// The input of the function with `legs` legs called `name`, the inverse of
+/// `variable_name`.
+pub fn variable_by_name(legs: usize, name: &str) -> Option<Variable> {
+    let momenta = MOMENTA.get(legs.checked_sub(1)?)?;
+    if let Some(i) = momenta.iter().position(|&p| p == name) {
+        return Some(Variable::Momentum(i));
+    }
+    (0..legs).map(Variable::Mass).find(|&m| variable_name(legs, m) == name)
+}
+
+/// The Laurent coefficients of a result, from `ε⁰` to `ε⁻²`.
+fn coefficients(result: &OLOResult) -> [Complex64; 3] {
+    [result.epsilon_0(), result.epsilon_minus_1(), result.epsilon_minus_2()]
+}
+
+const COEFFICIENTS: [&str; 3] = ["epsilon_0", "epsilon_minus_1", "epsilon_minus_2"];
+
+impl Scan {
+    /// Writes the samples as CSV with a header line, one column per momentum,
+    /// two per mass and coefficient (`m1_re`, `m1_im`, ..., `epsilon_0_re`,
+    /// ...), and numbers in the shortest form that reads back exactly.
+    pub fn write_csv(&self, mut out: impl Write) -> io::Result<()> {
+        let mut header: Vec<String> = MOMENTA[self.legs - 1].iter().map(|p| p.to_string()).collect();
+        for i in 0..self.legs {
+            let m = variable_name(self.legs, Variable::Mass(i));
+            header.extend([format!("{}_re", m), format!("{}_im", m)]);
+        }
+        for c in COEFFICIENTS {
+            header.extend([format!("{}_re", c), format!("{}_im", c)]);
+        }
+        writeln!(out, "{}", header.join(","))?;
+        for sample in &self.samples {
+            let mut row: Vec<String> = sample.momenta.iter().map(|p| format!("{:e}", p)).collect();
+            for z in sample.masses.iter().chain(&coefficients(&sample.result)) {
+                row.extend([format!("{:e}", z.re), format!("{:e}", z.im)]);
+            }
+            writeln!(out, "{}", row.join(","))?;
+        }
+        Ok(())
+    }
+
+    /// Writes the samples as JSON, one object per line, e.g.
+    ///
+    /// ```text
+    /// {"function": "B0", "p": 1e0, "m1": [5e-1, 0e0], "m2": [2e-1, 0e0], "scale": 1e0,
+    ///  "epsilon_0": [...], "epsilon_minus_1": [1e0, 0e0], "epsilon_minus_2": [0e0, 0e0]}
+    /// ```
+    ///
+    /// with complex numbers as `[re, im]` and non-finite numbers as `null`.
+    pub fn write_json_lines(&self, mut out: impl Write) -> io::Result<()> {
+        let number = |x: f64| if x.is_finite() { format!("{:e}", x) } else { "null".to_string() };
+        let pair = |z: Complex64| format!("[{}, {}]", number(z.re), number(z.im));
+        for sample in &self.samples {
+            let mut fields = vec![format!("\"function\": \"{}\"", ["A0", "B0", "C0", "D0"][self.legs - 1])];
+            for (name, &p) in MOMENTA[self.legs - 1].iter().zip(&sample.momenta) {
+                fields.push(format!("\"{}\": {}", name, number(p)));
+            }
+            for (i, &m) in sample.masses.iter().enumerate() {
+                fields.push(format!("\"{}\": {}", variable_name(self.legs, Variable::Mass(i)), pair(m)));
+            }
+            fields.push(format!("\"scale\": {}", number(self.mu)));
+            for (name, c) in COEFFICIENTS.iter().zip(coefficients(&sample.result)) {
+                fields.push(format!("\"{}\": {}", name, pair(c)));
+            }
+            writeln!(out, "{{{}}}", fields.join(", "))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+//! Per-call on-shell snapping of the inputs of the scalar functions.
+//!
+//! OneLOop only recognizes IR-divergent configurations if the relevant masses
+//! and momenta vanish, or momenta equal internal masses, exactly (or within the
+//! global threshold of `set_onshell_threshold`). The functions here first move
+//! every input that lies within `tolerance` times the largest input onto its
+//! exact value, and return the snaps they applied together with the result.
+//!
+//! Inputs are numbered by their position in the arguments of the corresponding
+//! scalar function, starting from zero. Momenta are only snapped to the real
+//! masses of the two propagators the leg attaches to.
+use crate::OLOResult;
+use num_complex::Complex64;
+
+/// An input that was moved onto its on-shell value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Snap {
+    /// A mass squared that was set to zero.
+    Mass(usize),
+    /// A momentum squared that was set to zero.
+    Momentum(usize),
+    /// A momentum squared that was set to the given mass squared.
+    Leg { momentum: usize, mass: usize },
+}
+
+/// Snaps masses to zero and then momenta to zero or to the mass of one of the
+/// propagators in `legs[i]`, relative to the largest input.
+fn snap(momenta: &mut [f64], legs: &[&[usize]], masses: &mut [Complex64], tolerance: f64) -> Vec<Snap> {
+    let scale = momenta
+        .iter()
+        .map(|p| p.abs())
+        .chain(masses.iter().map(|m| m.norm()))
+        .fold(0.0, f64::max);
+    let threshold = tolerance * scale;
+    let mut snaps = Vec::new();
+
+    for (i, m) in masses.iter_mut().enumerate() {
+        if *m != Complex64::new(0.0, 0.0) && m.norm() < threshold {
+            *m = Complex64::new(0.0, 0.0);
+            snaps.push(Snap::Mass(i));
+        }
+    }
+    for (i, p) in momenta.iter_mut().enumerate() {
+        if *p != 0.0 && p.abs() < threshold {
+            *p = 0.0;
+            snaps.push(Snap::Momentum(i));
+            continue;
+        }
+        let closest = legs
+            .get(i)
+            .into_iter()
+            .flat_map(|adjacent| adjacent.iter())
+            .filter(|&&j| masses[j].im == 0.0)
+            .map(|&j| (j, (*p - masses[j].re).abs()))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((j, distance)) = closest
+            && distance != 0.0
+            && distance < threshold
+        {
+            *p = masses[j].re;
+            snaps.push(Snap::Leg { momentum: i, mass: j });
+        }
+    }
+    snaps
+}
+
+/// Computes `two_point` after snapping the inputs within the relative
+/// `tolerance` on shell.
+pub fn two_point(
+    p: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    tolerance: f64,
+) -> (OLOResult, Vec<Snap>) {
+    let mut p = [p];
+    let mut m = [m1.into(), m2.into()];
+    let snaps = snap(&mut p, &[&[0, 1]], &mut m, tolerance);
+    (crate::two_point(p[0], m[0], m[1]), snaps)
+}
+
+/// Computes `three_point` after snapping the inputs within the relative
+/// `tolerance` on shell.
+pub fn three_point(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+    tolerance: f64,
+) -> (OLOResult, Vec<Snap>) {
+    let mut p = [p1, p2, p3];
+    let mut m = [m1.into(), m2.into(), m3.into()];
+    let snaps = snap(&mut p, &[&[0, 1], &[1, 2], &[2, 0]], &mut m, tolerance);
+    (crate::three_point(p[0], p[1], p[2], m[0], m[1], m[2]), snaps)
+}
+
+/// Computes `four_point` after snapping the inputs within the relative
+/// `tolerance` on shell. The invariants `p12` and `p23` are only snapped to zero.
+#[allow(clippy::too_many_arguments)]
+pub fn four_point(
+    p1: f64,
+    p2: f64,
+    p3: f64,
+    p4: f64,
+    p12: f64,
+    p23: f64,
+    m1: impl Into<Complex64>,
+    m2: impl Into<Complex64>,
+    m3: impl Into<Complex64>,
+    m4: impl Into<Complex64>,
+    tolerance: f64,
+) -> (OLOResult, Vec<Snap>) {
+    let mut p = [p1, p2, p3, p4, p12, p23];
+    let mut m = [m1.into(), m2.into(), m3.into(), m4.into()];
+    let snaps = snap(&mut p, &[&[0, 1], &[1, 2], &[2, 3], &[3, 0]], &mut m, tolerance);
+    let r = crate::four_point(p[0], p[1], p[2], p[3], p[4], p[5], m[0], m[1], m[2], m[3]);
+    (r, snaps)
+}
@@ -0,0 +1,454 @@
+//! Interpolation tables of the scalar functions along one or two inputs, for
+//! repeated evaluation on a fixed kinematic line or surface, e.g. in a Monte
+//! Carlo integration.
+//!
+//! A `Table` samples a function along the `Range` of one input, starting
+//! from the values of the range and bisecting intervals until piecewise cubic
+//! interpolation agrees with the function within `tolerance` at the midpoint
+//! of every interval, where the interpolation error of a smooth function is
+//! largest. A `Grid` covers the product of two ranges with cells, split into
+//! four until bicubic interpolation within each cell passes the same check.
+//! The error is that of the largest Laurent coefficient relative to the
+//! largest coefficient of the function, and logarithmic ranges are
+//! interpolated in the logarithm of the input.
+//!
+//! The tolerance is only checked at these samples, so `error` is an
+//! estimate, not a bound: between the samples, in particular near a feature
+//! narrower than an interval or cell, the interpolation may be worse.
+//!
+//! Near a threshold or singularity the function is not smooth, and intervals
+//! are only bisected down to `MIN_WIDTH` of the range, cells split
+//! `MAX_DEPTH` times. Intervals and cells that still fail the check, or all
+//! remaining ones once `MAX_NODES` values are computed, are evaluated
+//! directly instead, as are inputs outside the ranges. Direct evaluations use
+//! the current renormalization scale, which should be the scale `mu` the
+//! table was built at.
+use crate::OLOResult;
+use crate::derivative::Variable;
+use crate::input::Point;
+use crate::scan::{self, Range, Spacing};
+use std::collections::HashMap;
+
+/// The smallest width an interval of a `Table` is bisected to, relative to
+/// the range.
+pub const MIN_WIDTH: f64 = 1e-10;
+
+/// How often a cell of a `Grid` may be split, so that the smallest cells
+/// cover `2^-MAX_DEPTH` of each range.
+pub const MAX_DEPTH: u32 = 10;
+
+/// The largest number of nodes of a `Table`, and of evaluations building a
+/// `Grid`.
+pub const MAX_NODES: usize = 1 << 20;
+
+/// An input of a table, with the coordinate it is interpolated in.
+#[derive(Debug, Clone, Copy)]
+struct Axis {
+    variable: Variable,
+    logarithmic: bool,
+    start: f64,
+}
+
+impl Axis {
+    fn new(range: &Range) -> Self {
+        assert!(range.start != range.end, "the range of a table must not be empty");
+        Axis { variable: range.variable, logarithmic: range.spacing == Spacing::Logarithmic, start: range.start }
+    }
+
+    fn coordinate(self, x: f64) -> f64 {
+        if self.logarithmic { (x / self.start).ln() } else { x }
+    }
+
+    fn input(self, u: f64) -> f64 {
+        if self.logarithmic { self.start * u.exp() } else { u }
+    }
+
+    /// The initial nodes, at least the bounds, in increasing `u`.
+    fn nodes(self, range: &Range) -> Vec<f64> {
+        let mut xs = range.values();
+        if xs.len() < 2 {
+            xs = vec![range.start, range.end];
+        }
+        if self.coordinate(xs[0]) > self.coordinate(xs[xs.len() - 1]) {
+            xs.reverse();
+        }
+        xs
+    }
+}
+
+/// A function of one input, interpolated along a range.
+#[derive(Debug, Clone)]
+pub struct Table {
+    point: Point,
+    axis: Axis,
+    xs: Vec<f64>,
+    us: Vec<f64>,
+    values: Vec<OLOResult>,
+    /// Whether each interval is evaluated directly.
+    direct: Vec<bool>,
+    error: f64,
+    mu: f64,
+}
+
+impl Table {
+    /// Tabulates the function of `point` along `range`, starting from the
+    /// other inputs of `point`. The scale `point.mu` is not used.
+    ///
+    /// # Panics
+    /// If `scan::validate` fails for `range` or its bounds are equal.
+    pub fn new(point: &Point, range: Range, tolerance: f64) -> Self {
+        if let Err(error) = scan::validate(point, &[range]) {
+            panic!("{}", error);
+        }
+        let axis = Axis::new(&range);
+        let mut point = point.clone();
+        let mut f = |x: f64| {
+            scan::set(&mut point, axis.variable, x);
+            point.evaluate()
+        };
+        let xs = axis.nodes(&range);
+        let mut values: Vec<OLOResult> = xs.iter().map(|&x| f(x)).collect();
+        let mut us: Vec<f64> = xs.iter().map(|&x| axis.coordinate(x)).collect();
+        let min_width = MIN_WIDTH * (us[us.len() - 1] - us[0]);
+        let mut midpoints: HashMap<u64, OLOResult> = HashMap::new();
+
+        // The relative error at the midpoint of every interval
+        let mut check = |us: &[f64], values: &[OLOResult]| -> Vec<(f64, OLOResult, f64)> {
+            (0..us.len() - 1)
+                .map(|i| {
+                    let u = 0.5 * (us[i] + us[i + 1]);
+                    let exact = *midpoints.entry(u.to_bits()).or_insert_with(|| f(axis.input(u)));
+                    (u, exact, relative_error(interpolate(us, values, u), exact))
+                })
+                .collect()
+        };
+        let mut errors = check(&us, &values);
+        loop {
+            let refine: Vec<usize> = (0..us.len() - 1)
+                .filter(|&i| fails(errors[i].2, tolerance) && us[i + 1] - us[i] > min_width)
+                .collect();
+            if refine.is_empty() || us.len() + refine.len() > MAX_NODES {
+                break;
+            }
+            // Insert the midpoints, which are already evaluated
+            let mut refined = refine.iter().peekable();
+            let (mut new_us, mut new_values) = (Vec::new(), Vec::new());
+            for i in 0..us.len() {
+                new_us.push(us[i]);
+                new_values.push(values[i]);
+                if refined.next_if_eq(&&i).is_some() {
+                    new_us.push(errors[i].0);
+                    new_values.push(errors[i].1);
+                }
+            }
+            (us, values) = (new_us, new_values);
+            errors = check(&us, &values);
+        }
+
+        let direct: Vec<bool> = errors.iter().map(|e| fails(e.2, tolerance)).collect();
+        let error = errors.iter().zip(&direct).filter(|(_, d)| !**d).map(|(e, _)| e.2).fold(0.0, f64::max);
+        let xs = exact_bounds(axis, &us, &range);
+        Table { point, axis, xs, us, values, direct, error, mu: crate::renormalization_scale() }
+    }
+
+    /// The value of the function at `x`, interpolated or evaluated directly.
+    pub fn evaluate(&self, x: f64) -> OLOResult {
+        let u = self.axis.coordinate(x);
+        match interval(&self.us, u) {
+            Some(i) if !self.direct[i] => interpolate(&self.us, &self.values, u),
+            _ => {
+                let mut point = self.point.clone();
+                scan::set(&mut point, self.axis.variable, x);
+                point.evaluate()
+            }
+        }
+    }
+
+    /// Whether `evaluate` interpolates at `x`.
+    pub fn is_interpolated(&self, x: f64) -> bool {
+        interval(&self.us, self.axis.coordinate(x)).is_some_and(|i| !self.direct[i])
+    }
+
+    /// The nodes, in increasing order of the interpolation coordinate.
+    pub fn nodes(&self) -> &[f64] {
+        &self.xs
+    }
+
+    /// The intervals `(a, b)` between nodes that are evaluated directly.
+    pub fn direct_intervals(&self) -> Vec<(f64, f64)> {
+        (0..self.direct.len()).filter(|&i| self.direct[i]).map(|i| (self.xs[i], self.xs[i + 1])).collect()
+    }
+
+    /// The largest relative interpolation error found at the midpoints of
+    /// the interpolated intervals, at most the tolerance. Elsewhere in the
+    /// intervals the error may be larger.
+    pub fn error(&self) -> f64 {
+        self.error
+    }
+
+    /// The renormalization scale of the tabulated values.
+    pub fn mu(&self) -> f64 {
+        self.mu
+    }
+}
+
+/// A function of two inputs, interpolated on the product of two ranges.
+#[derive(Debug, Clone)]
+pub struct Grid {
+    point: Point,
+    axes: [Axis; 2],
+    /// The bounds of the interpolation coordinates, lower first.
+    bounds: [(f64, f64); 2],
+    /// The root cell, followed by the children of split cells.
+    cells: Vec<Cell>,
+    evaluations: usize,
+    error: f64,
+    mu: f64,
+}
+
+/// A cell of a `Grid`.
+#[derive(Debug, Clone)]
+enum Cell {
+    /// Interpolated from the values at `4 × 4` equally spaced nodes, with the
+    /// value at the `a`-th node in `u` and `b`-th in `v` at `4 * a + b`.
+    Leaf(Box<[OLOResult; 16]>),
+    /// Evaluated directly.
+    Direct,
+    /// Split into four at the center, with the children from the given index
+    /// on, the one with upper `u` half `a` and `v` half `b` at `2 * a + b`.
+    Split(usize),
+}
+
+/// The lattice points per side of the root cell at which a `Grid` evaluates
+/// the function, multiples of `1 / LATTICE`, so that split cells reuse the
+/// values of their parent.
+const LATTICE: u64 = 6 << MAX_DEPTH;
+
+impl Grid {
+    /// Tabulates the function of `point` on the product of `x` and `y`,
+    /// starting from the other inputs of `point`. The scale `point.mu` is
+    /// not used.
+    ///
+    /// A cell is split into four until bicubic interpolation between its
+    /// `4 × 4` nodes agrees with the function within `tolerance` at the
+    /// centers of the `3 × 3` squares between them, at most `MAX_DEPTH`
+    /// times, and at least until the cells are as fine as the values of the
+    /// ranges. Cells are refined breadth first, and once `MAX_NODES` values
+    /// are computed the remaining failing cells are evaluated directly.
+    ///
+    /// # Panics
+    /// If `scan::validate` fails for the ranges or the bounds of one are
+    /// equal.
+    pub fn new(point: &Point, x: Range, y: Range, tolerance: f64) -> Self {
+        if let Err(error) = scan::validate(point, &[x, y]) {
+            panic!("{}", error);
+        }
+        let axes = [Axis::new(&x), Axis::new(&y)];
+        let bounds = [(axes[0], x), (axes[1], y)].map(|(axis, range)| {
+            let (a, b) = (axis.coordinate(range.start), axis.coordinate(range.end));
+            (a.min(b), a.max(b))
+        });
+        // The depth down to which cells are split regardless, to resolve the
+        // values of the ranges with three intervals per cell
+        let initial = [x, y].iter().map(|r| r.points.saturating_sub(1).div_ceil(3).next_power_of_two().ilog2()).max();
+        let initial = initial.unwrap_or(0).min(MAX_DEPTH);
+
+        let mut point = point.clone();
+        let mut cache: HashMap<(u64, u64), OLOResult> = HashMap::new();
+        // The function at lattice point `(i, j)`
+        let mut f = |cache: &mut HashMap<(u64, u64), OLOResult>, i: u64, j: u64| {
+            *cache.entry((i, j)).or_insert_with(|| {
+                let (u, v) = (lattice(bounds[0], i), lattice(bounds[1], j));
+                scan::set(&mut point, axes[0].variable, axes[0].input(u));
+                scan::set(&mut point, axes[1].variable, axes[1].input(v));
+                point.evaluate()
+            })
+        };
+
+        let mut cells = vec![Cell::Direct];
+        let mut error: f64 = 0.0;
+        // Cells to refine, with their depth and position among the cells of
+        // that depth
+        let mut queue = std::collections::VecDeque::from([(0, 0, 0, 0)]);
+        let mut evaluations = 0;
+        while let Some((index, depth, i, j)) = queue.pop_front() {
+            // The spacing of the nodes in lattice points
+            let step = 2 << (MAX_DEPTH - depth);
+            let (i0, j0) = (3 * step * i, 3 * step * j);
+            let mut values = Box::new([OLOResult::default(); 16]);
+            for (k, value) in values.iter_mut().enumerate() {
+                *value = f(&mut cache, i0 + step * (k as u64 / 4), j0 + step * (k as u64 % 4));
+            }
+            let mut cell_error: f64 = 0.0;
+            for k in 0..9 {
+                let (a, b) = (k / 3, k % 3);
+                let exact = f(&mut cache, i0 + step * a + step / 2, j0 + step * b + step / 2);
+                let value = bicubic(&values, a as f64 + 0.5, b as f64 + 0.5);
+                cell_error = cell_error.max(relative_error(value, exact));
+            }
+            evaluations = cache.len();
+            let smooth = !fails(cell_error, tolerance);
+            if smooth && depth >= initial {
+                error = error.max(cell_error);
+                cells[index] = Cell::Leaf(values);
+            } else if depth < MAX_DEPTH && evaluations < MAX_NODES {
+                cells[index] = Cell::Split(cells.len());
+                for child in 0..4 {
+                    queue.push_back((cells.len(), depth + 1, 2 * i + child / 2, 2 * j + child % 2));
+                    cells.push(Cell::Direct);
+                }
+            } else if smooth {
+                error = error.max(cell_error);
+                cells[index] = Cell::Leaf(values);
+            }
+        }
+        Grid { point, axes, bounds, cells, evaluations, error, mu: crate::renormalization_scale() }
+    }
+
+    /// The value of the function at `(x, y)`, interpolated or evaluated
+    /// directly.
+    pub fn evaluate(&self, x: f64, y: f64) -> OLOResult {
+        if let Some((Cell::Leaf(values), s, t)) = self.locate(x, y) {
+            return bicubic(values, s, t);
+        }
+        let mut point = self.point.clone();
+        scan::set(&mut point, self.axes[0].variable, x);
+        scan::set(&mut point, self.axes[1].variable, y);
+        point.evaluate()
+    }
+
+    /// Whether `evaluate` interpolates at `(x, y)`.
+    pub fn is_interpolated(&self, x: f64, y: f64) -> bool {
+        matches!(self.locate(x, y), Some((Cell::Leaf(_), _, _)))
+    }
+
+    /// The leaf cell containing `(x, y)`, with the position in it in units of
+    /// the spacing of its nodes.
+    fn locate(&self, x: f64, y: f64) -> Option<(&Cell, f64, f64)> {
+        let (u, v) = (self.axes[0].coordinate(x), self.axes[1].coordinate(y));
+        let [(u0, u1), (v0, v1)] = self.bounds;
+        if !(u0..=u1).contains(&u) || !(v0..=v1).contains(&v) {
+            return None;
+        }
+        // The position in the current cell, in [0, 1]
+        let (mut s, mut t) = ((u - u0) / (u1 - u0), (v - v0) / (v1 - v0));
+        let mut cell = &self.cells[0];
+        while let Cell::Split(children) = cell {
+            let (a, b) = ((s >= 0.5) as usize, (t >= 0.5) as usize);
+            (s, t) = (2.0 * s - a as f64, 2.0 * t - b as f64);
+            cell = &self.cells[children + 2 * a + b];
+        }
+        Some((cell, 3.0 * s, 3.0 * t))
+    }
+
+    /// The number of cells that are interpolated and evaluated directly.
+    pub fn cells(&self) -> (usize, usize) {
+        let leaves = self.cells.iter().filter(|c| matches!(c, Cell::Leaf(_))).count();
+        let direct = self.cells.iter().filter(|c| matches!(c, Cell::Direct)).count();
+        (leaves, direct)
+    }
+
+    /// The number of evaluations of the function while building the grid.
+    pub fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    /// The largest relative interpolation error found at the centres between
+    /// the nodes of the interpolated cells, at most the tolerance. Elsewhere
+    /// in the cells the error may be larger.
+    pub fn error(&self) -> f64 {
+        self.error
+    }
+
+    /// The renormalization scale of the tabulated values.
+    pub fn mu(&self) -> f64 {
+        self.mu
+    }
+}
+
+/// The coordinate of lattice point `i` between `bounds`.
+fn lattice(bounds: (f64, f64), i: u64) -> f64 {
+    match i {
+        0 => bounds.0,
+        LATTICE => bounds.1,
+        _ => bounds.0 + (bounds.1 - bounds.0) * (i as f64 / LATTICE as f64),
+    }
+}
+
+/// Interpolates between the `4 × 4` values of a leaf at `(s, t)`, in units
+/// of the spacing of the nodes.
+fn bicubic(values: &[OLOResult; 16], s: f64, t: f64) -> OLOResult {
+    let (ws, wt) = (equispaced(s), equispaced(t));
+    let mut sum = OLOResult::default();
+    for (k, &value) in values.iter().enumerate() {
+        sum += value * (ws[k / 4] * wt[k % 4]);
+    }
+    sum
+}
+
+/// The Lagrange weights of the nodes 0, 1, 2 and 3 at `t`.
+fn equispaced(t: f64) -> [f64; 4] {
+    let (a, b, c, d) = (t, t - 1.0, t - 2.0, t - 3.0);
+    [-b * c * d / 6.0, a * c * d / 2.0, -a * b * d / 2.0, a * b * c / 6.0]
+}
+
+/// The inputs at the nodes `us`, with the bounds of `range` exact, as
+/// `input` need not round-trip.
+fn exact_bounds(axis: Axis, us: &[f64], range: &Range) -> Vec<f64> {
+    let mut xs: Vec<f64> = us.iter().map(|&u| axis.input(u)).collect();
+    let (first, last) = if axis.coordinate(range.start) <= axis.coordinate(range.end) {
+        (range.start, range.end)
+    } else {
+        (range.end, range.start)
+    };
+    let n = xs.len();
+    (xs[0], xs[n - 1]) = (first, last);
+    xs
+}
+
+/// The interval of `us` containing `u`, if any.
+fn interval(us: &[f64], u: f64) -> Option<usize> {
+    let n = us.len();
+    if !(us[0]..=us[n - 1]).contains(&u) {
+        return None;
+    }
+    Some(us.partition_point(|&node| node <= u).clamp(1, n - 1) - 1)
+}
+
+/// The first node and the Lagrange weights of the up to four nodes around
+/// the interval containing `u`.
+fn weights(us: &[f64], u: f64) -> (usize, usize, [f64; 4]) {
+    let n = us.len();
+    let k = n.min(4);
+    let i = us.partition_point(|&node| node <= u).clamp(1, n - 1) - 1;
+    let first = i.saturating_sub(1).min(n - k);
+    let mut w = [0.0; 4];
+    for a in 0..k {
+        w[a] = (0..k)
+            .filter(|&b| b != a)
+            .map(|b| (u - us[first + b]) / (us[first + a] - us[first + b]))
+            .product();
+    }
+    (first, k, w)
+}
+
+fn interpolate(us: &[f64], values: &[OLOResult], u: f64) -> OLOResult {
+    let (first, k, w) = weights(us, u);
+    (0..k).fold(OLOResult::default(), |sum, a| sum + values[first + a] * w[a])
+}
+
+fn fails(error: f64, tolerance: f64) -> bool {
+    error.is_nan() || error > tolerance
+}
+
+/// The largest coefficient of `a - b` relative to the largest of `b`, zero
+/// if they are equal and `NaN` if either is not finite.
+fn relative_error(a: OLOResult, b: OLOResult) -> f64 {
+    let largest = |r: OLOResult| {
+        [r.epsilon_0(), r.epsilon_minus_1(), r.epsilon_minus_2()].iter().map(|c| c.norm()).fold(0.0, |m: f64, c| {
+            if c.is_nan() { f64::NAN } else { m.max(c) }
+        })
+    };
+    let (difference, scale) = (largest(a - b), largest(b));
+    if difference == 0.0 { 0.0 } else { difference / scale }
+}
@@ -0,0 +1,58 @@
+use num_complex::Complex64;
+use oneloop_bridge::{Disagreement, OLOResult};
+
+fn result(values: [(f64, f64); 3]) -> OLOResult {
+    let [a, b, c] = values.map(|(re, im)| Complex64::new(re, im));
+    OLOResult::new(a, b, c)
+}
+
+#[test]
+fn test_agrees_with() {
+    let r = result([(1.0, -2.0), (0.5, 0.0), (0.0, 0.0)]);
+    assert_eq!(r.agrees_with(&r, 0.0), Ok(()));
+    let close = result([(1.0 + 1e-12, -2.0), (0.5, 1e-13), (0.0, 0.0)]);
+    assert_eq!(r.agrees_with(&close, 1e-10), Ok(()));
+    assert_eq!(close.agrees_with(&r, 1e-10), Ok(()));
+
+    // The finite part differs
+    let far = result([(1.1, -2.0), (0.5, 0.0), (0.0, 0.0)]);
+    let disagreement = r.agrees_with(&far, 1e-3).unwrap_err();
+    println!("{}", disagreement);
+    assert_eq!(disagreement.order, 0);
+    assert_eq!((disagreement.left, disagreement.right), (Complex64::new(1.0, -2.0), Complex64::new(1.1, -2.0)));
+    assert!((disagreement.relative_difference - 0.1 / far.epsilon_0().norm()).abs() < 1e-15);
+    assert!(r.agrees_with(&far, 0.1).is_ok());
+
+    // The highest pole order that differs is reported, and zero only agrees with zero
+    let poles = result([(2.0, 0.0), (0.5, 0.0), (1e-300, 0.0)]);
+    assert_eq!(r.agrees_with(&poles, 0.5).unwrap_err().order, -2);
+    let poles = result([(2.0, 0.0), (0.6, 0.0), (0.0, 0.0)]);
+    let disagreement = r.agrees_with(&poles, 1e-3).unwrap_err();
+    assert_eq!(disagreement.order, -1);
+    assert_eq!(
+        disagreement.to_string(),
+        "ε⁻¹ coefficients differ: 0.5+0i vs 0.6+0i (relative difference 1.6666666666666663e-1)"
+    );
+
+    // NaN never agrees
+    let nan = result([(f64::NAN, 0.0), (0.5, 0.0), (0.0, 0.0)]);
+    assert!(matches!(nan.agrees_with(&nan, 1.0), Err(Disagreement { order: 0, .. })));
+}
+
+#[cfg(feature = "approx")]
+#[test]
+fn test_approx() {
+    use approx::{assert_abs_diff_eq, assert_relative_eq, assert_relative_ne, assert_ulps_eq};
+
+    let r = result([(1.0, -2.0), (0.5, 0.0), (0.0, 0.0)]);
+    let close = result([(1.0 + 1e-12, -2.0), (0.5, 1e-13), (0.0, 0.0)]);
+    assert_abs_diff_eq!(r, close, epsilon = 1e-11);
+    assert_relative_eq!(r, close, max_relative = 1e-11, epsilon = 1e-12);
+    assert_relative_ne!(r, close);
+    assert_ulps_eq!(r, result([(1.0 + f64::EPSILON, -2.0), (0.5, 0.0), (0.0, 0.0)]));
+
+    // Every part of every coefficient is compared
+    let pole = result([(1.0, -2.0), (0.5, 0.0), (0.0, 1e-3)]);
+    assert!(approx::abs_diff_ne!(r, pole, epsilon = 1e-4));
+    assert!(approx::abs_diff_eq!(r, pole, epsilon = 1e-2));
+}
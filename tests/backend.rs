@@ -0,0 +1,235 @@
+use num_complex::Complex64;
+use oneloop_bridge::backend::{self, Builtin, ScalarIntegralBackend};
+use oneloop_bridge::complex_momentum::TwoPointCoefficients;
+use oneloop_bridge::{OLOResult, five_point, four_point, one_point, pv, three_point, two_point};
+use std::sync::Mutex;
+#[cfg(oneloop_linked)]
+use {oneloop_bridge::backend::OneLoop, std::collections::HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The backend is global, so the tests must not run concurrently.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Returns the same canned result for every integral and counts the boxes.
+struct Mock {
+    canned: OLOResult,
+}
+
+static BOXES: AtomicUsize = AtomicUsize::new(0);
+
+impl ScalarIntegralBackend for Mock {
+    fn one_point(&self, _: Complex64) -> OLOResult {
+        self.canned
+    }
+
+    fn two_point(&self, _: f64, _: Complex64, _: Complex64) -> OLOResult {
+        self.canned
+    }
+
+    fn three_point(&self, _: f64, _: f64, _: f64, _: Complex64, _: Complex64, _: Complex64) -> OLOResult {
+        self.canned
+    }
+
+    fn four_point(
+        &self,
+        _: f64,
+        _: f64,
+        _: f64,
+        _: f64,
+        _: f64,
+        _: f64,
+        _: Complex64,
+        _: Complex64,
+        _: Complex64,
+        _: Complex64,
+    ) -> OLOResult {
+        BOXES.fetch_add(1, Ordering::Relaxed);
+        self.canned
+    }
+}
+
+/// The number of triangles and of sets of 2-point tensor coefficients that
+/// reached `Builtin` through `Counting`.
+static TRIANGLES: AtomicUsize = AtomicUsize::new(0);
+static BUBBLES: AtomicUsize = AtomicUsize::new(0);
+
+/// Passes everything on to `Builtin` and counts the triangles and the 2-point
+/// tensor coefficients.
+struct Counting;
+
+impl ScalarIntegralBackend for Counting {
+    fn one_point(&self, m: Complex64) -> OLOResult {
+        Builtin.one_point(m)
+    }
+
+    fn two_point(&self, p: f64, m1: Complex64, m2: Complex64) -> OLOResult {
+        Builtin.two_point(p, m1, m2)
+    }
+
+    fn two_point_coefficients(&self, p: f64, m1: Complex64, m2: Complex64) -> TwoPointCoefficients {
+        BUBBLES.fetch_add(1, Ordering::Relaxed);
+        Builtin.two_point_coefficients(p, m1, m2)
+    }
+
+    fn three_point(&self, p1: f64, p2: f64, p3: f64, m1: Complex64, m2: Complex64, m3: Complex64) -> OLOResult {
+        TRIANGLES.fetch_add(1, Ordering::Relaxed);
+        Builtin.three_point(p1, p2, p3, m1, m2, m3)
+    }
+
+    fn four_point(
+        &self,
+        p1: f64,
+        p2: f64,
+        p3: f64,
+        p4: f64,
+        p12: f64,
+        p23: f64,
+        m1: Complex64,
+        m2: Complex64,
+        m3: Complex64,
+        m4: Complex64,
+    ) -> OLOResult {
+        Builtin.four_point(p1, p2, p3, p4, p12, p23, m1, m2, m3, m4)
+    }
+}
+
+/// The number of calls that reached `OneLoop` through `Caching`.
+#[cfg(oneloop_linked)]
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// Caches the bubbles of `OneLoop` and counts the calls that reach it.
+#[cfg(oneloop_linked)]
+#[derive(Default)]
+struct Caching {
+    bubbles: Mutex<HashMap<[u64; 5], OLOResult>>,
+}
+
+#[cfg(oneloop_linked)]
+impl ScalarIntegralBackend for Caching {
+    fn one_point(&self, m: Complex64) -> OLOResult {
+        CALLS.fetch_add(1, Ordering::Relaxed);
+        OneLoop.one_point(m)
+    }
+
+    fn two_point(&self, p: f64, m1: Complex64, m2: Complex64) -> OLOResult {
+        let key = [p, m1.re, m1.im, m2.re, m2.im].map(f64::to_bits);
+        *self.bubbles.lock().unwrap().entry(key).or_insert_with(|| {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+            OneLoop.two_point(p, m1, m2)
+        })
+    }
+
+    fn three_point(&self, p1: f64, p2: f64, p3: f64, m1: Complex64, m2: Complex64, m3: Complex64) -> OLOResult {
+        CALLS.fetch_add(1, Ordering::Relaxed);
+        OneLoop.three_point(p1, p2, p3, m1, m2, m3)
+    }
+
+    fn four_point(
+        &self,
+        p1: f64,
+        p2: f64,
+        p3: f64,
+        p4: f64,
+        p12: f64,
+        p23: f64,
+        m1: Complex64,
+        m2: Complex64,
+        m3: Complex64,
+        m4: Complex64,
+    ) -> OLOResult {
+        CALLS.fetch_add(1, Ordering::Relaxed);
+        OneLoop.four_point(p1, p2, p3, p4, p12, p23, m1, m2, m3, m4)
+    }
+
+    fn set_renormalization_scale(&self, mu: f64) {
+        self.bubbles.lock().unwrap().clear();
+        OneLoop.set_renormalization_scale(mu)
+    }
+}
+
+#[test]
+fn test_backend_mock() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let m = Complex64::new(1.0, 0.0);
+    let canned = two_point(2.0, Complex64::new(0.0, 0.0), m);
+    backend::set_backend(Mock { canned });
+
+    assert_eq!(one_point(m).epsilon_0(), canned.epsilon_0());
+    assert_eq!(two_point(1.0, m, m).epsilon_minus_1(), canned.epsilon_minus_1());
+    assert_eq!(three_point(1.0, 2.0, 3.0, m, m, m).epsilon_0(), canned.epsilon_0());
+    assert_eq!(four_point(1.0, 2.0, 3.0, 4.0, 5.0, 6.0, m, m, m, m).epsilon_0(), canned.epsilon_0());
+
+    // The pentagon is reduced to the five boxes of the mock
+    BOXES.store(0, Ordering::Relaxed);
+    let r = five_point([1.0, 2.0, 3.0, 4.0, 5.0, -1.0, -2.0, -3.0, -4.0, -5.0], [m; 5]).unwrap();
+    println!("{}", r);
+    assert_eq!(BOXES.load(Ordering::Relaxed), 5);
+
+    backend::reset_backend();
+    assert_ne!(one_point(m).epsilon_0(), canned.epsilon_0());
+}
+
+#[test]
+fn test_backend_pv() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let m = Complex64::new(0.3, 0.0);
+    let expected = pv::three_point(0.2, 0.5, 1.7, m, m, m, 2).unwrap();
+
+    // The scalar triangle and the bubbles of the pinched integrals all come
+    // from the installed backend
+    backend::set_backend(Counting);
+    TRIANGLES.store(0, Ordering::Relaxed);
+    BUBBLES.store(0, Ordering::Relaxed);
+    let c = pv::three_point(0.2, 0.5, 1.7, m, m, m, 2).unwrap();
+    backend::reset_backend();
+    assert_eq!(TRIANGLES.load(Ordering::Relaxed), 1);
+    assert_eq!(BUBBLES.load(Ordering::Relaxed), 3);
+    for (indices, value) in c.iter() {
+        assert_eq!(Some(*value), expected.get(indices), "{:?}", indices);
+    }
+}
+
+#[test]
+fn test_backend_pv_scalar_only() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let m = Complex64::new(0.3, 0.0);
+    let canned = two_point(2.0, Complex64::new(0.0, 0.0), m);
+
+    // The mock only provides the scalar integrals, the 2-point tensor
+    // coefficients of the pinched integrals fall back to the default
+    backend::set_backend(Mock { canned });
+    let c = pv::three_point(0.2, 0.5, 1.7, m, m, m, 2).unwrap();
+    backend::reset_backend();
+    assert_eq!(c.get(&[]), Some(canned));
+    for (indices, value) in c.iter() {
+        assert!(value.epsilon_0().is_finite(), "{:?}", indices);
+    }
+}
+
+#[cfg(oneloop_linked)]
+#[test]
+fn test_backend_caching() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let m = Complex64::new(0.5, 0.0);
+    let expected = two_point(3.0, m, m);
+
+    backend::set_backend(Caching::default());
+    CALLS.store(0, Ordering::Relaxed);
+    for _ in 0..3 {
+        let r = two_point(3.0, m, m);
+        println!("{}", r);
+        assert_eq!(r.epsilon_0(), expected.epsilon_0());
+    }
+    assert_eq!(CALLS.load(Ordering::Relaxed), 1);
+
+    // Changing the scale reaches the decorator and empties the cache
+    oneloop_bridge::set_renormalization_scale(2.0);
+    let r = two_point(3.0, m, m);
+    assert_eq!(CALLS.load(Ordering::Relaxed), 2);
+    assert_ne!(r.epsilon_0(), expected.epsilon_0());
+    oneloop_bridge::set_renormalization_scale(1.0);
+    assert_eq!(two_point(3.0, m, m).epsilon_0(), expected.epsilon_0());
+    assert_eq!(CALLS.load(Ordering::Relaxed), 3);
+
+    backend::reset_backend();
+}
@@ -1,7 +1,9 @@
 use num_complex::Complex64;
 use oneloop_bridge::{
-    one_point, two_point, three_point, four_point,
+    one_point, two_point, three_point,
 };
+#[cfg(oneloop_linked)]
+use oneloop_bridge::four_point;
 
 #[test]
 fn test_olo_1_point_complex() {
@@ -31,6 +33,7 @@ fn test_olo_3_point_complex() {
     let r = three_point(s1, s2, s3, m1_sq, m2_sq, m3_sq);
     print!("{}", r)
 }
+#[cfg(oneloop_linked)]
 #[test]
 fn test_olo_4_point_complex() {
     let r = four_point(
@@ -0,0 +1,142 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn olo(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_olo"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    println!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+    output
+}
+
+/// The numbers in a line of JSON output after `key`.
+fn numbers(json: &str, key: &str) -> Vec<f64> {
+    let start = json.find(&format!("\"{}\": ", key)).unwrap() + key.len() + 4;
+    let end = start + json[start..].find(']').unwrap();
+    json[start..end].split(',').map(|x| x.trim_matches(|c: char| c == '[' || c == ' ').parse().unwrap()).collect()
+}
+
+#[test]
+fn test_cli_arguments() {
+    // B0(-1.7, 0, 1.5) at μ = 0.1726, the first point of oneloop/example/input
+    let output = olo(&["B0", "-1.7", "0", "1.5", "--scale", "0.1726", "--json"], "");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 1);
+    assert!(stdout.contains("\"function\": \"B0\""));
+    let finite = numbers(&stdout, "epsilon_0");
+    assert!((finite[0] + 3.3452540178514623).abs() < 1e-12 && finite[1] == 0.0);
+    assert_eq!(numbers(&stdout, "epsilon_minus_1"), vec![1.0, 0.0]);
+
+    // A sign applies after the power, as in expressions
+    let squared = olo(&["B0", "-1.5^2", "0", "1.5", "--json"], "");
+    let expected = olo(&["B0", "-2.25", "0", "1.5", "--json"], "");
+    assert!(squared.status.success());
+    assert_eq!(squared.stdout, expected.stdout);
+
+    // Text output, complex masses, powers and the Feynman normalization
+    let output = olo(&["A0", "80.4^2-170i", "--normalization", "feynman", "--precision", "6"], "");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("A0(6464.160000000001-170i)"));
+    assert!(stdout.contains("ε⁻¹:    -4.09348e1 + 1.07654e0i"));
+    assert!(stdout.contains("ε⁻²:     0.00000e0 + 0.00000e0i"));
+}
+
+#[test]
+fn test_cli_expression() {
+    // Twice B0(-1.7, 0, 1.5) at μ = 0.1726, as in test_cli_arguments
+    let args = ["--expression", "2*B0[p, 0, m]", "--set", "p=-1.7", "--set", "m=1.5", "--scale", "0.1726"];
+    let output = olo(&[&args[..], &["--json"]].concat(), "");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("{\"expression\": \"2*B0[p, 0, m]\", \"scale\": 1.726e-1"));
+    let finite = numbers(&stdout, "epsilon_0");
+    assert!((finite[0] + 2.0 * 3.3452540178514623).abs() < 1e-12 && finite[1] == 0.0);
+    assert_eq!(numbers(&stdout, "epsilon_minus_1"), vec![2.0, 0.0]);
+
+    let output = olo(&[&args[..], &["--precision", "6"]].concat(), "");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().collect::<Vec<_>>()[..2], ["2*B0[p, 0, m]", "  ε⁰ :    -6.69051e0 + 0.00000e0i"]);
+
+    for args in [
+        &["--expression", "B0[p, 0, m]", "--set", "p=1"][..],
+        &["--expression", "A0[1]*A0[1]"],
+        &["--expression", "A0[1]", "B0", "1", "1", "1"],
+        &["--expression", "A0[1]", "--csv"],
+        &["--expression", "A0[m]", "--set", "m"],
+        &["A0", "1", "--set", "m=1"],
+    ] {
+        let output = olo(args, "");
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+    }
+}
+
+#[test]
+fn test_cli_scan() {
+    // B0 across the threshold at p = 4, as CSV with a header
+    let output = olo(&["B0", "0", "1", "1", "--scan", "p=3:5:21", "--csv"], "");
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 22);
+    assert!(lines[0].starts_with("p,m1_re,m1_im,m2_re,m2_im,epsilon_0_re"));
+    assert!(lines[1].starts_with("3e0,1e0,0e0,1e0,0e0,"));
+    assert!(lines[21].starts_with("5e0,"));
+
+    // A logarithmic grid in two masses, as JSON
+    let output = olo(&["C0", "1", "2", "3", "1", "1", "1", "--scan", "m1=0.1:10:3:log", "--scan", "m3=1:2:2", "--json"], "");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 6);
+    assert!(stdout.lines().nth(3).unwrap().contains(r#""m1": [1e0, 0e0], "m2": [1e0, 0e0], "m3": [2e0, 0e0]"#));
+
+    // Text output, one block per sample
+    let output = olo(&["A0", "1", "--scan", "m=1:2:2"], "");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().filter(|l| l.starts_with("A0(")).count(), 2);
+}
+
+#[cfg(oneloop_linked)]
+#[test]
+fn test_cli_stdin() {
+    let input = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/oneloop/example/irdiv_input")).unwrap();
+    let output = olo(&["--json"], &input);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 26);
+    // Each point at its own scale
+    assert!(stdout.lines().all(|l| l.contains("\"scale\": 1.1e0")));
+    assert!(stdout.lines().last().unwrap().contains("\"function\": \"D0\""));
+}
+
+#[test]
+fn test_cli_errors() {
+    for args in [
+        &["B0", "1", "2"][..],
+        &["X0", "1"],
+        &["A0", "1", "--precision", "30"],
+        &["A0", "1", "--normalization", "cms"],
+        &["A0", "1+"],
+        &["A0", "1", "--verbose"],
+        &["A0", "1", "--scale"],
+        &["A0", "1", "--json", "--csv"],
+        &["A0", "1", "--scan", "p=1:2:2"],
+        &["A0", "1", "--scan", "m=1:2"],
+        &["A0", "1", "--scan", "m=1:2:0"],
+        &["A0", "1", "--scan", "m=-1:2:3:log"],
+        &["B0", "1", "1", "1", "--scan", "p=1:2:3", "--scan", "p=1:2:3"],
+        &["--scan", "p=1:2:3"],
+        &["--csv"],
+    ] {
+        let output = olo(args, "");
+        assert_eq!(output.status.code(), Some(2));
+        assert!(String::from_utf8(output.stderr).unwrap().starts_with("olo: "));
+    }
+    assert_eq!(olo(&[], "4 1.0\n(1d0,1d0)\n").status.code(), Some(2));
+    assert!(olo(&["--help"], "").status.success());
+}
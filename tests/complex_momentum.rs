@@ -0,0 +1,116 @@
+use num_complex::{Complex64, ComplexFloat};
+use oneloop_bridge::complex_momentum::{self, Sheet, TwoPointCoefficients};
+use oneloop_bridge::two_point;
+use std::f64::consts::PI;
+
+fn assert_close(a: Complex64, b: Complex64, tolerance: f64) {
+    println!("{}\n{}", a, b);
+    assert!((a - b).abs() <= tolerance * b.abs().max(1.0));
+}
+
+/// `∫₀¹ dx f(x)` by Simpson's rule.
+fn simpson(f: impl Fn(f64) -> Complex64) -> Complex64 {
+    let steps = 20000;
+    let h = 1.0 / steps as f64;
+    let mut sum = f(0.0) + f(1.0);
+    for i in 1..steps {
+        sum += f(i as f64 * h) * if i % 2 == 1 { 4.0 } else { 2.0 };
+    }
+    sum * h / 3.0
+}
+
+/// The finite parts of `B0`, `B1`, `B00` and `B11` from the Feynman-parameter
+/// integrals with the principal logarithm, valid where `Δ(x)` stays off the
+/// negative real axis.
+fn quadrature(p: Complex64, m1: Complex64, m2: Complex64) -> [Complex64; 4] {
+    let delta = |x: f64| x * m2 + (1.0 - x) * m1 - x * (1.0 - x) * p;
+    let b0 = -simpson(|x| delta(x).ln());
+    let b1 = simpson(|x| x * delta(x).ln());
+    let b00 = (simpson(delta) - simpson(|x| delta(x) * delta(x).ln())) / 2.0;
+    let b11 = -simpson(|x| x * x * delta(x).ln());
+    [b0, b1, b00, b11]
+}
+
+fn finite_parts(r: TwoPointCoefficients) -> [Complex64; 4] {
+    [r.b0.epsilon_0(), r.b1.epsilon_0(), r.b00.epsilon_0(), r.b11.epsilon_0()]
+}
+
+#[test]
+fn test_complex_momentum_first_sheet() {
+    let c = Complex64::new;
+    let zero = c(0.0, 0.0);
+
+    // Real momenta agree with the real-momentum bubble
+    let cases = [
+        (1.0, c(0.5, 0.0), c(0.2, 0.0)),
+        (10.0, c(0.5, 0.0), c(0.2, 0.0)),
+        (4.0, c(1.0, 0.0), c(1.0, 0.0)),
+        (-3.0, c(0.2, 0.0), c(0.5, 0.0)),
+        (0.0, c(0.5, 0.0), c(0.2, 0.0)),
+        (0.5, zero, c(0.5, 0.0)),
+        (2.0, c(0.5, 0.0), zero),
+        (-2.0, zero, zero),
+        (3.0, c(1.0, -0.1), c(0.3, -0.05)),
+    ];
+    for (p, m1, m2) in cases {
+        let r = complex_momentum::two_point(c(p, 0.0), m1, m2, Sheet::First);
+        let expected = two_point(p, m1, m2);
+        assert_close(r.epsilon_0(), expected.epsilon_0(), 1e-13);
+        assert_close(r.epsilon_minus_1(), expected.epsilon_minus_1(), 1e-15);
+    }
+
+    // Complex momenta on either side of the real axis for real masses, and a
+    // complex pole with a complex mass running in the loop
+    let m = c(80.4f64.powi(2), -80.4 * 2.1);
+    let cases = [
+        (c(10.0, 2.0), c(0.5, 0.0), c(0.2, 0.0)),
+        (c(10.0, -2.0), c(0.5, 0.0), c(0.2, 0.0)),
+        (c(-3.0, -1.0), c(1.0, 0.0), c(2.0, 0.0)),
+        (c(0.3, -0.1), c(1.0, 0.0), c(1.0, 0.0)),
+        (c(5.0, 1.0), c(1.0, -0.3), c(0.5, -0.1)),
+        (m, c(1e-3, 0.0), m),
+    ];
+    for (p, m1, m2) in cases {
+        let r = complex_momentum::two_point_coefficients(p, m1, m2, Sheet::First);
+        for (a, b) in finite_parts(r).into_iter().zip(quadrature(p, m1, m2)) {
+            assert_close(a, b, 1e-10);
+        }
+        assert_close(r.b1.epsilon_minus_1(), c(-0.5, 0.0), 1e-15);
+        assert_close(r.b00.epsilon_minus_1(), (m1 + m2) / 4.0 - p / 12.0, 1e-15);
+        assert_close(r.b11.epsilon_minus_1(), c(1.0 / 3.0, 0.0), 1e-15);
+    }
+}
+
+#[test]
+fn test_complex_momentum_second_sheet() {
+    let c = Complex64::new;
+    for (s, m1, m2) in [(10.0, c(0.5, 0.0), c(0.2, 0.0)), (5.0, c(1.0, 0.0), c(1.0, 0.0)), (3.0, c(0.0, 0.0), c(1.0, 0.0))] {
+        // Crossing the cut above threshold continues onto the second sheet
+        let eta = 1e-8;
+        let above = complex_momentum::two_point_coefficients(c(s, eta), m1, m2, Sheet::First);
+        let below = complex_momentum::two_point_coefficients(c(s, -eta), m1, m2, Sheet::Second);
+        for (a, b) in finite_parts(below).into_iter().zip(finite_parts(above)) {
+            assert_close(a, b, 1e-6);
+        }
+
+        // The sheets differ by 2πi √λ/p in B0, and agree in the poles
+        let p = c(s, -0.5);
+        let first = complex_momentum::two_point_coefficients(p, m1, m2, Sheet::First);
+        let second = complex_momentum::two_point_coefficients(p, m1, m2, Sheet::Second);
+        let lambda = p * p + m1 * m1 + m2 * m2 - 2.0 * (p * m1 + p * m2 + m1 * m2);
+        let discontinuity = c(0.0, 2.0 * PI) * lambda.sqrt() / p;
+        assert_close(second.b0.epsilon_0() - first.b0.epsilon_0(), discontinuity, 1e-13);
+        assert_eq!(second.b00.epsilon_minus_1(), first.b00.epsilon_minus_1());
+    }
+
+    // Both sheets coincide at vanishing momentum
+    let m1 = c(0.5, 0.0);
+    let m2 = c(0.2, 0.0);
+    let zero = c(0.0, 0.0);
+    let first = complex_momentum::two_point_coefficients(zero, m1, m2, Sheet::First);
+    let second = complex_momentum::two_point_coefficients(zero, m1, m2, Sheet::Second);
+    assert_eq!(first, second);
+    for (a, b) in finite_parts(first).into_iter().zip(quadrature(zero, m1, m2)) {
+        assert_close(a, b, 1e-12);
+    }
+}
@@ -0,0 +1,94 @@
+use num_complex::{Complex64, ComplexFloat};
+use oneloop_bridge::derivative::{self, Method, Variable};
+use oneloop_bridge::{OLOResult, two_point};
+#[cfg(oneloop_linked)]
+use oneloop_bridge::{four_point, three_point};
+
+fn assert_close(a: Complex64, b: Complex64, tolerance: f64) {
+    println!("{}\n{}", a, b);
+    assert!((a - b).abs() <= tolerance * b.abs().max(1.0));
+}
+
+/// A plain central difference of `f` at zero with step `h`.
+fn difference(f: impl Fn(f64) -> OLOResult, h: f64) -> Complex64 {
+    (f(h).epsilon_0() - f(-h).epsilon_0()) / (2.0 * h)
+}
+
+#[test]
+fn test_derivative_two_point() {
+    let c = Complex64::new;
+    let cases = [
+        (1.0, c(0.5, 0.0), c(0.2, 0.0)),
+        (10.0, c(0.5, 0.0), c(0.2, 0.0)),
+        (-3.0, c(0.2, 0.0), c(0.5, 0.0)),
+        (3.0, c(1.0, -0.1), c(0.3, -0.05)),
+        (91.2f64.powi(2), c(80.4f64.powi(2), -80.4 * 2.1), c(1e-3, 0.0)),
+    ];
+    for (p, m1, m2) in cases {
+        let h = 1e-6 * p.abs();
+        let d = derivative::two_point(p, m1, m2, Variable::Momentum(0));
+        assert_eq!(d.method, Method::Analytic);
+        assert_close(d.value.epsilon_0(), difference(|t| two_point(p + t, m1, m2), h), 1e-6);
+        assert_eq!(d.value.epsilon_minus_1(), c(0.0, 0.0));
+
+        let d = derivative::two_point(p, m1, m2, Variable::Mass(0));
+        assert_close(d.value.epsilon_0(), difference(|t| two_point(p, m1 + t, m2), 1e-6 * m1.abs()), 1e-6);
+        let d = derivative::two_point(p, m1, m2, Variable::Mass(1));
+        assert_close(d.value.epsilon_0(), difference(|t| two_point(p, m1, m2 + t), 1e-6 * m2.abs()), 1e-6);
+    }
+
+    // Finite differences at the special points, with ∂B0(p, 0, 0)/∂p = -1/p
+    // and ∂B0(0, m, m)/∂m1 = -1/(2m)
+    let zero = c(0.0, 0.0);
+    let d = derivative::two_point(-2.0, zero, zero, Variable::Momentum(0));
+    println!("{:?}", d);
+    assert_eq!(d.method, Method::Richardson);
+    assert!(d.error < 1e-10);
+    assert_close(d.value.epsilon_0(), c(0.5, 0.0), 1e-10);
+    let m = c(0.5, -0.1);
+    let d = derivative::two_point(0.0, m, m, Variable::Mass(0));
+    assert!(d.error < 1e-10);
+    assert_close(d.value.epsilon_0(), -0.5 / m, 1e-10);
+}
+
+#[test]
+#[should_panic(expected = "mass index 2 out of range")]
+fn test_derivative_index() {
+    derivative::two_point(1.0, Complex64::new(1.0, 0.0), Complex64::new(1.0, 0.0), Variable::Mass(2));
+}
+
+#[cfg(oneloop_linked)]
+#[test]
+fn test_derivative_three_and_four_point() {
+    let c = Complex64::new;
+
+    let p = [1.0, 2.0, 10.0];
+    let m = [c(0.5, 0.0), c(1.0, -0.1), c(0.3, 0.0)];
+    let d = derivative::three_point(p[0], p[1], p[2], m[0], m[1], m[2], Variable::Mass(1));
+    println!("{:?}", d);
+    assert_eq!(d.method, Method::Richardson);
+    let expected = difference(|t| three_point(p[0], p[1], p[2], m[0], m[1] + t, m[2]), 1e-6);
+    assert_close(d.value.epsilon_0(), expected, 1e-6);
+
+    // C0 and D0 are homogeneous of degree -1 and -2 in the squared masses
+    // and momenta
+    let euler: Complex64 = (0..3)
+        .map(|i| {
+            let dp = derivative::three_point(p[0], p[1], p[2], m[0], m[1], m[2], Variable::Momentum(i));
+            let dm = derivative::three_point(p[0], p[1], p[2], m[0], m[1], m[2], Variable::Mass(i));
+            assert!(dp.error < 1e-8 && dm.error < 1e-8);
+            p[i] * dp.value.epsilon_0() + m[i] * dm.value.epsilon_0()
+        })
+        .sum();
+    assert_close(euler, -three_point(p[0], p[1], p[2], m[0], m[1], m[2]).epsilon_0(), 1e-8);
+
+    let p = [1.0, 2.0, 3.0, 4.0, 20.0, -6.0];
+    let m = [c(0.5, 0.0), c(1.0, 0.0), c(0.3, -0.01), c(2.0, 0.0)];
+    let d4 = |v| derivative::four_point(p[0], p[1], p[2], p[3], p[4], p[5], m[0], m[1], m[2], m[3], v);
+    let euler: Complex64 = (0..6)
+        .map(|i| p[i] * d4(Variable::Momentum(i)).value.epsilon_0())
+        .chain((0..4).map(|i| m[i] * d4(Variable::Mass(i)).value.epsilon_0()))
+        .sum();
+    let d0 = four_point(p[0], p[1], p[2], p[3], p[4], p[5], m[0], m[1], m[2], m[3]);
+    assert_close(euler, -2.0 * d0.epsilon_0(), 1e-7);
+}
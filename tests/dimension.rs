@@ -0,0 +1,121 @@
+use num_complex::{Complex64, ComplexFloat};
+#[cfg(oneloop_linked)]
+use oneloop_bridge::four_point_d6;
+use oneloop_bridge::{set_renormalization_scale, three_point_d6};
+use std::f64::consts::PI;
+
+fn assert_close(a: Complex64, b: Complex64, tolerance: f64) {
+    println!("{}\n{}", a, b);
+    assert!((a - b).abs() <= tolerance * b.abs().max(1.0));
+}
+
+/// Gauss-Legendre nodes and weights on `[0, 1]`.
+fn gauss_legendre(n: usize) -> Vec<(f64, f64)> {
+    (0..n)
+        .map(|i| {
+            let mut x = (PI * (i as f64 + 0.75) / (n as f64 + 0.5)).cos();
+            let mut derivative = 0.0;
+            for _ in 0..100 {
+                let (mut p0, mut p1) = (1.0, x);
+                for k in 2..=n {
+                    (p0, p1) = (p1, ((2 * k - 1) as f64 * x * p1 - (k - 1) as f64 * p0) / k as f64);
+                }
+                derivative = n as f64 * (x * p1 - p0) / (x * x - 1.0);
+                x -= p1 / derivative;
+            }
+            ((1.0 - x) / 2.0, 1.0 / ((1.0 - x * x) * derivative * derivative))
+        })
+        .collect()
+}
+
+/// `F = ½ xᵀ Y x` with the modified Cayley matrix of masses `m` and
+/// invariants `r`.
+fn cayley_form(x: &[f64], m: &[Complex64], r: &[Vec<f64>]) -> Complex64 {
+    let mut f = Complex64::new(0.0, 0.0);
+    for i in 0..x.len() {
+        for j in 0..x.len() {
+            f += x[i] * x[j] * (m[i] + m[j] - r[i][j]) / 2.0;
+        }
+    }
+    f
+}
+
+/// `∫ d³x δ(1 - Σx) f(x)` over the triangle, mapped from the unit square.
+fn triangle(f: impl Fn(&[f64]) -> Complex64) -> Complex64 {
+    let rule = gauss_legendre(40);
+    let mut sum = Complex64::new(0.0, 0.0);
+    for &(u, wu) in &rule {
+        for &(v, wv) in &rule {
+            let x = [u, (1.0 - u) * v, (1.0 - u) * (1.0 - v)];
+            sum += f(&x) * wu * wv * (1.0 - u);
+        }
+    }
+    sum
+}
+
+/// `∫ d⁴x δ(1 - Σx) f(x)` over the tetrahedron, mapped from the unit cube.
+#[cfg(oneloop_linked)]
+fn tetrahedron(f: impl Fn(&[f64]) -> Complex64) -> Complex64 {
+    let rule = gauss_legendre(24);
+    let mut sum = Complex64::new(0.0, 0.0);
+    for &(u, wu) in &rule {
+        for &(v, wv) in &rule {
+            for &(w, ww) in &rule {
+                let rest = (1.0 - u) * (1.0 - v);
+                let x = [u, (1.0 - u) * v, rest * w, rest * (1.0 - w)];
+                sum += f(&x) * wu * wv * ww * (1.0 - u) * (1.0 - u) * (1.0 - v);
+            }
+        }
+    }
+    sum
+}
+
+#[test]
+fn test_three_point_d6() {
+    let c = Complex64::new;
+    // -Γ(ε) ∫ F^(-ε) = -1/(2ε) + ∫ ln(F/μ²) + O(ε)
+    let cases = [
+        ([-1.0, -2.0, -3.0], [c(0.5, 0.0), c(1.0, 0.0), c(0.3, 0.0)]),
+        ([1.0, 2.0, 0.5], [c(0.5, 0.0), c(1.0, -0.1), c(0.3, -0.2)]),
+        ([-1.0, 0.0, -4.0], [c(0.2, 0.0), c(1.0, 0.0), c(2.0, 0.0)]),
+    ];
+    for mu in [1.0, 3.0] {
+        set_renormalization_scale(mu);
+        for ([p1, p2, p3], m) in cases {
+            let r = vec![vec![0.0, p1, p3], vec![p1, 0.0, p2], vec![p3, p2, 0.0]];
+            let expected = triangle(|x| (cayley_form(x, &m, &r) / (mu * mu)).ln());
+            let result = three_point_d6(p1, p2, p3, m[0], m[1], m[2]);
+            println!("{}", result);
+            assert_close(result.epsilon_0(), expected, 1e-10);
+            assert_close(result.epsilon_minus_1(), c(-0.5, 0.0), 1e-12);
+            assert!(result.epsilon_minus_2().abs() < 1e-12);
+        }
+    }
+    set_renormalization_scale(1.0);
+}
+
+#[cfg(oneloop_linked)]
+#[test]
+fn test_four_point_d6() {
+    let c = Complex64::new;
+    // Γ(1+ε) ∫ F^(-1-ε) = ∫ 1/F + O(ε)
+    let cases = [
+        ([-1.0, -2.0, -3.0, -4.0, -5.0, -6.0], [c(0.5, 0.0), c(1.0, 0.0), c(0.3, 0.0), c(2.0, 0.0)]),
+        ([-1.0, -2.0, -3.0, -4.0, -5.0, -6.0], [c(0.5, -0.1), c(1.0, -0.3), c(0.3, 0.0), c(2.0, -0.5)]),
+        ([0.0, 0.0, -3.0, -4.0, -5.0, -6.0], [c(1.0, 0.0), c(1.0, 0.0), c(1.0, 0.0), c(1.0, 0.0)]),
+    ];
+    for ([p1, p2, p3, p4, p12, p23], m) in cases {
+        let r = vec![
+            vec![0.0, p1, p12, p4],
+            vec![p1, 0.0, p2, p23],
+            vec![p12, p2, 0.0, p3],
+            vec![p4, p23, p3, 0.0],
+        ];
+        let expected = tetrahedron(|x| 1.0 / cayley_form(x, &m, &r));
+        let result = four_point_d6(p1, p2, p3, p4, p12, p23, m[0], m[1], m[2], m[3]);
+        println!("{}", result);
+        assert_close(result.epsilon_0(), expected, 1e-9);
+        assert!(result.epsilon_minus_1().abs() < 1e-12);
+        assert!(result.epsilon_minus_2().abs() < 1e-12);
+    }
+}
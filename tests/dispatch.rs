@@ -0,0 +1,68 @@
+#![cfg(oneloop_linked)]
+
+use num_complex::{Complex64, ComplexFloat};
+use oneloop_bridge::dispatch::{self, Routine};
+use oneloop_bridge::{OLOResult, four_point, three_point};
+
+/// The highest pole with a non-zero coefficient.
+fn pole_order(r: &OLOResult) -> usize {
+    if r.epsilon_minus_2().abs() > 0.0 {
+        2
+    } else if r.epsilon_minus_1().abs() > 0.0 {
+        1
+    } else {
+        0
+    }
+}
+
+#[test]
+fn test_dispatch_3_point() {
+    let zero = Complex64::new(0.0, 0.0);
+    let m = Complex64::new(1.0, 0.0);
+    let cases = [
+        ([0.0, 0.0, 2.0], [zero, zero, zero], Routine::Tria0 { massive_legs: 1 }),
+        ([0.0, 3.0, 2.0], [zero, zero, zero], Routine::Tria0 { massive_legs: 2 }),
+        ([1.0, 3.0, 2.0], [zero, zero, zero], Routine::TriF0),
+        ([0.0, 1.0, 1.0], [zero, zero, m], Routine::Tria1),
+        ([0.0, 1.0, 2.0], [zero, zero, m], Routine::Tria2),
+        ([0.0, 3.0, 2.0], [zero, zero, m], Routine::Tria3),
+        ([1.0, 2.5, 1.0], [zero, m, m], Routine::Tria4),
+        ([1.0, 2.5, 1.5], [zero, m, m], Routine::TriF2),
+        ([1.0, 1.0, 5.0], [m, m, m], Routine::TriF3),
+        ([1.0, 2.0, 3.0], [m, m, m], Routine::TriF3HV),
+    ];
+    for (p, m, expected) in cases {
+        let d = dispatch::three_point(p[0], p[1], p[2], m[0], m[1], m[2]).unwrap();
+        let r = three_point(p[0], p[1], p[2], m[0], m[1], m[2]);
+        println!("{:?}\n{}", d, r);
+        assert_eq!(d.routine, expected);
+        assert_eq!(d.pole_order(), pole_order(&r));
+    }
+
+    // OneLOop returns zero for a massless triangle with light-like legs
+    assert_eq!(dispatch::three_point(0.0, 0.0, 0.0, zero, zero, zero), None);
+}
+
+#[test]
+fn test_dispatch_4_point() {
+    let zero = Complex64::new(0.0, 0.0);
+    let m = Complex64::new(1.0, 0.0);
+    let cases = [
+        ([0.0, 0.0, 0.0, 0.0, -3.0, -2.0], [zero; 4], Routine::Box00 { massive_legs: 0 }),
+        ([0.0, 2.0, 0.0, 5.0, -3.0, -2.0], [zero; 4], Routine::Box03),
+        ([0.0, 0.0, 1.0, 1.0, -3.0, -2.0], [zero, zero, zero, m], Routine::Box06),
+        // The same box with the massive propagator first
+        ([1.0, 0.0, 0.0, 1.0, -2.0, -3.0], [m, zero, zero, zero], Routine::Box06),
+        ([1.0, 1.0, 1.0, 1.0, -3.0, -2.0], [zero, m, zero, m], Routine::Box14),
+        ([-1.0, 2.0, 3.0, 4.0, -5.0, 6.0], [m; 4], Routine::BoxF4),
+        ([1.0, 2.0, 3.0, 4.0, 5.0, 6.0], [m; 4], Routine::BoxC),
+    ];
+    for (p, m, expected) in cases {
+        let d = dispatch::four_point(p[0], p[1], p[2], p[3], p[4], p[5], m[0], m[1], m[2], m[3])
+            .unwrap();
+        let r = four_point(p[0], p[1], p[2], p[3], p[4], p[5], m[0], m[1], m[2], m[3]);
+        println!("{:?}\n{}", d, r);
+        assert_eq!(d.routine, expected);
+        assert_eq!(d.pole_order(), pole_order(&r));
+    }
+}
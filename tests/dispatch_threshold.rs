@@ -0,0 +1,23 @@
+// A test binary of its own, as the on-shell threshold is global and OneLOop
+// cannot go back to its default once it is set
+use num_complex::Complex64;
+use oneloop_bridge::dispatch::{self, NearlyOnShell, Routine};
+use oneloop_bridge::set_onshell_threshold;
+
+#[test]
+fn test_dispatch_nearly_on_shell() {
+    let zero = Complex64::new(0.0, 0.0);
+    let m = Complex64::new(1.0, 0.0);
+    let p1 = 1.0 + 2.0 * f64::EPSILON;
+
+    // OneLOop treats the leg as off shell and only warns
+    let d = dispatch::three_point(p1, 2.5, 1.0, zero, m, m).unwrap();
+    println!("{:?}", d);
+    assert_eq!(d.routine, Routine::TriF2);
+    assert_eq!(d.nearly_on_shell, vec![NearlyOnShell::Leg { momentum: 0, mass: 1 }]);
+
+    set_onshell_threshold(1e-6);
+    let d = dispatch::three_point(p1, 2.5, 1.0, zero, m, m).unwrap();
+    assert_eq!(d.routine, Routine::Tria4);
+    assert!(d.nearly_on_shell.is_empty());
+}
@@ -0,0 +1,59 @@
+use num_complex::Complex64;
+use oneloop_bridge::OLOResult;
+use oneloop_bridge::expression::evaluate;
+use oneloop_bridge::input::Point;
+use std::collections::HashMap;
+
+#[test]
+fn test_result_expressions() {
+    let r = OLOResult::new(Complex64::new(-1.5, 3.25e-7), Complex64::new(-0.1, 0.0), Complex64::new(0.0, 0.0));
+    println!("{}\n{}\n{}", r.to_mathematica(), r.to_form(), r.to_latex());
+    assert_eq!(r.to_mathematica(), "(-1.5 + 3.25*^-7*I) - 1.*^-1/eps");
+    assert_eq!(
+        r.to_form(),
+        "(-3/2 + 6139076427730539/18889465931478580854784*i_) - 3602879701896397/36028797018963968/ep"
+    );
+    assert_eq!(r.to_latex(), "(-1.5 + 3.25 \\times 10^{-7}\\,i) - \\frac{0.1}{\\epsilon}");
+
+    // Finite results have no poles, and all digits of a coefficient are kept
+    let r = OLOResult::new(Complex64::new(1.0 / 3.0, -0.0), Complex64::new(0.0, 0.0), Complex64::new(0.0, 0.0));
+    assert_eq!(r.to_mathematica(), "3.333333333333333`*^-1");
+    assert_eq!(r.to_mathematica().replace("`*^", "e").parse::<f64>().unwrap(), 1.0 / 3.0);
+    assert_eq!(r.to_form(), "6004799503160661/18014398509481984");
+    assert_eq!(r.to_latex(), "0.3333333333333333");
+
+    // Mantissas beyond machine precision are marked as machine numbers
+    let r = OLOResult::new(Complex64::new(0.1 + 0.2, 0.0), Complex64::new(0.0, 0.0), Complex64::new(0.0, 0.0));
+    assert_eq!(r.to_mathematica(), "3.0000000000000004`*^-1");
+    assert_eq!(evaluate(&r.to_mathematica(), &HashMap::new()).unwrap(), r);
+
+    // Double poles with purely imaginary and integer coefficients
+    let r = OLOResult::new(Complex64::new(0.0, 0.0), Complex64::new(0.0, 2.0), Complex64::new(1e20, 0.0));
+    assert_eq!(r.to_mathematica(), "0. + 2.*I/eps + 1.*^20/eps^2");
+    assert_eq!(r.to_form(), "0 + 2*i_/ep + 100000000000000000000/ep^2");
+    assert_eq!(r.to_latex(), "0 + \\frac{2\\,i}{\\epsilon} + \\frac{1 \\times 10^{20}}{\\epsilon^2}");
+
+    let zero = Complex64::new(0.0, 0.0);
+    let r = OLOResult::new(Complex64::new(f64::NAN, f64::NEG_INFINITY), zero, zero);
+    assert_eq!(r.to_mathematica(), "(Indeterminate - Infinity*I)");
+    assert_eq!(r.to_latex(), "(\\mathrm{NaN} - \\infty\\,i)");
+}
+
+#[test]
+fn test_point_expressions() {
+    let point = Point {
+        legs: 2,
+        mu: 1.0,
+        momenta: vec![1.5],
+        masses: vec![Complex64::new(1.0, -0.25), Complex64::new(0.0, 0.0)],
+        line: 0,
+    };
+    assert_eq!(point.to_mathematica(), "B0[1.5, 1. - 2.5*^-1*I, 0.]");
+    assert_eq!(point.to_form(), "B0(3/2, 1 - 1/4*i_, 0)");
+    assert_eq!(point.to_latex(), "B_0(1.5, 1 - 0.25\\,i, 0)");
+
+    let point = Point { legs: 1, mu: 1.0, momenta: vec![], masses: vec![Complex64::new(6464.16, 0.0)], line: 0 };
+    assert_eq!(point.to_mathematica(), "A0[6.46416*^3]");
+    assert_eq!(point.to_latex(), "A_0(6464.16)");
+    assert_eq!(point.to_form(), "A0(1776854770951127/274877906944)");
+}
@@ -0,0 +1,76 @@
+use num_complex::Complex64;
+use oneloop_bridge::complex_momentum::{Sheet, two_point_coefficients};
+use oneloop_bridge::derivative::{self, Variable};
+use oneloop_bridge::expression::{Expression, ExpressionError, evaluate};
+use oneloop_bridge::{OLOResult, one_point, three_point, two_point};
+use std::collections::HashMap;
+
+fn bindings(values: &[(&str, f64)]) -> HashMap<String, Complex64> {
+    values.iter().map(|&(name, x)| (name.to_string(), Complex64::from(x))).collect()
+}
+
+#[test]
+fn test_linear_combinations() {
+    let (s, mt) = (-2.5, 1.3);
+    let values = bindings(&[("s", s), ("mt", mt)]);
+    let text = "2*B0[s, 0, mt^2] - C0[0, 0, s, mt^2, mt^2, mt^2]/s + A0[mt^2]";
+    let r = evaluate(text, &values).unwrap();
+    println!("{} = {}", text, r);
+    let m = mt * mt;
+    let expected = two_point(s, 0.0, m) * 2.0 - three_point(0.0, 0.0, s, m, m, m) * (1.0 / s) + one_point(m);
+    r.agrees_with(&expected, 1e-14).unwrap();
+
+    // Tensor coefficients, which come from the backend and so possibly from
+    // OneLOop, and the derivative of B0
+    let coefficients = two_point_coefficients(s.into(), 0.5, m, Sheet::First);
+    let r = evaluate("B1[s, 0.5, mt^2] - 3*B00[s, 0.5, mt^2]", &values).unwrap();
+    r.agrees_with(&(coefficients.b1 - coefficients.b00 * 3.0), 1e-12).unwrap();
+    let r = evaluate("db0(s, 0.5, mt^2)", &values).unwrap();
+    r.agrees_with(&derivative::two_point(s, 0.5, m, Variable::Momentum(0)).value, 1e-14).unwrap();
+
+    // Mathematica numbers, complex masses and constants
+    let r = evaluate("-(B0[1.5*^0, 1 - 1.*^-1*I, 0] + 2)*Pi", &HashMap::new()).unwrap();
+    let zero = Complex64::new(0.0, 0.0);
+    let two = OLOResult::new(Complex64::new(2.0, 0.0), zero, zero);
+    let expected = (two_point(1.5, Complex64::new(1.0, -0.1), 0.0) + two) * -std::f64::consts::PI;
+    r.agrees_with(&expected, 1e-14).unwrap();
+
+    // Numbers alone are a finite part, -a^2 is -(a^2) and powers are right-associative
+    let r = evaluate("-2^2^3 + 1e-3*I + .5", &HashMap::new()).unwrap();
+    assert_eq!(r, OLOResult::new(Complex64::new(-255.5, 1e-3), zero, zero));
+}
+
+#[test]
+fn test_symbols() {
+    let expression = Expression::parse("B0[p, m^2, m^2] + x*A0[m^2] - x").unwrap();
+    assert_eq!(expression.symbols(), vec!["m", "p", "x"]);
+    assert!(Expression::parse("Pi*I").unwrap().symbols().is_empty());
+}
+
+#[test]
+fn test_errors() {
+    let error = |text: &str| {
+        let e = evaluate(text, &bindings(&[("s", 2.0)])).unwrap_err();
+        println!("{}: {}", text, e);
+        e
+    };
+    assert_eq!(error("B0[s, 0, 1] # 2"), ExpressionError::InvalidCharacter { column: 13, character: '#' });
+    assert_eq!(error("B0[s, 0, 1))"), ExpressionError::UnexpectedToken { column: 11, token: ")".to_string() });
+    assert_eq!(error("2*B0[s, 0, 1] 3"), ExpressionError::UnexpectedToken { column: 15, token: "3".to_string() });
+    assert_eq!(error("2*(s + "), ExpressionError::UnexpectedEnd);
+    assert_eq!(error("E0[1]"), ExpressionError::UnknownFunction { column: 1, name: "E0".to_string() });
+    assert_eq!(
+        error("1 + C0[s, s, 0, 1]"),
+        ExpressionError::WrongArgumentCount { column: 5, function: "C0".to_string(), expected: 6, found: 4 }
+    );
+    assert_eq!(error("B0[s, 0, mt^2]"), ExpressionError::UnboundSymbol { column: 10, name: "mt".to_string() });
+    assert_eq!(
+        error("B0[s + I, 0, 1]"),
+        ExpressionError::ComplexMomentum { column: 1, function: "B0".to_string() }
+    );
+    assert_eq!(error("A0[1] * A0[2]"), ExpressionError::NotLinear { column: 7 });
+    assert_eq!(error("1/A0[1]"), ExpressionError::NotLinear { column: 2 });
+    assert_eq!(error("A0[1]^2"), ExpressionError::NotLinear { column: 6 });
+    assert_eq!(error("B0[A0[1], 0, 1]"), ExpressionError::NotLinear { column: 1 });
+    assert_eq!(error("A0[1] * A0[2]").to_string(), "column 7: the expression is not linear in the integrals");
+}
@@ -0,0 +1,124 @@
+use num_complex::Complex64;
+#[cfg(oneloop_linked)]
+use num_complex::ComplexFloat;
+use oneloop_bridge::{ReductionError, five_point, six_point};
+
+/// Minkowski square of the sum of the given momenta.
+#[cfg(oneloop_linked)]
+fn square(momenta: &[[f64; 4]]) -> f64 {
+    let mut sum = [0.0; 4];
+    for k in momenta {
+        for mu in 0..4 {
+            sum[mu] += k[mu];
+        }
+    }
+    sum[0] * sum[0] - sum[1] * sum[1] - sum[2] * sum[2] - sum[3] * sum[3]
+}
+
+/// Six four-momenta adding up to zero, the first five of which also give
+/// a pentagon when the last two are merged.
+#[cfg(oneloop_linked)]
+fn momenta() -> [[f64; 4]; 6] {
+    let k = [
+        [0.5, 0.1, -0.2, 0.3],
+        [0.4, -0.3, 0.1, 0.2],
+        [-0.6, 0.2, 0.3, -0.1],
+        [0.3, 0.4, -0.1, -0.2],
+        [0.2, -0.1, 0.2, 0.1],
+        [0.0; 4],
+    ];
+    let mut last = [0.0; 4];
+    for ki in &k[..5] {
+        for mu in 0..4 {
+            last[mu] -= ki[mu];
+        }
+    }
+    [k[0], k[1], k[2], k[3], k[4], last]
+}
+
+#[cfg(oneloop_linked)]
+#[test]
+fn test_olo_5_point_heavy_mass() {
+    let k = momenta();
+    let k5 = [k[4][0] + k[5][0], k[4][1] + k[5][1], k[4][2] + k[5][2], k[4][3] + k[5][3]];
+    let p = [
+        square(&k[0..1]),
+        square(&k[1..2]),
+        square(&k[2..3]),
+        square(&k[3..4]),
+        square(&[k5]),
+        square(&k[0..2]),
+        square(&k[1..3]),
+        square(&k[2..4]),
+        square(&[k[3], k5]),
+        square(&[k5, k[0]]),
+    ];
+    let m = Complex64::new(100.0, 0.0);
+    let r = five_point(p, [m; 5]).unwrap();
+    println!("{}", r);
+
+    // For m² ≫ p² the integral approaches -1/(12 m⁶)
+    let expected = -1.0 / (12.0 * 100.0_f64.powi(3));
+    assert!((r.epsilon_0() - expected).abs() < 1e-2 * expected.abs());
+    assert!(r.epsilon_minus_1().abs() < 1e-12);
+}
+
+#[cfg(oneloop_linked)]
+#[test]
+fn test_olo_5_point_cyclic_symmetry() {
+    let p = [0.0, 0.0, 0.0, 0.0, 0.0, 3.0, -1.2, 2.1, -0.7, -0.4];
+    let m = [0.1, 0.2, 0.3, 0.4, 0.5].map(|m| Complex64::new(m, 0.0));
+    let r = five_point(p, m).unwrap();
+    let rotated = five_point(
+        [p[1], p[2], p[3], p[4], p[0], p[6], p[7], p[8], p[9], p[5]],
+        [m[1], m[2], m[3], m[4], m[0]],
+    )
+    .unwrap();
+    println!("{}\n{}", r, rotated);
+    assert!((r.epsilon_0() - rotated.epsilon_0()).abs() < 1e-10 * r.epsilon_0().abs());
+}
+
+#[cfg(oneloop_linked)]
+#[test]
+fn test_olo_6_point_heavy_mass() {
+    let k = momenta();
+    let p = [
+        square(&k[0..1]),
+        square(&k[1..2]),
+        square(&k[2..3]),
+        square(&k[3..4]),
+        square(&k[4..5]),
+        square(&k[5..6]),
+        square(&k[0..2]),
+        square(&k[1..3]),
+        square(&k[2..4]),
+        square(&k[3..5]),
+        square(&k[4..6]),
+        square(&[k[5], k[0]]),
+        square(&k[0..3]),
+        square(&k[1..4]),
+        square(&k[2..5]),
+    ];
+    let m = Complex64::new(100.0, 0.0);
+    let r = six_point(p, [m; 6]).unwrap();
+    println!("{}", r);
+
+    // For m² ≫ p² the integral approaches 1/(20 m⁸)
+    let expected = 1.0 / (20.0 * 100.0_f64.powi(4));
+    assert!((r.epsilon_0() - expected).abs() < 1e-2 * expected.abs());
+}
+
+#[test]
+fn test_olo_5_point_degenerate_momenta() {
+    // With k1 = 0 the first two propagators coincide, and so do the first two
+    // rows of the modified Cayley matrix
+    let p = [0.0, 1.0, 2.0, 3.0, 4.0, 1.0, -1.2, 2.1, -1.2, 4.0];
+    let m = [0.3, 0.3, 0.5, 0.7, 0.9].map(|m| Complex64::new(m, 0.0));
+    let r = five_point(p, m);
+    println!("{:?}", r);
+    assert!(matches!(r, Err(ReductionError::SingularCayleyMatrix { .. })));
+
+    // Vanishing momenta and equal masses give a matrix of rank one
+    let r = six_point([0.0; 15], [Complex64::new(0.5, 0.0); 6]);
+    assert!(matches!(r, Err(ReductionError::SingularCayleyMatrix { relative_pivot: 0.0 })));
+}
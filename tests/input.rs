@@ -0,0 +1,54 @@
+use num_complex::Complex64;
+use oneloop_bridge::input::{self, InputError};
+
+#[test]
+fn test_input_parse() {
+    let text = "\
+2  0.1726d0     ! nleg  mu
+(-1.7d0 , 0d0 ) ! p1^2
+( 0.0d0 , 0d0 ) ! m1^2
+( 1.5d0 , 0d0 ) ! m2^2
+next
+4  1.1d0
+(0d0,0d0)
+(0d0,0d0)
+(1.2D0,0d0)
+(1.2d0,0d0)
+(2.1d0,0d0)
+(-1.9d0,0d0)
+(0d0,0d0)
+(0d0,0d0)
+0d0
+( 1.0d0 ,-1d-2  )
+stop
+1 1.0
+garbage after stop
+";
+    let points = input::parse(text).unwrap();
+    println!("{:?}", points);
+    assert_eq!(points.len(), 2);
+    assert_eq!((points[0].legs, points[0].mu, points[0].line), (2, 0.1726, 1));
+    assert_eq!(points[0].momenta, vec![-1.7]);
+    assert_eq!(points[0].masses, vec![Complex64::new(0.0, 0.0), Complex64::new(1.5, 0.0)]);
+    assert_eq!(points[1].momenta, vec![0.0, 0.0, 1.2, 1.2, 2.1, -1.9]);
+    assert_eq!(points[1].masses[3], Complex64::new(1.0, -1e-2));
+
+    // The last point may end without `next` or `stop`
+    assert_eq!(input::parse("1 1.0\n(2d0,-1d0)\n").unwrap()[0].masses, vec![Complex64::new(2.0, -1.0)]);
+}
+
+#[test]
+fn test_input_errors() {
+    assert_eq!(input::parse("two 1.0\n"), Err(InputError::InvalidHeader { line: 1 }));
+    assert_eq!(input::parse("5 1.0\n"), Err(InputError::UnsupportedLegs { line: 1, legs: 5 }));
+    assert_eq!(input::parse("1 1.0\n(1d0;0d0)\n"), Err(InputError::InvalidValue { line: 2 }));
+    assert_eq!(input::parse("2 1.0\n(1d0,1d0)\n"), Err(InputError::ComplexMomentum { line: 2 }));
+    assert_eq!(
+        input::parse("2 1.0\n(1d0,0d0)\n(1d0,0d0)\nnext\n"),
+        Err(InputError::WrongValueCount { line: 4, expected: 3, found: 2 })
+    );
+    assert_eq!(
+        input::parse("1 1.0\n(1d0,0d0)\n(1d0,0d0)\n"),
+        Err(InputError::WrongValueCount { line: 3, expected: 1, found: 2 })
+    );
+}
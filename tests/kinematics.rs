@@ -0,0 +1,63 @@
+use num_complex::{Complex64, ComplexFloat};
+use oneloop_bridge::kinematics::{Kinematics, Singularity};
+
+#[test]
+fn test_kinematics_matrices() {
+    let m = Complex64::new(1.0, 0.0);
+    let k = Kinematics::three_point(3.0, 3.0, 3.0, m, m, m);
+    println!("{:?}\n{:?}", k.cayley_matrix(), k.gram_matrix());
+
+    // Y = [[2, -1, -1], [-1, 2, -1], [-1, -1, 2]] and Z = [[6, 3], [3, 6]]
+    assert!(k.cayley_determinant().abs() < 1e-12);
+    assert!((k.gram_determinant() - 27.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_kinematics_ir_divergences() {
+    let zero = Complex64::new(0.0, 0.0);
+    let m = Complex64::new(1.0, 0.0);
+
+    // Photon exchange between two on-shell massive legs
+    let k = Kinematics::three_point(1.0, 2.5, 1.0, zero, m, m);
+    assert_eq!(k.classify(), vec![Singularity::Soft { propagator: 0 }]);
+
+    // Massless triangle with two light-like legs
+    let k = Kinematics::three_point(0.0, 0.0, 2.0, zero, zero, zero);
+    let singularities = k.classify();
+    println!("{:?}", singularities);
+    assert!(singularities.contains(&Singularity::Soft { propagator: 1 }));
+    assert!(singularities.contains(&Singularity::Collinear { propagators: (0, 1) }));
+    assert!(singularities.contains(&Singularity::Collinear { propagators: (1, 2) }));
+}
+
+#[test]
+fn test_kinematics_thresholds() {
+    let m = Complex64::new(1.0, 0.0);
+    let k = Kinematics::two_point(4.0, m, m);
+    assert_eq!(k.classify(), vec![Singularity::Threshold { propagators: (0, 1) }]);
+
+    // The pseudo-threshold is not a singularity on the physical sheet
+    assert!(Kinematics::two_point(0.0, m, m).is_regular());
+
+    let k = Kinematics::three_point(3.0, 3.0, 3.0, m, m, m);
+    assert_eq!(
+        k.classify(),
+        vec![Singularity::AnomalousThreshold { propagators: vec![0, 1, 2] }]
+    );
+
+    let k = Kinematics::three_point(-1.0, -2.0, -3.0, m, m * 0.5, m * 0.2);
+    assert!(k.is_regular());
+}
+
+#[test]
+fn test_kinematics_onshell_threshold() {
+    let zero = Complex64::new(0.0, 0.0);
+    let m = Complex64::new(1.0, 0.0);
+    let k = Kinematics::three_point(1.0 + 1e-9, 2.5, 1.0, zero, m, m);
+    println!("{:?}", k.classify());
+    assert!(!k.classify().contains(&Singularity::Soft { propagator: 0 }));
+
+    // Within the threshold the leg is on shell and the divergence is soft; the
+    // global threshold is left alone, as the other tests of this binary see it
+    assert_eq!(k.classify_with_tolerance(1e-6), vec![Singularity::Soft { propagator: 0 }]);
+}
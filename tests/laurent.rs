@@ -0,0 +1,124 @@
+use num_complex::{Complex64, ComplexFloat};
+use oneloop_bridge::laurent::{self, Laurent};
+use oneloop_bridge::{one_point, two_point};
+use std::f64::consts::PI;
+
+const ZETA3: f64 = 1.202_056_903_159_594_2;
+
+fn assert_close(a: Complex64, b: Complex64, tolerance: f64) {
+    assert!((a - b).abs() <= tolerance * b.abs().max(1.0), "{} != {}", a, b);
+}
+
+fn assert_series(rust: &Laurent, expected: &Laurent, tolerance: f64) {
+    println!("{}\n{}", rust, expected);
+    assert_eq!(rust.leading_order(), expected.leading_order());
+    assert_eq!(rust.order(), expected.order());
+    for k in rust.leading_order()..=rust.order() {
+        assert_close(rust.coefficient(k), expected.coefficient(k), tolerance);
+    }
+}
+
+/// `Σₖ cₖ εᵏ` for `k` from `leading`.
+fn series(leading: i32, coefficients: &[f64]) -> Laurent {
+    Laurent::new(leading, coefficients.iter().map(|&c| Complex64::from(c)).collect())
+}
+
+/// `exp(a ε)` through `ε⁴`.
+fn exponential(a: Complex64) -> Laurent {
+    Laurent::new(0, vec![1.0.into(), a, a * a / 2.0, a.powi(3) / 6.0, a.powi(4) / 24.0])
+}
+
+/// `Γ(1-2ε)/Γ²(1-ε)` through `ε⁴`.
+fn gamma_ratio() -> Laurent {
+    let zeta2 = PI * PI / 6.0;
+    let zeta4 = PI.powi(4) / 90.0;
+    series(0, &[1.0, 0.0, zeta2, 2.0 * ZETA3, 3.5 * zeta4 + zeta2 * zeta2 / 2.0])
+}
+
+#[test]
+fn test_laurent_arithmetic() {
+    let a = series(-1, &[1.0, 2.0, 3.0]);
+    let b = series(0, &[1.0, -1.0, 0.5, 4.0]);
+
+    // Sums and products are known up to the lower order of the two
+    let sum = a.clone() + b.clone();
+    assert_eq!((sum.leading_order(), sum.order()), (-1, 1));
+    assert_eq!(sum.coefficients(), series(-1, &[1.0, 3.0, 2.0]).coefficients());
+    let product = a.clone() * b;
+    assert_eq!((product.leading_order(), product.order()), (-1, 1));
+    assert_eq!(product.coefficients(), series(-1, &[1.0, 1.0, 1.5]).coefficients());
+    assert_eq!((a.clone() - a.clone()).coefficient(0), Complex64::from(0.0));
+    assert_eq!(a.clone().truncate(0).order(), 0);
+    assert_eq!(a.coefficient(5), Complex64::from(0.0));
+
+    let r = two_point(1.0, Complex64::new(0.5, 0.0), Complex64::new(0.2, 0.0));
+    let l = Laurent::from(r);
+    println!("{}", l);
+    assert_eq!((l.leading_order(), l.order()), (-2, 0));
+    assert_eq!(l.coefficient(0), r.epsilon_0());
+}
+
+#[test]
+fn test_laurent_one_point() {
+    // m/(ε(1-ε)) Γ(1-2ε)/Γ²(1-ε) (m/μ²)^(-ε)
+    for m in [Complex64::new(1.0, 0.0), Complex64::new(100.0, -1.4), Complex64::new(-2.0, 0.0)] {
+        let l = laurent::one_point(m, 3);
+        let pole = Laurent::new(-1, vec![m; 6]);
+        let ln_m = Complex64::new(m.re, -m.im.abs()).ln();
+        assert_series(&l, &(pole * exponential(-ln_m) * gamma_ratio()).truncate(3), 1e-14);
+
+        let r = one_point(m);
+        assert_close(l.coefficient(-1), r.epsilon_minus_1(), 1e-14);
+        assert_close(l.coefficient(0), r.epsilon_0(), 1e-14);
+    }
+    assert!(laurent::one_point(Complex64::new(0.0, 0.0), 2).coefficients().iter().all(|c| c.abs() == 0.0));
+}
+
+#[test]
+fn test_laurent_two_point() {
+    let c = Complex64::new;
+    let zero = c(0.0, 0.0);
+    let cases = [
+        (1.0, c(0.5, 0.0), c(0.2, 0.0)),
+        (10.0, c(0.5, 0.0), c(0.2, 0.0)),
+        (4.0, c(1.0, 0.0), c(1.0, 0.0)),
+        (-3.0, c(0.2, 0.0), c(0.5, 0.0)),
+        (1e6, c(1.0, 0.0), c(2.0, 0.0)),
+        (0.0, c(0.5, 0.0), c(0.2, 0.0)),
+        (0.5, zero, c(0.5, 0.0)),
+        (2.0, zero, c(0.5, 0.0)),
+        (-2.0, zero, zero),
+        (3.0, c(1.0, -0.1), c(0.3, -0.05)),
+        (100.0, c(1.0, -1.0), zero),
+    ];
+    for (p, m1, m2) in cases {
+        let l = laurent::two_point(p, m1, m2, 4);
+        let r = two_point(p, m1, m2);
+        println!("{}\n{}", l, r);
+        assert_eq!((l.leading_order(), l.order()), (-1, 4));
+        assert_close(l.coefficient(-1), r.epsilon_minus_1(), 1e-14);
+        assert_close(l.coefficient(0), r.epsilon_0(), 1e-13);
+    }
+
+    // Massless: (-p - i0)^(-ε)/(ε(1-2ε))
+    let pole = series(-1, &[1.0, 2.0, 4.0, 8.0, 16.0, 32.0]);
+    for (p, ln) in [(-2.0, c(2f64.ln(), 0.0)), (3.0, c(3f64.ln(), -PI))] {
+        let expected = pole.clone() * exponential(-ln);
+        assert_series(&laurent::two_point(p, zero, zero, 3), &expected.truncate(3), 1e-13);
+    }
+
+    // On shell with one massless line: Γ(1-2ε)/Γ²(1-ε) m^(-ε)/(ε(1-2ε))
+    let m = c(0.5, 0.0);
+    let expected = pole.clone() * exponential(-m.ln()) * gamma_ratio();
+    assert_series(&laurent::two_point(0.5, zero, m, 3), &expected.truncate(3), 1e-13);
+
+    // Vanishing momentum, where B0(0, 0, m) = A0(m)/m and
+    // B0(0, m, m) = Γ(1-2ε)/Γ²(1-ε) m^(-ε)/ε
+    for m in [c(0.5, 0.0), c(2.0, -0.3)] {
+        let tadpole = laurent::one_point(m, 3) * (1.0 / m);
+        assert_series(&laurent::two_point(0.0, zero, m, 3), &tadpole, 1e-13);
+        let expected = Laurent::new(-1, (exponential(-m.ln()) * gamma_ratio()).coefficients().to_vec());
+        assert_series(&laurent::two_point(0.0, m, m, 3), &expected.truncate(3), 1e-13);
+    }
+    assert!(laurent::two_point(0.0, zero, zero, 2).coefficients().iter().all(|c| c.abs() == 0.0));
+}
@@ -1,4 +1,5 @@
 use num_complex::Complex64;
+use oneloop_bridge::mass::Mass;
 use oneloop_bridge::{TO_FEYNMAN, two_point, three_point};
 
 
@@ -27,11 +28,11 @@ fn main() {
     let k3 = [k1[0] + k2[0], k1[1] + k2[1], k1[2] + k2[2], k1[3] + k2[3]];
     let p3 = minkowski_dot(k3);
 
-    let m = Complex64::new(0.02, 0.0);
+    let m = Mass::new(0.02).unwrap();
 
-    let m1 = m * m;
-    let m2 = m * m;
-    let m3 = m * m;
+    let m1 = m;
+    let m2 = m;
+    let m3 = m;
 
     let result = three_point(p1, p2, p3, m1, m2, m3);
     println!("3-point result: {:?}", result);
@@ -0,0 +1,67 @@
+use num_complex::Complex64;
+use oneloop_bridge::kinematics::Kinematics;
+use oneloop_bridge::mass::{Mass, MassError};
+use oneloop_bridge::{dispatch, pv};
+#[cfg(oneloop_linked)]
+use oneloop_bridge::{five_point, four_point, one_point, three_point, two_point};
+
+#[test]
+fn test_mass_constructors() {
+    let c = Complex64::new;
+    let w = Mass::with_width(80.4, 2.1).unwrap();
+    println!("{}", w);
+    assert_eq!(w.squared(), c(80.4 * 80.4, -80.4 * 2.1));
+    assert!((w.pole() * w.pole() - w.squared()).norm() <= 1e-12 * w.squared().norm());
+    assert_eq!(Mass::new(0.5).unwrap().squared(), c(0.25, 0.0));
+    assert_eq!(Mass::from_squared(c(2.0, -0.1)).unwrap().squared(), c(2.0, -0.1));
+    assert_eq!(Mass::from_squared(c(-2.0, 0.0)).unwrap().squared(), c(-2.0, 0.0));
+    assert!(Mass::MASSLESS.is_massless());
+    assert_eq!(Mass::default(), Mass::MASSLESS);
+    assert_eq!(Mass::try_from(c(1.0, -1.0)), Mass::from_squared(c(1.0, -1.0)));
+
+    // A flipped sign of the width is rejected
+    assert_eq!(
+        Mass::from_squared(c(2.0, 0.1)),
+        Err(MassError::InvalidSquaredMass { squared: c(2.0, 0.1) })
+    );
+    assert_eq!(Mass::with_width(80.4, -2.1), Err(MassError::InvalidWidth { width: -2.1 }));
+    assert_eq!(Mass::new(-1.0), Err(MassError::InvalidMass { mass: -1.0 }));
+    assert!(Mass::new(f64::NAN).is_err());
+    assert!(Mass::from_squared(c(f64::INFINITY, 0.0)).is_err());
+    println!("{}", Mass::with_width(1.0, -1.0).unwrap_err());
+}
+
+#[cfg(oneloop_linked)]
+#[test]
+fn test_mass_scalar_functions() {
+    let c = Complex64::new;
+    let m = Mass::with_width(1.0, 0.1).unwrap();
+    let s = m.squared();
+    let zero = c(0.0, 0.0);
+
+    assert_eq!(one_point(m), one_point(s));
+    assert_eq!(two_point(3.0, m, Mass::MASSLESS), two_point(3.0, s, zero));
+    assert_eq!(three_point(1.0, 2.0, 3.0, m, s, m), three_point(1.0, 2.0, 3.0, s, s, s));
+    let r = four_point(1.0, 2.0, 3.0, 4.0, 5.0, -6.0, m, m, Mass::MASSLESS, m);
+    assert_eq!(r, four_point(1.0, 2.0, 3.0, 4.0, 5.0, -6.0, s, s, zero, s));
+    let p = [1.0, 2.0, 3.0, 4.0, 5.0, -1.0, -2.0, -3.0, -4.0, -5.0];
+    assert_eq!(five_point(p, [m; 5]).unwrap(), five_point(p, [s; 5]).unwrap());
+}
+
+#[test]
+fn test_mass_kinematics_dispatch_pv() {
+    let c = Complex64::new;
+    let m = Mass::with_width(1.0, 0.1).unwrap();
+    let s = m.squared();
+    let zero = c(0.0, 0.0);
+
+    let k = Kinematics::four_point(1.0, 2.0, 3.0, 4.0, 5.0, -6.0, m, m, Mass::MASSLESS, m);
+    assert_eq!(k, Kinematics::four_point(1.0, 2.0, 3.0, 4.0, 5.0, -6.0, s, s, zero, s));
+    let d = dispatch::four_point(1.0, 2.0, 3.0, 4.0, 5.0, -6.0, m, Mass::MASSLESS, m, m);
+    assert_eq!(d, dispatch::four_point(1.0, 2.0, 3.0, 4.0, 5.0, -6.0, s, zero, s, s));
+    let r = pv::three_point(0.2, 0.5, 1.7, m, Mass::MASSLESS, m, 2).unwrap();
+    let expected = pv::three_point(0.2, 0.5, 1.7, s, zero, s, 2).unwrap();
+    for (indices, value) in r.iter() {
+        assert_eq!(Some(*value), expected.get(indices), "{:?}", indices);
+    }
+}
@@ -0,0 +1,125 @@
+// Compares with `OneLoop` itself, as `three_point` is `native` with `pure-rust`
+#![cfg(oneloop_linked)]
+
+use num_complex::{Complex64, ComplexFloat};
+use oneloop_bridge::backend::{OneLoop, ScalarIntegralBackend};
+use oneloop_bridge::{OLOResult, native};
+
+/// Asserts that all coefficients agree to `1e-14` relative to the largest one.
+fn assert_agrees(rust: OLOResult, fortran: OLOResult) {
+    assert_agrees_within(rust, fortran, 1e-14);
+}
+
+/// Asserts that all coefficients agree to `tolerance` relative to the largest one.
+fn assert_agrees_within(rust: OLOResult, fortran: OLOResult, tolerance: f64) {
+    println!("{}\n{}", rust, fortran);
+    let scale = [fortran.epsilon_0(), fortran.epsilon_minus_1(), fortran.epsilon_minus_2()]
+        .iter()
+        .fold(1.0, |a: f64, c| a.max(c.abs()));
+    assert!((rust.epsilon_0() - fortran.epsilon_0()).abs() < tolerance * scale);
+    assert!((rust.epsilon_minus_1() - fortran.epsilon_minus_1()).abs() < tolerance * scale);
+    assert!((rust.epsilon_minus_2() - fortran.epsilon_minus_2()).abs() < tolerance * scale);
+}
+
+/// A xorshift generator, uniform in `[0, 1)`.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[test]
+fn test_native_1_point() {
+    for m in [
+        Complex64::new(0.0, 0.0),
+        Complex64::new(0.5, 0.0),
+        Complex64::new(100.0, -1.4),
+        Complex64::new(-2.0, 0.0),
+        Complex64::new(1e-8, 0.0),
+    ] {
+        assert_agrees(native::one_point(m), OneLoop.one_point(m));
+    }
+}
+
+#[test]
+fn test_native_2_point() {
+    let c = Complex64::new;
+    let zero = c(0.0, 0.0);
+    let cases = [
+        // Generic real masses below, above and at threshold, and spacelike
+        (1.0, c(0.5, 0.0), c(0.2, 0.0)),
+        (10.0, c(0.5, 0.0), c(0.2, 0.0)),
+        (4.0, c(1.0, 0.0), c(1.0, 0.0)),
+        (-3.0, c(0.2, 0.0), c(0.5, 0.0)),
+        (1e6, c(1.0, 0.0), c(2.0, 0.0)),
+        (-1e5, c(1.0, 0.0), c(1.0, 0.0)),
+        (1e-6, c(1.0, 0.0), c(2.0, 0.0)),
+        // Vanishing momentum
+        (0.0, c(0.5, 0.0), c(0.2, 0.0)),
+        (0.0, c(0.5, 0.0), c(0.5, 0.0)),
+        (0.0, zero, c(0.5, 0.0)),
+        // One massless propagator, on and off shell
+        (0.5, zero, c(0.5, 0.0)),
+        (0.5, c(0.5, 0.0), zero),
+        (2.0, zero, c(0.5, 0.0)),
+        (-2.0, zero, c(0.5, 0.0)),
+        // Massless
+        (2.0, zero, zero),
+        (-2.0, zero, zero),
+        (0.0, zero, zero),
+        // Complex masses
+        (3.0, c(1.0, -0.1), c(0.3, -0.05)),
+        (100.0, c(1.0, -1.0), zero),
+        (1.0, c(1.0, -0.1), c(1.0, -0.1)),
+        (0.0, c(1.0, -0.1), c(2.0, -0.3)),
+    ];
+    for (p, m1, m2) in cases {
+        assert_agrees(native::two_point(p, m1, m2), OneLoop.two_point(p, m1, m2));
+    }
+}
+
+#[test]
+fn test_native_3_point() {
+    let c = Complex64::new;
+    let zero = c(0.0, 0.0);
+    let cases = [
+        ([-1.0, -2.0, -3.0], [zero, zero, zero]),
+        ([1.0, 2.0, 3.0], [zero, zero, c(0.5, 0.0)]),
+        ([1.0, 2.5, 1.5], [zero, c(1.0, 0.0), c(1.0, 0.0)]),
+        ([1.0, 1.0, 5.0], [c(1.0, 0.0); 3]),
+        ([1.0, 2.0, 3.0], [c(1.0, 0.0); 3]),
+        ([0.0, 3.0, 9.0], [c(1.0, -0.1), c(2.0, -0.3), c(0.5, -0.05)]),
+        ([10.0, 2.0, 30.0], [zero, c(2.0, -0.3), c(0.5, -0.05)]),
+    ];
+    for (p, m) in cases {
+        let rust = native::three_point(p[0], p[1], p[2], m[0], m[1], m[2]).unwrap();
+        assert_agrees_within(rust, OneLoop.three_point(p[0], p[1], p[2], m[0], m[1], m[2]), 1e-12);
+    }
+
+    // Divergent triangles are left to the Fortran
+    let m = c(1.0, 0.0);
+    assert!(native::three_point(1.0, 2.5, 1.0, zero, m, m).is_none());
+    assert!(native::three_point(0.0, 0.0, 2.0, zero, zero, zero).is_none());
+}
+
+#[test]
+fn test_native_3_point_random() {
+    let mut random = Random(0x2545f4914f6cdd1d);
+    for _ in 0..500 {
+        let p = [(); 3].map(|_| 20.0 * random.next() - 10.0);
+        let m = [(); 3].map(|_| {
+            if random.next() < 0.25 {
+                return Complex64::new(0.0, 0.0);
+            }
+            let width = if random.next() < 0.5 { 0.5 * random.next() } else { 0.0 };
+            Complex64::new(5.0 * random.next(), -width)
+        });
+        let rust = native::three_point(p[0], p[1], p[2], m[0], m[1], m[2]).unwrap();
+        assert_agrees_within(rust, OneLoop.three_point(p[0], p[1], p[2], m[0], m[1], m[2]), 1e-10);
+    }
+}
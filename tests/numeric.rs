@@ -0,0 +1,61 @@
+use num_complex::{Complex64, ComplexFloat};
+use oneloop_bridge::numeric::{self, Integral, MAX_EVALUATIONS};
+use oneloop_bridge::{OLOResult, three_point};
+#[cfg(oneloop_linked)]
+use oneloop_bridge::four_point;
+
+/// Checks `numeric` against OneLOop within its own error estimate.
+fn assert_agrees(integral: Integral, expected: OLOResult, tolerance: f64) {
+    let value = integral.value.epsilon_0();
+    println!("{} ± {:e} ({} evaluations)\n{}", value, integral.error, integral.evaluations, expected.epsilon_0());
+    assert!(integral.error <= tolerance * value.abs() || integral.evaluations >= MAX_EVALUATIONS - 1000);
+    assert!((value - expected.epsilon_0()).abs() <= 10.0 * integral.error.max(1e-12));
+    assert_eq!(integral.value.epsilon_minus_1(), Complex64::new(0.0, 0.0));
+    assert_eq!(integral.value.epsilon_minus_2(), Complex64::new(0.0, 0.0));
+}
+
+#[test]
+fn test_numeric_three_point() {
+    let c = Complex64::new;
+    let cases = [
+        // Euclidean, without deformation
+        (-1.0, -2.0, -3.0, c(0.5, 0.0), c(1.0, 0.0), c(1.5, 0.0)),
+        // Above thresholds
+        (1.0, 2.0, 5.0, c(0.5, 0.0), c(1.0, 0.0), c(1.5, 0.0)),
+        (10.0, 2.0, 30.0, c(0.1, 0.0), c(0.3, 0.0), c(0.2, 0.0)),
+        // Complex mass
+        (5.0, 1.0, 2.0, c(1.0, -0.1), c(0.5, 0.0), c(0.2, 0.0)),
+    ];
+    for (p1, p2, p3, m1, m2, m3) in cases {
+        let integral = numeric::three_point(p1, p2, p3, m1, m2, m3, 1e-6).unwrap();
+        assert_agrees(integral, three_point(p1, p2, p3, m1, m2, m3), 1e-6);
+    }
+}
+
+#[cfg(oneloop_linked)]
+#[test]
+fn test_numeric_four_point() {
+    let c = Complex64::new;
+    let cases = [
+        ([-1.0, -2.0, -0.5, -1.5, -3.0, -2.5], [c(0.5, 0.0), c(1.0, 0.0), c(1.5, 0.0), c(0.7, 0.0)]),
+        ([1.0, 1.0, 1.0, 1.0, 10.0, -3.0], [c(0.5, 0.0); 4]),
+        ([0.1, 0.2, 0.3, 0.4, 8.0, -2.0], [c(1.0, -0.1), c(0.5, 0.0), c(0.2, 0.0), c(0.3, 0.0)]),
+    ];
+    for (p, m) in cases {
+        let integral = numeric::four_point(p[0], p[1], p[2], p[3], p[4], p[5], m[0], m[1], m[2], m[3], 1e-4).unwrap();
+        assert_agrees(integral, four_point(p[0], p[1], p[2], p[3], p[4], p[5], m[0], m[1], m[2], m[3]), 1e-4);
+    }
+
+    // At vanishing momenta D0 = 1/(6(m²)²), the volume of the simplex over (m²)²
+    let integral = numeric::four_point(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 2.0, 2.0, 2.0, 2.0, 1e-8).unwrap();
+    assert!((integral.value.epsilon_0() - 1.0 / 24.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_numeric_divergent() {
+    // IR divergent: massless triangle with on-shell legs and box with a soft exchange
+    assert!(numeric::three_point(0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1e-6).is_none());
+    assert!(numeric::four_point(0.0, 0.0, 0.0, 0.0, 1.0, -1.0, 0.0, 0.0, 0.0, 0.0, 1e-6).is_none());
+    // Singular at threshold
+    assert!(numeric::three_point(4.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1e-6).is_none());
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "oni")]
+use oneloop_bridge::oni;
+use std::sync::Mutex;
+#[cfg(oneloop_linked)]
+use {
+    num_complex::{Complex64, ComplexFloat},
+    oneloop_bridge::{OLOResult, four_point, one_point, three_point, two_point},
+};
+
+/// The maximal number of evaluations is global, so the tests must not run concurrently.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Asserts that all coefficients agree to `tolerance` relative to the largest one.
+#[cfg(oneloop_linked)]
+fn assert_agrees_within(oni: OLOResult, oneloop: OLOResult, tolerance: f64) {
+    println!("{}\n{}", oni, oneloop);
+    let scale = [oneloop.epsilon_0(), oneloop.epsilon_minus_1(), oneloop.epsilon_minus_2()]
+        .iter()
+        .map(|c| c.abs())
+        .fold(1e-300, f64::max);
+    let d = oni - oneloop;
+    for c in [d.epsilon_0(), d.epsilon_minus_1(), d.epsilon_minus_2()] {
+        assert!(c.abs() <= tolerance * scale);
+    }
+}
+
+#[cfg(oneloop_linked)]
+#[test]
+fn test_oni_against_oneloop() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let c = Complex64::new;
+    assert_agrees_within(oni::one_point(c(0.7, -0.1)), one_point(c(0.7, -0.1)), 1e-12);
+    assert_agrees_within(oni::two_point(3.0, 0.5, 0.2), two_point(3.0, 0.5, 0.2), 1e-8);
+    assert_agrees_within(oni::two_point(0.0, 0.0, 1.0), two_point(0.0, 0.0, 1.0), 1e-8);
+
+    let triangles = [
+        (-1.0, -2.0, -3.0, c(0.5, 0.0), c(1.0, 0.0), c(1.5, 0.0)),
+        (10.0, 2.0, 30.0, c(0.1, 0.0), c(0.3, 0.0), c(0.2, 0.0)),
+        // IR divergent
+        (0.0, 0.0, 1.0, c(0.0, 0.0), c(0.0, 0.0), c(0.0, 0.0)),
+    ];
+    for (p1, p2, p3, m1, m2, m3) in triangles {
+        assert_agrees_within(oni::three_point(p1, p2, p3, m1, m2, m3), three_point(p1, p2, p3, m1, m2, m3), 1e-5);
+    }
+
+    let boxes = [
+        ([-1.0, -2.0, -0.5, -1.5, -3.0, -2.5], [c(0.5, 0.0), c(1.0, 0.0), c(1.5, 0.0), c(0.7, 0.0)]),
+        ([1.0, 1.0, 1.0, 1.0, 10.0, -3.0], [c(0.5, 0.0); 4]),
+        // IR divergent
+        ([0.0, 0.0, 0.0, 0.0, 1.0, -1.0], [c(0.0, 0.0); 4]),
+    ];
+    for (p, m) in boxes {
+        assert_agrees_within(
+            oni::four_point(p[0], p[1], p[2], p[3], p[4], p[5], m[0], m[1], m[2], m[3]),
+            four_point(p[0], p[1], p[2], p[3], p[4], p[5], m[0], m[1], m[2], m[3]),
+            1e-4,
+        );
+    }
+}
+
+#[test]
+fn test_oni_maxeval() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let default = oni::maxeval();
+    assert_eq!(default, 1_000_000);
+    oni::set_maxeval(1000);
+    assert_eq!(oni::maxeval(), 1000);
+    // Still finite, only less precise
+    let r = oni::three_point(10.0, 2.0, 30.0, 0.1, 0.3, 0.2);
+    assert!(r.epsilon_0().is_finite());
+    oni::set_maxeval(default);
+}
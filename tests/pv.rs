@@ -0,0 +1,124 @@
+use num_complex::Complex64;
+use oneloop_bridge::pv::{self, PVError};
+use num_complex::ComplexFloat;
+use oneloop_bridge::{three_point, two_point};
+#[cfg(oneloop_linked)]
+use oneloop_bridge::four_point;
+
+#[test]
+fn test_pv_3_point_uv_poles() {
+    let m = Complex64::new(0.3, 0.0);
+    let c = pv::three_point(0.2, 0.5, 1.7, m, m, m, 3).unwrap();
+    println!("{:?}", c);
+
+    // Only C00 and C00i are UV divergent
+    for (indices, value) in c.iter() {
+        let expected = match indices {
+            [0, 0] => 0.25,
+            [0, 0, _] => -1.0 / 12.0,
+            _ => 0.0,
+        };
+        assert!((value.epsilon_minus_1() - expected).abs() < 1e-12, "{:?}", indices);
+        assert!(value.epsilon_minus_2().abs() < 1e-12, "{:?}", indices);
+    }
+}
+
+#[test]
+fn test_pv_3_point_metric_contraction() {
+    let (p1, p2, p3) = (0.2, 0.5, 1.7);
+    let m1 = Complex64::new(0.3, -0.01);
+    let m2 = Complex64::new(0.1, 0.0);
+    let m3 = Complex64::new(0.4, 0.0);
+    let c = pv::three_point(p1, p2, p3, m1, m2, m3, 2).unwrap();
+
+    // g_μν C^μν = (4 - 2ε) C00 + Σ_ij P_i·P_j C_ij = B0(p2, m2, m3) + m1 C0
+    let c00 = c.get(&[0, 0]).unwrap();
+    let lhs = c00.epsilon_0() * 4.0 - c00.epsilon_minus_1() * 2.0
+        + c.get(&[1, 1]).unwrap().epsilon_0() * p1
+        + c.get(&[2, 2]).unwrap().epsilon_0() * p3
+        + c.get(&[1, 2]).unwrap().epsilon_0() * (p1 + p3 - p2);
+    let rhs = two_point(p2, m2, m3).epsilon_0()
+        + m1 * three_point(p1, p2, p3, m1, m2, m3).epsilon_0();
+    println!("lhs: {}, rhs: {}", lhs, rhs);
+    assert!((lhs - rhs).abs() < 1e-10 * rhs.abs());
+}
+
+#[test]
+fn test_pv_3_point_small_gram_determinant() {
+    // k1 and k2 are collinear: k1² = k2² = 1, (k1 + k2)² = 4
+    let (p1, p2, p3) = (1.0, 1.0, 4.0);
+    let m = Complex64::new(0.3, 0.0);
+    let c = pv::three_point(p1, p2, p3, m, m, m, 2).unwrap();
+
+    // The Gram expansion must still satisfy the metric contraction
+    let c00 = c.get(&[0, 0]).unwrap();
+    let lhs = c00.epsilon_0() * 4.0 - c00.epsilon_minus_1() * 2.0
+        + c.get(&[1, 1]).unwrap().epsilon_0() * p1
+        + c.get(&[2, 2]).unwrap().epsilon_0() * p3
+        + c.get(&[1, 2]).unwrap().epsilon_0() * (p1 + p3 - p2);
+    let rhs = two_point(p2, m, m).epsilon_0() + m * three_point(p1, p2, p3, m, m, m).epsilon_0();
+    println!("lhs: {}, rhs: {}", lhs, rhs);
+    assert!((lhs - rhs).abs() < 1e-10 * rhs.abs());
+}
+
+#[test]
+fn test_pv_3_point_degenerate_kinematics() {
+    // Collinear momenta where the Gram expansion does not apply either
+    let zero = Complex64::new(0.0, 0.0);
+    let r = pv::three_point(1.0, 1.0, 4.0, Complex64::new(2.0, 0.0), zero, zero, 1);
+    println!("{:?}", r);
+    assert!(matches!(r, Err(PVError::SmallGramDeterminant { .. })));
+}
+
+#[test]
+fn test_pv_3_point_unsupported_rank() {
+    let m = Complex64::new(0.3, 0.0);
+    let r = pv::three_point(0.2, 0.5, 1.7, m, m, m, 4);
+    assert_eq!(r.unwrap_err(), PVError::UnsupportedRank { rank: 4, max_rank: 3 });
+}
+
+#[cfg(oneloop_linked)]
+#[test]
+fn test_pv_4_point_uv_poles() {
+    let m = Complex64::new(0.3, 0.0);
+    let d = pv::four_point(0.2, 0.5, 1.7, 0.4, 2.5, -1.1, m, m, m, m, 4).unwrap();
+
+    // Only D0000 is UV divergent
+    for (indices, value) in d.iter() {
+        let expected = if indices == [0, 0, 0, 0] { 1.0 / 24.0 } else { 0.0 };
+        assert!((value.epsilon_minus_1() - expected).abs() < 1e-12, "{:?}", indices);
+        assert!(value.epsilon_minus_2().abs() < 1e-12, "{:?}", indices);
+    }
+}
+
+#[cfg(oneloop_linked)]
+#[test]
+fn test_pv_4_point_metric_contraction() {
+    let (p1, p2, p3, p4, p12, p23) = (0.2, 0.5, 1.7, 0.4, 2.5, -1.1);
+    let m1 = Complex64::new(0.3, -0.01);
+    let m2 = Complex64::new(0.1, 0.0);
+    let m3 = Complex64::new(0.4, 0.0);
+    let m4 = Complex64::new(0.2, 0.0);
+    let d = pv::four_point(p1, p2, p3, p4, p12, p23, m1, m2, m3, m4, 2).unwrap();
+
+    // g_μν D^μν = D D00 + Σ_ij P_i·P_j D_ij = C0(p2, p3, p23, m2, m3, m4) + m1 D0
+    let dij = |i: usize, j: usize| d.get(&[i, j]).unwrap().epsilon_0();
+    let lhs = d.get(&[0, 0]).unwrap().epsilon_0() * 4.0
+        + dij(1, 1) * p1
+        + dij(2, 2) * p12
+        + dij(3, 3) * p4
+        + dij(1, 2) * (p1 + p12 - p2)
+        + dij(1, 3) * (p1 + p4 - p23)
+        + dij(2, 3) * (p12 + p4 - p3);
+    let rhs = three_point(p2, p3, p23, m2, m3, m4).epsilon_0()
+        + m1 * four_point(p1, p2, p3, p4, p12, p23, m1, m2, m3, m4).epsilon_0();
+    println!("lhs: {}, rhs: {}", lhs, rhs);
+    assert!((lhs - rhs).abs() < 1e-10 * rhs.abs());
+}
+
+#[test]
+fn test_pv_4_point_unsupported_rank() {
+    let m = Complex64::new(0.3, 0.0);
+    let r = pv::four_point(0.2, 0.5, 1.7, 0.4, 2.5, -1.1, m, m, m, m, 5);
+    assert_eq!(r.unwrap_err(), PVError::UnsupportedRank { rank: 5, max_rank: 4 });
+}
@@ -0,0 +1,129 @@
+use num_complex::{Complex64, ComplexFloat};
+use oneloop_bridge::qmplx::{self, bnlog, dilog, dilog2, li2, olog, olog1, qonv};
+use std::f64::consts::PI;
+
+const CATALAN: f64 = 0.915_965_594_177_219;
+
+fn assert_close(a: Complex64, b: Complex64, tolerance: f64) {
+    println!("{}\n{}", a, b);
+    assert!((a - b).abs() <= tolerance * b.abs().max(1.0));
+}
+
+/// `(n+1)∫₀¹ dt tⁿ ln(1 - t/x)` by Simpson's rule, for `x` away from `[0, 1]`.
+fn bnlog_quadrature(n: usize, x: Complex64) -> Complex64 {
+    let steps = 4000;
+    let f = |t: f64| t.powi(n as i32) * (1.0 - t / x).ln();
+    let h = 1.0 / steps as f64;
+    let mut sum = f(0.0) + f(1.0);
+    for i in 1..steps {
+        sum += f(i as f64 * h) * if i % 2 == 1 { 4.0 } else { 2.0 };
+    }
+    (n + 1) as f64 * sum * h / 3.0
+}
+
+/// `-(n+1) Σ_k x^(-k)/(k (n+k+1))`, for large `|x|`.
+fn bnlog_expansion(n: usize, x: Complex64) -> Complex64 {
+    (1..60)
+        .map(|k| -((n + 1) as f64) / (k * (n + k + 1)) as f64 / x.powi(k as i32))
+        .sum()
+}
+
+#[test]
+fn test_olog() {
+    let c = Complex64::new;
+    for x in [c(2.0, 0.0), c(0.3, 1.5), c(1.0, -1e-3), c(1.0 + 1e-9, 0.0)] {
+        for n in [-2, -1, 0, 1, 3] {
+            assert_close(olog(x, n), x.ln() + c(0.0, PI * n as f64), 1e-15);
+        }
+    }
+
+    // The sheet of a negative real argument
+    assert_close(olog(c(-2.0, 0.0), 0), c(2f64.ln(), PI), 1e-15);
+    assert_close(olog(c(2.0, 0.0), -1), c(2f64.ln(), -PI), 1e-15);
+    assert_eq!(qonv(c(-2.0, 0.0), -1.0).p, -1);
+    assert_close(qonv(c(-2.0, 0.0), -1.0).ln(), c(2f64.ln(), -PI), 1e-15);
+
+    // ln(x)/(x - 1) close to one
+    assert_close(olog1(c(1.0, 0.0), 0), c(1.0, 0.0), 1e-15);
+    assert_close(olog1(c(1.0, 1e-9), 0), c(1.0, -5e-10), 1e-15);
+    assert_close(olog1(c(1.0 + 1e-12, 0.0), 0), c(1.0 - 5e-13, 0.0), 1e-15);
+}
+
+#[test]
+fn test_dilog() {
+    let c = Complex64::new;
+    let zeta2 = PI * PI / 6.0;
+    let ln2 = 2f64.ln();
+
+    // Li₂(1 - x) at known points
+    assert_close(dilog(c(0.0, 0.0), 0), c(zeta2, 0.0), 1e-15);
+    assert_close(dilog(c(1.0, 0.0), 0), c(0.0, 0.0), 1e-15);
+    assert_close(dilog(c(2.0, 0.0), 0), c(-zeta2 / 2.0, 0.0), 1e-15);
+    assert_close(dilog(c(0.5, 0.0), 0), c(zeta2 / 2.0 - ln2 * ln2 / 2.0, 0.0), 1e-15);
+    assert_close(li2(c(0.0, 1.0)), c(-zeta2 / 8.0, CATALAN), 1e-15);
+
+    // Below the cut
+    assert_close(li2(c(2.0, 0.0)), c(PI * PI / 4.0, -PI * ln2), 1e-15);
+    assert_close(li2(c(2.0, -1e-8)), c(PI * PI / 4.0, -PI * ln2), 1e-7);
+
+    // The power series inside the unit disk, and the reflection formula
+    for z in [c(0.3, 0.2), c(-0.4, 0.1), c(0.05, -0.45)] {
+        let series: Complex64 = (1..200).map(|k| z.powi(k) / (k * k) as f64).sum();
+        assert_close(li2(z), series, 1e-15);
+        let reflection = zeta2 - z.ln() * (1.0 - z).ln();
+        assert_close(li2(z) + li2(1.0 - z), reflection, 1e-14);
+    }
+
+    // Going once around x = 0, but not around x = 1, adds -2πi ln(1 - x)
+    for x in [c(0.3, 0.2), c(0.2, -0.6), c(0.7, 0.0), c(-0.5, 0.1)] {
+        for n in [-2, 0, 2] {
+            let shift = c(0.0, -2.0 * PI) * (1.0 - x).ln();
+            assert_close(dilog(x, n + 2), dilog(x, n) + shift, 1e-14);
+        }
+    }
+    assert_close(qonv(c(0.4, 0.3), 1.0).li2(), dilog(c(0.4, 0.3), 0), 1e-15);
+}
+
+#[test]
+fn test_dilog2() {
+    let c = Complex64::new;
+    for (x1, x2) in [(c(0.3, 0.2), c(2.0, -1.0)), (c(0.5, 0.0), c(3.0, 0.0)), (c(1.5, 0.5), c(0.2, -0.1))] {
+        for (n1, n2) in [(0, 0), (2, 2), (0, 2)] {
+            let y1 = x1 * if n1 % 2 == 0 { 1.0 } else { -1.0 };
+            let y2 = x2 * if n2 % 2 == 0 { 1.0 } else { -1.0 };
+            let quotient = (dilog(x1, n1) - dilog(x2, n2)) / (y1 - y2);
+            assert_close(dilog2(x1, n1, x2, n2), quotient, 1e-14);
+        }
+    }
+
+    // Nearby arguments give the derivative -ln(x)/(x - 1)
+    for x in [c(0.3, 0.2), c(2.0, -1.0), c(0.5, 0.0), c(1.1, 0.0)] {
+        for n in [0, 2] {
+            let derivative = -olog1(x, 0) - c(0.0, PI * n as f64) / (x - 1.0);
+            assert_close(dilog2(x, n, x * (1.0 + 1e-10), n), derivative, 1e-9);
+        }
+    }
+    assert_close(dilog2(c(1.0, 0.0), 0, c(1.0, 0.0), 0), c(-1.0, 0.0), 1e-15);
+}
+
+#[test]
+fn test_bnlog() {
+    let c = Complex64::new;
+    for n in 0..=qmplx::BNLOG_MAX_RANK {
+        // Closed form and series against the integral
+        for x in [c(0.5, 0.5), c(-0.3, -0.1), c(2.0, 1.0), c(-1.5, 0.0), c(3.0, 0.0), c(1.0, 0.2)] {
+            assert_close(bnlog(n, x), bnlog_quadrature(n, x), 1e-12);
+        }
+        for x in [c(1e4, 0.0), c(-50.0, 3.0), c(20.0, -40.0), c(-8.0, 0.0)] {
+            assert_close(bnlog(n, x), bnlog_expansion(n, x), 1e-14);
+        }
+
+        // On the cut the sides differ by 2πi(1 - x^(n+1))
+        let x = 0.4;
+        let discontinuity = bnlog(n, c(x, 0.0)) - bnlog(n, c(x, -1e-300));
+        assert_close(discontinuity, c(0.0, 2.0 * PI * (1.0 - x.powi(n as i32 + 1))), 1e-15);
+
+        let harmonic: f64 = (1..=n + 1).map(|i| 1.0 / i as f64).sum();
+        assert_close(bnlog(n, c(1.0, 0.0)), c(-harmonic, 0.0), 1e-15);
+    }
+}
@@ -0,0 +1,158 @@
+use num_complex::Complex64;
+#[cfg(oneloop_linked)]
+use {num_complex::ComplexFloat, oneloop_bridge::OLOResult, std::fmt::Write};
+use oneloop_bridge::dispatch::{self, Routine};
+use oneloop_bridge::input::{self, Point};
+use oneloop_bridge::set_renormalization_scale;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+const FILES: [&str; 2] = ["input", "irdiv_input"];
+const REFERENCE: &str = "tests/data/oneloop_reference.txt";
+
+/// The dispatch depends on the renormalization scale, which the tests set for
+/// every point, so they must not run concurrently.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// The IR-divergent triangles and boxes of OneLOop.
+const DIVERGENT: [Routine; 22] = [
+    Routine::Tria0 { massive_legs: 1 },
+    Routine::Tria0 { massive_legs: 2 },
+    Routine::Tria1,
+    Routine::Tria2,
+    Routine::Tria3,
+    Routine::Tria4,
+    Routine::Box00 { massive_legs: 0 },
+    Routine::Box00 { massive_legs: 1 },
+    Routine::Box00 { massive_legs: 2 },
+    Routine::Box03,
+    Routine::Box05,
+    Routine::Box06,
+    Routine::Box07,
+    Routine::Box08,
+    Routine::Box09,
+    Routine::Box10,
+    Routine::Box11,
+    Routine::Box12,
+    Routine::Box13,
+    Routine::Box14,
+    Routine::Box15,
+    Routine::Box16,
+];
+
+/// The points of `file` in `oneloop/example`.
+fn points(file: &str) -> Vec<Point> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("oneloop/example").join(file);
+    input::parse(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+/// The stored results by file and index, with their tolerance.
+fn references() -> HashMap<(String, usize), ([Complex64; 3], f64)> {
+    let text = std::fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join(REFERENCE)).unwrap();
+    let mut references = HashMap::new();
+    for line in text.lines().filter(|l| !l.starts_with('#') && !l.trim().is_empty()) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let x: Vec<f64> = fields[2..].iter().map(|f| f.parse().unwrap()).collect();
+        let values = [0, 2, 4].map(|i| Complex64::new(x[i], x[i + 1]));
+        references.insert((fields[0].to_string(), fields[1].parse().unwrap()), (values, x[6]));
+    }
+    references
+}
+
+#[cfg(oneloop_linked)]
+fn coefficients(r: OLOResult) -> [Complex64; 3] {
+    [r.epsilon_0(), r.epsilon_minus_1(), r.epsilon_minus_2()]
+}
+
+/// The routine OneLOop dispatches a triangle or box to.
+fn routine(point: &Point) -> Option<Routine> {
+    let (p, m) = (&point.momenta, &point.masses);
+    match point.legs {
+        3 => dispatch::three_point(p[0], p[1], p[2], m[0], m[1], m[2]),
+        4 => dispatch::four_point(p[0], p[1], p[2], p[3], p[4], p[5], m[0], m[1], m[2], m[3]),
+        _ => None,
+    }
+    .map(|d| d.routine)
+}
+
+#[test]
+fn test_regression_reference_coverage() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let references = references();
+    let mut routines = Vec::new();
+    for file in FILES {
+        let points = points(file);
+        for index in references.keys().filter(|(f, _)| f == file).map(|(_, i)| *i) {
+            let point = points.get(index).unwrap_or_else(|| panic!("no point {} {}", file, index));
+            set_renormalization_scale(point.mu);
+            routines.extend(routine(point));
+        }
+    }
+    set_renormalization_scale(1.0);
+
+    // Every IR-divergent triangle and box of OneLOop has a reference result
+    for routine in DIVERGENT {
+        assert!(routines.contains(&routine), "{:?} not covered by {}", routine, REFERENCE);
+    }
+}
+
+#[cfg(oneloop_linked)]
+#[test]
+fn test_regression_oneloop_examples() {
+    let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let references = references();
+    let bless = std::env::var_os("OLO_BLESS").is_some();
+    let mut blessed = String::new();
+    let mut missing = Vec::new();
+    let mut routines = Vec::new();
+
+    for file in FILES {
+        for (index, point) in points(file).iter().enumerate() {
+            set_renormalization_scale(point.mu);
+            let result = coefficients(point.evaluate());
+            println!("{} {} (line {}): {:?}", file, index, point.line, result);
+            assert!(result.iter().all(|c| c.re.is_finite() && c.im.is_finite()));
+
+            // No poles beyond the order of the routine
+            if let Some(routine) = routine(point) {
+                routines.push(routine);
+                for (order, c) in result.iter().enumerate().skip(routine.pole_order() + 1) {
+                    assert_eq!(*c, Complex64::new(0.0, 0.0), "pole of order {} for {:?}", order, routine);
+                }
+            }
+
+            match references.get(&(file.to_string(), index)) {
+                Some((expected, tolerance)) => {
+                    let scale = expected.iter().map(|c| c.abs()).fold(f64::MIN_POSITIVE, f64::max);
+                    for (a, b) in result.iter().zip(expected) {
+                        assert!((a - b).abs() <= tolerance * scale, "{} {}: {} != {}", file, index, a, b);
+                    }
+                }
+                None => missing.push(format!("{} {}", file, index)),
+            }
+            let [c0, c1, c2] = result;
+            writeln!(
+                blessed,
+                "{} {} {:?} {:?} {:?} {:?} {:?} {:?} 1e-12",
+                file, index, c0.re, c0.im, c1.re, c1.im, c2.re, c2.im
+            )
+            .unwrap();
+        }
+    }
+    set_renormalization_scale(1.0);
+
+    // Every IR-divergent triangle and box of OneLOop is exercised
+    for routine in DIVERGENT {
+        assert!(routines.contains(&routine), "{:?} not exercised", routine);
+    }
+
+    if bless {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(REFERENCE);
+        let text = std::fs::read_to_string(&path).unwrap();
+        let header: String = text.lines().take_while(|l| l.starts_with('#')).map(|l| format!("{}\n", l)).collect();
+        std::fs::write(path, header + &blessed).unwrap();
+    } else {
+        assert!(missing.is_empty(), "no reference recorded for: {}", missing.join(", "));
+    }
+}
@@ -0,0 +1,135 @@
+use num_complex::Complex64;
+use oneloop_bridge::derivative::Variable;
+use oneloop_bridge::scan::{self, Range, ScanError};
+use oneloop_bridge::{input::Point, two_point};
+#[cfg(oneloop_linked)]
+use oneloop_bridge::{four_point, scan::Spacing};
+
+#[test]
+fn test_range_values() {
+    let p = Variable::Momentum(0);
+    assert_eq!(Range::linear(p, 0.0, 1.0, 5).values(), vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+    assert_eq!(Range::linear(p, 2.0, 3.0, 1).values(), vec![2.0]);
+    let values = Range::logarithmic(p, -1e-2, -1e2, 5).values();
+    println!("{:?}", values);
+    for (value, expected) in values.iter().zip([-1e-2, -1e-1, -1.0, -1e1, -1e2]) {
+        assert!((value - expected).abs() <= 1e-14 * expected.abs());
+    }
+    assert_eq!((values[0], values[4]), (-1e-2, -1e2));
+}
+
+#[test]
+fn test_scan_two_point() {
+    // B0 across the threshold at p = (m1 + m2)² = 4
+    let (m1, m2) = (Complex64::new(1.0, 0.0), Complex64::new(1.0, -0.1));
+    let scan = scan::two_point(0.0, m1, m2, &[Range::linear(Variable::Momentum(0), 3.0, 5.0, 21)]);
+    assert_eq!(scan.samples.len(), 21);
+    assert_eq!(scan.variables, vec![Variable::Momentum(0)]);
+    for sample in &scan.samples {
+        assert_eq!(sample.momenta, sample.values);
+        assert_eq!(sample.result, two_point(sample.values[0], m1, m2));
+    }
+
+    // A scanned mass keeps its width
+    let scan = scan::two_point(1.0, m1, m2, &[Range::logarithmic(Variable::Mass(1), 0.1, 10.0, 3)]);
+    let masses: Vec<Complex64> = scan.samples.iter().map(|s| s.masses[1]).collect();
+    assert_eq!(masses, vec![Complex64::new(0.1, -0.1), Complex64::new(1.0, -0.1), Complex64::new(10.0, -0.1)]);
+
+    // Without ranges, the base point
+    let scan = scan::two_point(1.0, m1, m2, &[]);
+    assert_eq!(scan.samples.len(), 1);
+    assert_eq!(scan.samples[0].result, two_point(1.0, m1, m2));
+}
+
+#[cfg(oneloop_linked)]
+#[test]
+fn test_scan_grid() {
+    let m = Complex64::new(0.5, 0.0);
+    let s = Range::linear(Variable::Momentum(4), 1.0, 3.0, 3);
+    let t = Range { variable: Variable::Momentum(5), start: -1.0, end: -2.0, points: 2, spacing: Spacing::Linear };
+    let scan = scan::four_point(0.1, 0.2, 0.3, 0.4, 0.0, 0.0, m, m, m, m, &[s, t]);
+    let values: Vec<Vec<f64>> = scan.samples.iter().map(|s| s.values.clone()).collect();
+    assert_eq!(values, vec![vec![1.0, -1.0], vec![1.0, -2.0], vec![2.0, -1.0], vec![2.0, -2.0], vec![3.0, -1.0], vec![3.0, -2.0]]);
+    for sample in &scan.samples {
+        let [s, t] = [sample.values[0], sample.values[1]];
+        assert_eq!(sample.momenta, vec![0.1, 0.2, 0.3, 0.4, s, t]);
+        assert_eq!(sample.result, four_point(0.1, 0.2, 0.3, 0.4, s, t, m, m, m, m));
+    }
+}
+
+#[test]
+fn test_scan_validate() {
+    let point = Point { legs: 2, mu: 1.0, momenta: vec![1.0], masses: vec![Complex64::new(1.0, 0.0); 2], line: 0 };
+    let p = Variable::Momentum(0);
+    let name = || "p".to_string();
+    assert_eq!(scan::validate(&point, &[Range::linear(p, 0.0, 1.0, 2)]), Ok(()));
+    assert_eq!(
+        scan::validate(&point, &[Range::linear(Variable::Mass(2), 0.0, 1.0, 2)]),
+        Err(ScanError::UnknownVariable { name: "Mass(2)".to_string() })
+    );
+    assert_eq!(
+        scan::validate(&point, &[Range::linear(p, 0.0, 1.0, 2), Range::linear(p, 1.0, 2.0, 2)]),
+        Err(ScanError::ScannedTwice { name: name() })
+    );
+    assert_eq!(scan::validate(&point, &[Range::linear(p, 0.0, 1.0, 0)]), Err(ScanError::NoPoints { name: name() }));
+    assert_eq!(
+        scan::validate(&point, &[Range::logarithmic(p, -1.0, 1.0, 2)]),
+        Err(ScanError::LogarithmicBounds { name: name(), start: -1.0, end: 1.0 })
+    );
+}
+
+#[test]
+#[should_panic(expected = "m2 is scanned twice")]
+fn test_scan_panics() {
+    let m = Range::linear(Variable::Mass(1), 1.0, 2.0, 2);
+    scan::three_point(1.0, 2.0, 3.0, 1.0, 1.0, 1.0, &[m, m]);
+}
+
+#[test]
+fn test_variable_names() {
+    assert_eq!(scan::variable_name(1, Variable::Mass(0)), "m");
+    assert_eq!(scan::variable_name(2, Variable::Momentum(0)), "p");
+    assert_eq!(scan::variable_name(4, Variable::Momentum(5)), "p23");
+    assert_eq!(scan::variable_name(4, Variable::Mass(3)), "m4");
+    for legs in 1..=4 {
+        for variable in (0..[0, 1, 3, 6][legs - 1]).map(Variable::Momentum).chain((0..legs).map(Variable::Mass)) {
+            assert_eq!(scan::variable_by_name(legs, &scan::variable_name(legs, variable)), Some(variable));
+        }
+    }
+    assert_eq!(scan::variable_by_name(2, "m3"), None);
+    assert_eq!(scan::variable_by_name(1, "m1"), None);
+    assert_eq!(scan::variable_by_name(5, "p"), None);
+}
+
+#[test]
+fn test_scan_output() {
+    let scan = scan::two_point(1.0, Complex64::new(0.5, -0.25), 0.0, &[Range::linear(Variable::Momentum(0), -1.0, 1.0, 3)]);
+    let mut csv = Vec::new();
+    scan.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    println!("{}", csv);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "p,m1_re,m1_im,m2_re,m2_im,epsilon_0_re,epsilon_0_im,epsilon_minus_1_re,epsilon_minus_1_im,epsilon_minus_2_re,epsilon_minus_2_im"
+    );
+    assert_eq!(lines.len(), 4);
+    for (line, sample) in lines[1..].iter().zip(&scan.samples) {
+        let fields: Vec<f64> = line.split(',').map(|f| f.parse().unwrap()).collect();
+        assert_eq!(fields.len(), 11);
+        assert_eq!(fields[..5], [sample.momenta[0], 0.5, -0.25, 0.0, 0.0]);
+        // Written exactly
+        assert_eq!(fields[5], sample.result.epsilon_0().re);
+        assert_eq!(fields[6], sample.result.epsilon_0().im);
+        assert_eq!(fields[7..], [1.0, 0.0, 0.0, 0.0]);
+    }
+
+    let mut json = Vec::new();
+    scan.write_json_lines(&mut json).unwrap();
+    let json = String::from_utf8(json).unwrap();
+    println!("{}", json);
+    let lines: Vec<&str> = json.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with(r#"{"function": "B0", "p": -1e0, "m1": [5e-1, -2.5e-1], "m2": [0e0, 0e0], "scale": "#));
+    assert!(lines[2].ends_with(r#""epsilon_minus_1": [1e0, 0e0], "epsilon_minus_2": [0e0, 0e0]}"#));
+}
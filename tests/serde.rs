@@ -0,0 +1,80 @@
+#![cfg(feature = "serde")]
+use num_complex::Complex64;
+use oneloop_bridge::derivative::Variable;
+use oneloop_bridge::input::Point;
+use oneloop_bridge::mass::Mass;
+use oneloop_bridge::scan::{Range, Spacing};
+use oneloop_bridge::{OLOResult, OLOUnit, Settings};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+
+/// Checks the JSON of `value` and that it reads back as `value`.
+fn assert_round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: &T, json: &str) {
+    let written = serde_json::to_string(value).unwrap();
+    println!("{}", written);
+    assert_eq!(written, json);
+    assert_eq!(&serde_json::from_str::<T>(&written).unwrap(), value);
+}
+
+#[test]
+fn test_serde_result() {
+    let c = Complex64::new;
+    let result = OLOResult::new(c(1.5, -0.25), c(1.0, 0.0), c(0.0, 0.0));
+    let coefficients = (result.epsilon_0(), result.epsilon_minus_1(), result.epsilon_minus_2());
+    assert_eq!(coefficients, (c(1.5, -0.25), c(1.0, 0.0), c(0.0, 0.0)));
+    assert_round_trip(
+        &result,
+        r#"{"epsilon_0":[1.5,-0.25],"epsilon_minus_1":[1.0,0.0],"epsilon_minus_2":[0.0,0.0]}"#,
+    );
+
+    // Exact for any finite value
+    let result = OLOResult::new(c(0.1 + 0.2, -1e-300), c(std::f64::consts::PI, 1.0 / 3.0), c(-0.0, 6.02e23));
+    let written = serde_json::to_string(&result).unwrap();
+    assert_eq!(serde_json::from_str::<OLOResult>(&written).unwrap(), result);
+
+    // All fields are required, and no others allowed
+    assert!(serde_json::from_str::<OLOResult>(r#"{"epsilon_0":[1.0,0.0],"epsilon_minus_1":[0.0,0.0]}"#).is_err());
+    let extra = r#"{"epsilon_0":[1,0],"epsilon_minus_1":[0,0],"epsilon_minus_2":[0,0],"epsilon_1":[0,0]}"#;
+    assert!(serde_json::from_str::<OLOResult>(extra).is_err());
+}
+
+#[test]
+fn test_serde_settings() {
+    assert_round_trip(&OLOUnit::Warning, r#""Warning""#);
+    let names: Vec<String> = [OLOUnit::PrintAll, OLOUnit::Message, OLOUnit::Warning, OLOUnit::Error]
+        .iter()
+        .map(|u| serde_json::to_string(u).unwrap())
+        .collect();
+    assert_eq!(names, [r#""PrintAll""#, r#""Message""#, r#""Warning""#, r#""Error""#]);
+
+    let settings = Settings { renormalization_scale: 91.1876, onshell_threshold: Some(1e-10) };
+    assert_round_trip(&settings, r#"{"renormalization_scale":91.1876,"onshell_threshold":1e-10}"#);
+    let settings = Settings { renormalization_scale: 1.0, onshell_threshold: None };
+    assert_round_trip(&settings, r#"{"renormalization_scale":1.0,"onshell_threshold":null}"#);
+}
+
+#[test]
+fn test_serde_inputs() {
+    let mass = Mass::with_width(2.0, 0.5).unwrap();
+    assert_round_trip(&mass, "[4.0,-1.0]");
+    // Checked as squared masses
+    assert!(serde_json::from_str::<Mass>("[4.0,1.0]").is_err());
+
+    let point = Point {
+        legs: 2,
+        mu: 0.5,
+        momenta: vec![1.5],
+        masses: vec![Complex64::new(1.0, -0.1), Complex64::new(0.0, 0.0)],
+        line: 3,
+    };
+    assert_round_trip(&point, r#"{"legs":2,"mu":0.5,"momenta":[1.5],"masses":[[1.0,-0.1],[0.0,0.0]],"line":3}"#);
+
+    assert_round_trip(&Variable::Momentum(4), r#"{"Momentum":4}"#);
+    assert_round_trip(&Variable::Mass(1), r#"{"Mass":1}"#);
+    assert_round_trip(&Spacing::Logarithmic, r#""Logarithmic""#);
+    assert_round_trip(
+        &Range::linear(Variable::Mass(0), 0.5, 2.0, 11),
+        r#"{"variable":{"Mass":0},"start":0.5,"end":2.0,"points":11,"spacing":"Linear"}"#,
+    );
+}
@@ -0,0 +1,55 @@
+use num_complex::Complex64;
+use oneloop_bridge::snap;
+#[cfg(oneloop_linked)]
+use {
+    num_complex::ComplexFloat,
+    oneloop_bridge::snap::Snap,
+    oneloop_bridge::{four_point, three_point},
+};
+
+#[cfg(oneloop_linked)]
+#[test]
+fn test_snap_3_point_soft() {
+    let zero = Complex64::new(0.0, 0.0);
+    let m = Complex64::new(1.0, 0.0);
+
+    // Photon exchange between two legs that are on shell up to rounding
+    let (r, snaps) = snap::three_point(1.0 + 1e-12, 2.5, 1.0 - 1e-12, zero, m, m, 1e-10);
+    println!("{}\n{:?}", r, snaps);
+    assert_eq!(
+        snaps,
+        vec![Snap::Leg { momentum: 0, mass: 1 }, Snap::Leg { momentum: 2, mass: 2 }]
+    );
+    let exact = three_point(1.0, 2.5, 1.0, zero, m, m);
+    assert!((r.epsilon_minus_1() - exact.epsilon_minus_1()).abs() < 1e-15);
+    assert!(r.epsilon_minus_1().abs() > 0.0);
+
+    // Nothing to snap within a tighter tolerance
+    let (_, snaps) = snap::three_point(1.0 + 1e-12, 2.5, 1.0 - 1e-12, zero, m, m, 1e-14);
+    assert!(snaps.is_empty());
+}
+
+#[cfg(oneloop_linked)]
+#[test]
+fn test_snap_4_point_massless() {
+    let zero = Complex64::new(0.0, 0.0);
+    let tiny = Complex64::new(1e-13, 0.0);
+    let (r, snaps) = snap::four_point(1e-13, 0.0, -1e-13, 0.0, -3.0, -2.0, tiny, zero, zero, zero, 1e-10);
+    println!("{}\n{:?}", r, snaps);
+    assert_eq!(snaps, vec![Snap::Mass(0), Snap::Momentum(0), Snap::Momentum(2)]);
+
+    // The massless box with light-like legs: ε⁻² coefficient 4/(st)
+    let exact = four_point(0.0, 0.0, 0.0, 0.0, -3.0, -2.0, zero, zero, zero, zero);
+    assert!((r.epsilon_minus_2() - 4.0 / 6.0).abs() < 1e-14);
+    assert!((r.epsilon_0() - exact.epsilon_0()).abs() < 1e-14);
+}
+
+#[test]
+fn test_snap_complex_mass() {
+    let zero = Complex64::new(0.0, 0.0);
+    let m = Complex64::new(1.0, -1e-12);
+
+    // A width is not snapped away, and legs are not put on a complex mass
+    let (_, snaps) = snap::two_point(1.0, zero, m, 1e-10);
+    assert!(snaps.is_empty());
+}
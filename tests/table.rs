@@ -0,0 +1,126 @@
+use num_complex::Complex64;
+use oneloop_bridge::derivative::Variable;
+use oneloop_bridge::input::Point;
+use oneloop_bridge::scan::Range;
+use oneloop_bridge::table::{Grid, Table};
+use oneloop_bridge::{OLOResult, three_point, two_point};
+
+/// The largest coefficient of `a - b` relative to the largest of `b`.
+fn relative_error(a: OLOResult, b: OLOResult) -> f64 {
+    let largest = |r: OLOResult| {
+        [r.epsilon_0(), r.epsilon_minus_1(), r.epsilon_minus_2()].iter().map(|c| c.norm()).fold(0.0, f64::max)
+    };
+    largest(a - b) / largest(b)
+}
+
+fn bubble(p: f64, m1: f64, m2: f64) -> Point {
+    Point { legs: 2, mu: 1.0, momenta: vec![p], masses: vec![m1.into(), m2.into()], line: 0 }
+}
+
+/// `n` points spread irregularly over `[a, b]`.
+fn samples(a: f64, b: f64, n: usize) -> impl Iterator<Item = f64> {
+    (0..n).map(move |i| a + (b - a) * ((i as f64 * 0.618_033_988_749_894_9) % 1.0))
+}
+
+#[test]
+fn test_table_threshold() {
+    // B0(p, 1, 1) across the threshold at p = 4
+    let tolerance = 1e-8;
+    let table = Table::new(&bubble(0.0, 1.0, 1.0), Range::linear(Variable::Momentum(0), 0.5, 8.0, 9), tolerance);
+    println!("{} nodes, error {:e}, direct {:?}", table.nodes().len(), table.error(), table.direct_intervals());
+    assert!(table.error() <= tolerance);
+    assert_eq!((table.nodes()[0], *table.nodes().last().unwrap()), (0.5, 8.0));
+    assert!(!table.is_interpolated(4.0));
+    let direct = table.direct_intervals();
+    assert!(direct.iter().all(|&(a, b)| (a - 4.0).abs() < 1e-6 && (b - 4.0).abs() < 1e-6));
+
+    let mut worst: f64 = 0.0;
+    for p in samples(0.5, 8.0, 10_000) {
+        let (value, exact) = (table.evaluate(p), two_point(p, 1.0, 1.0));
+        if table.is_interpolated(p) {
+            worst = worst.max(relative_error(value, exact));
+        } else {
+            assert_eq!(value, exact);
+        }
+    }
+    println!("largest error {:e}", worst);
+    assert!(worst <= 10.0 * tolerance);
+
+    // Outside the range, directly
+    assert!(!table.is_interpolated(9.0));
+    assert_eq!(table.evaluate(9.0), two_point(9.0, 1.0, 1.0));
+}
+
+#[test]
+fn test_table_logarithmic() {
+    // B0(p, 0.1, 0.3) over six decades of space-like p, and of m2
+    let tolerance = 1e-10;
+    let range = Range::logarithmic(Variable::Momentum(0), -1e-3, -1e3, 7);
+    let table = Table::new(&bubble(0.0, 0.1, 0.3), range, tolerance);
+    println!("{} nodes, error {:e}", table.nodes().len(), table.error());
+    assert!(table.direct_intervals().is_empty());
+    assert!(table.nodes().len() < 1000);
+    for p in samples(-3.0, 3.0, 1000).map(|e| -(10f64).powf(e)) {
+        assert!(relative_error(table.evaluate(p), two_point(p, 0.1, 0.3)) <= 10.0 * tolerance);
+    }
+
+    let m = Complex64::new(0.0, -0.1);
+    let point = Point { masses: vec![m, m], ..bubble(2.0, 0.0, 0.0) };
+    let table = Table::new(&point, Range::logarithmic(Variable::Mass(1), 1e3, 1e-3, 2), tolerance);
+    assert!(table.direct_intervals().is_empty());
+    // Decreasing bounds give increasing nodes
+    assert_eq!((table.nodes()[0], *table.nodes().last().unwrap()), (1e-3, 1e3));
+    for m2 in samples(-3.0, 3.0, 1000).map(|e| (10f64).powf(e)) {
+        let exact = two_point(2.0, m, Complex64::new(m2, -0.1));
+        assert!(relative_error(table.evaluate(m2), exact) <= 10.0 * tolerance);
+    }
+}
+
+#[test]
+fn test_grid() {
+    // A space-like triangle is smooth everywhere
+    let tolerance = 1e-8;
+    let point = Point {
+        legs: 3,
+        mu: 1.0,
+        momenta: vec![0.0, 0.0, -2.0],
+        masses: vec![Complex64::new(1.0, 0.0); 3],
+        line: 0,
+    };
+    let x = Range::linear(Variable::Momentum(0), -5.0, -1.0, 5);
+    let y = Range::logarithmic(Variable::Momentum(1), -0.1, -10.0, 5);
+    let grid = Grid::new(&point, x, y, tolerance);
+    println!("{:?} cells, {} evaluations, error {:e}", grid.cells(), grid.evaluations(), grid.error());
+    assert_eq!(grid.cells().1, 0);
+    for (p1, e) in samples(-5.0, -1.0, 2000).zip(samples(-1.0, 1.0, 2000).skip(7)) {
+        let p2 = -(10f64).powf(e);
+        let exact = three_point(p1, p2, -2.0, 1.0, 1.0, 1.0);
+        assert!(relative_error(grid.evaluate(p1, p2), exact) <= 10.0 * tolerance);
+    }
+}
+
+#[test]
+fn test_grid_threshold() {
+    // B0(p, 1, m2) has a threshold along the curve p = (1 + √m2)²
+    let tolerance = 1e-6;
+    let x = Range::linear(Variable::Momentum(0), 0.5, 8.0, 9);
+    let y = Range::linear(Variable::Mass(1), 0.5, 2.0, 5);
+    let grid = Grid::new(&bubble(0.0, 1.0, 0.0), x, y, tolerance);
+    println!("{:?} cells, {} evaluations, error {:e}", grid.cells(), grid.evaluations(), grid.error());
+    assert!(grid.cells().1 > 0);
+    assert!(grid.error() <= tolerance);
+    for m2 in [0.5, 1.0, 1.7, 2.0] {
+        assert!(!grid.is_interpolated((1.0 + f64::sqrt(m2)).powi(2), m2));
+    }
+    let mut worst: f64 = 0.0;
+    for (p, m2) in samples(0.5, 8.0, 5000).zip(samples(0.5, 2.0, 5000).skip(11)) {
+        let (value, exact) = (grid.evaluate(p, m2), two_point(p, 1.0, m2));
+        if grid.is_interpolated(p, m2) {
+            worst = worst.max(relative_error(value, exact));
+        } else {
+            assert_eq!(value, exact);
+        }
+    }
+    println!("largest error {:e}", worst);
+    assert!(worst <= 10.0 * tolerance);
+}